@@ -0,0 +1,132 @@
+use crate::domain::config_models::AppConfig;
+use crate::domain::error::AppError;
+use crate::dto::account_history_dto::{
+    AccountBalanceHistoryDto, LedgerEntryDto, OpeningDateWarningDto,
+};
+use crate::dto::net_worth_dto::NetWorthDto;
+use crate::dto::pagination_dto::Pagination;
+use crate::helpers::datetime_helpers::{
+    end_of_day_timestamp, timestamp_now, timestamp_to_local_date,
+};
+use crate::helpers::json_helpers::{load_json, save_json};
+use crate::services::account_history::{self, BalanceInterval};
+use crate::services::accounts::get_account_balance_at;
+use crate::services::net_worth;
+use crate::AppState;
+
+#[tauri::command]
+pub async fn get_account_balance_history(
+    state: tauri::State<'_, AppState>,
+    account_ids: Vec<String>,
+    from: i64,
+    to: i64,
+    interval: Option<BalanceInterval>,
+) -> Result<Vec<AccountBalanceHistoryDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    account_history::get_account_balance_history(
+        conn,
+        &account_ids,
+        from,
+        to,
+        interval.unwrap_or(BalanceInterval::Day),
+    )
+}
+
+// Saldo de la cuenta al final del día indicado
+#[tauri::command]
+pub async fn get_account_balance_on(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+    date: i64,
+) -> Result<f64, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let until = end_of_day_timestamp(timestamp_to_local_date(date));
+    get_account_balance_at(conn, &account_id, Some(until))
+}
+
+#[tauri::command]
+pub async fn get_account_ledger(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+    page: i16,
+    size: i16,
+) -> Result<Pagination<LedgerEntryDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    account_history::get_account_ledger(conn, &account_id, page, size)
+}
+
+#[tauri::command]
+pub async fn get_net_worth(state: tauri::State<'_, AppState>) -> Result<NetWorthDto, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let app_config: AppConfig = load_json(&workspace_path.join(".finance").join("app.json"))?;
+
+    net_worth::get_net_worth(conn, &app_config.currency, &app_config.exchange_rates)
+}
+
+// Tasa de cambio hacia la moneda del workspace (None la elimina)
+#[tauri::command]
+pub async fn set_exchange_rate(
+    state: tauri::State<'_, AppState>,
+    currency: String,
+    rate: Option<f64>,
+) -> Result<(), AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let app_path = workspace_path.join(".finance").join("app.json");
+    let mut app_config: AppConfig = load_json(&app_path)?;
+    let currency = currency.trim().to_ascii_uppercase();
+
+    match rate {
+        Some(rate) if rate > 0.0 => {
+            app_config.exchange_rates.insert(currency, rate);
+        }
+        Some(_) => {
+            return Err(AppError::ConfigError(
+                "La tasa de cambio debe ser mayor que cero".into(),
+            ))
+        }
+        None => {
+            app_config.exchange_rates.remove(&currency);
+        }
+    }
+    app_config.updated_at = timestamp_now();
+
+    save_json(app_path, &app_config)
+}
+
+// Cuentas con registros fechados antes de su apertura
+#[tauri::command]
+pub async fn get_opening_date_warnings(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<OpeningDateWarningDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    account_history::get_opening_date_warnings(conn)
+}
@@ -0,0 +1,221 @@
+use crate::domain::config_models::BudgetItem;
+use crate::domain::error::AppError;
+use crate::dto::budget_dto::{BudgetAlertDto, BudgetStatusDto};
+use crate::fs::budget_file_management::{load_budgets, remove_budget, save_budgets, upsert_budget};
+use crate::helpers::datetime_helpers::timestamp_now;
+use crate::services::budget_alerts::{
+    check_budget_alerts as run_budget_alerts, check_budget_alerts_quietly,
+};
+use crate::services::budgets::{
+    copy_month_budgets, current_month, format_month, get_budget_status as build_budget_status,
+    parse_month, validate_budget,
+};
+use crate::AppState;
+
+#[tauri::command]
+pub async fn get_budgets(
+    state: tauri::State<'_, AppState>,
+    month: String,
+) -> Result<Vec<BudgetItem>, AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let (year, month_number) = parse_month(&month)?;
+    let month = format_month(year, month_number);
+
+    Ok(load_budgets(workspace_path)?
+        .budgets
+        .into_iter()
+        .filter(|b| b.month == month)
+        .collect())
+}
+
+// Crea o reemplaza el presupuesto de la categoría en el mes
+#[tauri::command]
+pub async fn set_budget(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    month: String,
+    category_id: String,
+    amount: f64,
+    notes: Option<String>,
+) -> Result<String, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Validar mes, categoría y monto
+    let (year, month_number) = parse_month(&month)?;
+    let month = format_month(year, month_number);
+    validate_budget(conn, &category_id, amount)?;
+
+    // 2. Conservar id y fecha de creación si ya existía
+    let existing = load_budgets(workspace_path)?
+        .budgets
+        .into_iter()
+        .find(|b| b.month == month && b.category_id == category_id);
+    let now = timestamp_now();
+
+    let budget = BudgetItem {
+        id: existing
+            .as_ref()
+            .map(|b| b.id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        month,
+        category_id,
+        amount,
+        notes: notes.filter(|n| !n.trim().is_empty()),
+        created_at: existing.map(|b| b.created_at).unwrap_or(now),
+        updated_at: now,
+    };
+    let budget_id = budget.id.clone();
+    let month = budget.month.clone();
+
+    upsert_budget(workspace_path, budget)?;
+
+    // 3. Un presupuesto más bajo puede dejar el mes por encima de un umbral
+    let app_data_dir = state.workspace_app_data_dir.lock().unwrap().clone();
+    check_budget_alerts_quietly(
+        &app,
+        conn,
+        workspace_path,
+        app_data_dir.as_deref(),
+        &[month],
+    );
+
+    Ok(budget_id)
+}
+
+#[tauri::command]
+pub async fn delete_budget(
+    state: tauri::State<'_, AppState>,
+    month: String,
+    category_id: String,
+) -> Result<(), AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let (year, month_number) = parse_month(&month)?;
+    remove_budget(
+        workspace_path,
+        &format_month(year, month_number),
+        &category_id,
+    )
+}
+
+// Copia los presupuestos de un mes a otro; sin overwrite se conservan los
+// que ya existan en el destino. Devuelve cuántos se copiaron.
+#[tauri::command]
+pub async fn copy_budgets(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    from_month: String,
+    to_month: String,
+    overwrite: Option<bool>,
+) -> Result<usize, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let (year, month_number) = parse_month(&from_month)?;
+    let from_month = format_month(year, month_number);
+    let (year, month_number) = parse_month(&to_month)?;
+    let to_month = format_month(year, month_number);
+
+    if from_month == to_month {
+        return Err(AppError::ConfigError(
+            "El mes de destino debe ser distinto del de origen".into(),
+        ));
+    }
+
+    let overwrite = overwrite.unwrap_or(false);
+    let mut budgets_config = load_budgets(workspace_path)?;
+    let copied = copy_month_budgets(&mut budgets_config, &from_month, &to_month, overwrite);
+
+    if copied > 0 {
+        save_budgets(workspace_path, &budgets_config)?;
+
+        // Los gastos ya registrados en el mes destino pueden cruzar umbrales
+        let app_data_dir = state.workspace_app_data_dir.lock().unwrap().clone();
+        check_budget_alerts_quietly(
+            &app,
+            conn,
+            workspace_path,
+            app_data_dir.as_deref(),
+            &[to_month],
+        );
+    }
+
+    Ok(copied)
+}
+
+// Estado de los presupuestos del mes (por defecto el actual)
+#[tauri::command]
+pub async fn get_budget_status(
+    state: tauri::State<'_, AppState>,
+    month: Option<String>,
+) -> Result<Vec<BudgetStatusDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let month = month.unwrap_or_else(current_month);
+
+    build_budget_status(conn, &load_budgets(workspace_path)?, &month)
+}
+
+// Revisa los umbrales del mes (por defecto el actual) y devuelve las alertas
+// nuevas; también se emiten como evento "budget-alert"
+#[tauri::command]
+pub async fn check_budget_alerts(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    month: Option<String>,
+) -> Result<Vec<BudgetAlertDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let app_data_guard = state.workspace_app_data_dir.lock().unwrap();
+    let app_data_dir = app_data_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un directorio de datos del workspace en el estado".into(),
+    ))?;
+
+    let (year, month_number) = parse_month(&month.unwrap_or_else(current_month))?;
+
+    run_budget_alerts(
+        &app,
+        conn,
+        workspace_path,
+        app_data_dir,
+        &[format_month(year, month_number)],
+    )
+}
@@ -0,0 +1,409 @@
+use crate::domain::config_models::{AccountsConfig, CategoryItem};
+use crate::domain::error::AppError;
+use crate::dto::category_dto::{CategoryTreeDto, SaveCategoryDto};
+use crate::dto::category_merge_dto::CategoryMergeReportDto;
+use crate::fs::budget_file_management::load_budgets;
+use crate::fs::category_file_management::{load_categories, remove_category, upsert_category};
+use crate::fs::record_file_management::load_all_records;
+use crate::fs::rules_file_management::load_rules;
+use crate::helpers::datetime_helpers::timestamp_now;
+use crate::helpers::json_helpers::{apply_file_changes, load_json, restore_files, to_json};
+use crate::services::budget_alerts::{check_budget_alerts_quietly, month_of};
+use crate::services::categories::{
+    build_category_tree, count_category_budgets, count_category_rules, count_category_usage,
+    count_subcategories, delete_category_in_database, get_categories, merge_categories_in_database,
+    upsert_category_in_database, validate_category,
+};
+use crate::services::envelopes::reassign_envelope_category;
+use crate::AppState;
+use std::path::{Path, PathBuf};
+
+// Busca la categoría en categories.json (la fuente de verdad)
+fn find_category(workspace_path: &Path, category_id: &str) -> Result<CategoryItem, AppError> {
+    load_categories(workspace_path)?
+        .categories
+        .into_iter()
+        .find(|c| c.id == category_id)
+        .ok_or_else(|| AppError::NotFound(format!("Categoría {} no encontrada", category_id)))
+}
+
+#[tauri::command]
+pub async fn get_category_tree(
+    state: tauri::State<'_, AppState>,
+    include_inactive: Option<bool>,
+) -> Result<Vec<CategoryTreeDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let categories = get_categories(conn, include_inactive.unwrap_or(false))?;
+
+    Ok(build_category_tree(categories))
+}
+
+#[tauri::command]
+pub async fn create_category(
+    state: tauri::State<'_, AppState>,
+    category: SaveCategoryDto,
+) -> Result<String, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Validar contra las categorías existentes
+    validate_category(&load_categories(workspace_path)?, None, &category)?;
+
+    // 2. Guardar en categories.json y luego en la base
+    let item = CategoryItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: category.name.trim().to_string(),
+        r#type: category.r#type,
+        icon: category.icon,
+        color: category.color,
+        created_by_user: true,
+        is_active: true,
+        created_at: timestamp_now(),
+        parent_id: category.parent_id,
+    };
+    let category_id = item.id.clone();
+
+    upsert_category(workspace_path, item.clone())?;
+    upsert_category_in_database(conn, &item)?;
+
+    Ok(category_id)
+}
+
+#[tauri::command]
+pub async fn update_category(
+    state: tauri::State<'_, AppState>,
+    category_id: String,
+    category: SaveCategoryDto,
+) -> Result<(), AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Validar; el tipo solo puede cambiar si nadie usa la categoría
+    let mut item = find_category(workspace_path, &category_id)?;
+    validate_category(
+        &load_categories(workspace_path)?,
+        Some(&category_id),
+        &category,
+    )?;
+
+    if item.r#type != category.r#type && count_category_usage(conn, &category_id)? > 0 {
+        return Err(AppError::ConfigError(
+            "No se puede cambiar el tipo de una categoría que ya tiene registros".into(),
+        ));
+    }
+
+    // 2. Aplicar los cambios: primero categories.json (la fuente de verdad), luego la base
+    item.name = category.name.trim().to_string();
+    item.r#type = category.r#type;
+    item.icon = category.icon;
+    item.color = category.color;
+    item.parent_id = category.parent_id;
+
+    upsert_category(workspace_path, item.clone())?;
+    upsert_category_in_database(conn, &item)
+}
+
+// Deshabilitar (false) o habilitar (true); los registros conservan la categoría
+fn set_category_active(
+    state: tauri::State<'_, AppState>,
+    category_id: &str,
+    is_active: bool,
+) -> Result<(), AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let mut item = find_category(workspace_path, category_id)?;
+    item.is_active = is_active;
+
+    upsert_category(workspace_path, item.clone())?;
+    upsert_category_in_database(conn, &item)
+}
+
+#[tauri::command]
+pub async fn disable_category(
+    state: tauri::State<'_, AppState>,
+    category_id: String,
+) -> Result<(), AppError> {
+    set_category_active(state, &category_id, false)
+}
+
+#[tauri::command]
+pub async fn enable_category(
+    state: tauri::State<'_, AppState>,
+    category_id: String,
+) -> Result<(), AppError> {
+    set_category_active(state, &category_id, true)
+}
+
+#[tauri::command]
+pub async fn delete_category(
+    state: tauri::State<'_, AppState>,
+    category_id: String,
+) -> Result<(), AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Las categorías predeterminadas solo se pueden deshabilitar
+    let item = find_category(workspace_path, &category_id)?;
+    if !item.created_by_user {
+        return Err(AppError::ConfigError(format!(
+            "La categoría '{}' es predeterminada; puede deshabilitarla pero no eliminarla",
+            item.name
+        )));
+    }
+
+    // 2. Solo se elimina si no tiene subcategorías y ningún registro, préstamo o regla la usa
+    let subcategories = count_subcategories(&load_categories(workspace_path)?, &category_id);
+    if subcategories > 0 {
+        return Err(AppError::ConfigError(format!(
+            "La categoría '{}' tiene {} subcategorías; muévalas o elimínelas primero",
+            item.name, subcategories
+        )));
+    }
+
+    let usage = count_category_usage(conn, &category_id)?;
+    let rules = count_category_rules(&load_rules(workspace_path)?, &category_id);
+    let budgets = count_category_budgets(&load_budgets(workspace_path)?, &category_id);
+    if usage > 0 || rules > 0 || budgets > 0 {
+        return Err(AppError::ConfigError(format!(
+            "La categoría '{}' está en uso ({} registros o préstamos, {} reglas, {} presupuestos); deshabilítela en su lugar",
+            item.name, usage, rules, budgets
+        )));
+    }
+
+    // 3. Eliminar de categories.json y luego de la base
+    remove_category(workspace_path, &category_id)?;
+    delete_category_in_database(conn, &category_id)
+}
+
+#[tauri::command]
+pub async fn merge_categories(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    source_id: String,
+    target_id: String,
+    remove_merged: Option<bool>,
+) -> Result<CategoryMergeReportDto, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Validar ambas categorías
+    if source_id == target_id {
+        return Err(AppError::ConfigError(
+            "La categoría destino debe ser distinta de la que se fusiona".into(),
+        ));
+    }
+    let mut categories = load_categories(workspace_path)?;
+    let source = find_category(workspace_path, &source_id)?;
+    let target = find_category(workspace_path, &target_id)?;
+    if source.r#type != target.r#type {
+        return Err(AppError::ConfigError(
+            "Solo se pueden fusionar categorías del mismo tipo".into(),
+        ));
+    }
+    if !target.is_active {
+        return Err(AppError::ConfigError(format!(
+            "La categoría destino '{}' está deshabilitada; habilítela antes de fusionar",
+            target.name
+        )));
+    }
+
+    // Si la destino estaba dentro de la fusionada, sube a la posición de esta
+    // para que las subcategorías movidas no formen un ciclo
+    let mut target_parent_id = target.parent_id.clone();
+    let mut ancestor = target.parent_id.clone();
+    let mut depth = 0;
+    while let Some(id) = ancestor.filter(|_| depth <= categories.categories.len()) {
+        if id == source_id {
+            target_parent_id = source.parent_id.clone();
+            break;
+        }
+        ancestor = categories
+            .categories
+            .iter()
+            .find(|c| c.id == id)
+            .and_then(|c| c.parent_id.clone());
+        depth += 1;
+    }
+
+    // Las categorías predeterminadas no se eliminan, solo se deshabilitan
+    let remove = remove_merged.unwrap_or(false) && source.created_by_user;
+
+    // 2. Preparar en memoria los registros afectados
+    let mut moved_records = Vec::new();
+    for (path, mut record) in load_all_records(workspace_path)? {
+        if record.category_id.as_deref() == Some(source_id.as_str()) {
+            record.category_id = Some(target_id.clone());
+            moved_records.push((path, record));
+        }
+    }
+    let records_moved = moved_records.len();
+    let mut months: Vec<String> = moved_records
+        .iter()
+        .map(|(_, record)| month_of(record.timestamp))
+        .collect();
+
+    // 3. Reglas que asignaban la categoría fusionada
+    let mut rules = load_rules(workspace_path)?;
+    let mut rules_updated = 0;
+    for rule in rules
+        .rules
+        .iter_mut()
+        .filter(|r| r.actions.category_id.as_deref() == Some(source_id.as_str()))
+    {
+        rule.actions.category_id = Some(target_id.clone());
+        rules_updated += 1;
+    }
+
+    // 4. Presupuestos y sobres: si la destino ya tiene uno en el mismo mes se suman
+    let mut budgets = load_budgets(workspace_path)?;
+    let mut budgets_updated = 0;
+    let source_budgets: Vec<_> = budgets
+        .budgets
+        .iter()
+        .filter(|b| b.category_id == source_id)
+        .cloned()
+        .collect();
+    for source_budget in source_budgets {
+        months.push(source_budget.month.clone());
+        let target_budget = budgets
+            .budgets
+            .iter_mut()
+            .find(|b| b.category_id == target_id && b.month == source_budget.month);
+
+        match target_budget {
+            Some(target_budget) => {
+                target_budget.amount += source_budget.amount;
+                target_budget.notes = target_budget.notes.take().or(source_budget.notes);
+                target_budget.updated_at = timestamp_now();
+                budgets.budgets.retain(|b| b.id != source_budget.id);
+            }
+            None => {
+                if let Some(budget) = budgets
+                    .budgets
+                    .iter_mut()
+                    .find(|b| b.id == source_budget.id)
+                {
+                    budget.category_id = target_id.clone();
+                    budget.updated_at = timestamp_now();
+                }
+            }
+        }
+        budgets_updated += 1;
+    }
+    if let Some(envelopes) = budgets.envelopes.as_mut() {
+        budgets_updated +=
+            reassign_envelope_category(envelopes, &source_id, &target_id, timestamp_now());
+    }
+
+    // 5. Categoría de intereses de los préstamos
+    let accounts_path = workspace_path.join(".finance").join("accounts.json");
+    let mut accounts: AccountsConfig = load_json(&accounts_path)?;
+    let mut loans_updated = 0;
+    for loan in accounts.accounts.iter_mut().filter_map(|a| a.loan.as_mut()) {
+        if loan.interest_category_id.as_deref() == Some(source_id.as_str()) {
+            loan.interest_category_id = Some(target_id.clone());
+            loans_updated += 1;
+        }
+    }
+
+    let mut subcategories_moved = 0;
+    for category in categories.categories.iter_mut() {
+        if category.id == target_id {
+            category.parent_id = target_parent_id.clone();
+        } else if category.parent_id.as_deref() == Some(source_id.as_str()) {
+            category.parent_id = Some(target_id.clone());
+            subcategories_moved += 1;
+        }
+    }
+    if remove {
+        categories.categories.retain(|c| c.id != source_id);
+    } else if let Some(merged) = categories.categories.iter_mut().find(|c| c.id == source_id) {
+        merged.is_active = false;
+    }
+
+    // 6. Primero los archivos (se restauran todos si alguno falla) y luego
+    // SQLite en una transacción; si la base falla los archivos vuelven atrás
+    let finance_dir = workspace_path.join(".finance");
+    let mut changes: Vec<(PathBuf, Option<String>)> = Vec::new();
+    for (path, record) in moved_records {
+        changes.push((path, Some(to_json(&record)?)));
+    }
+    if rules_updated > 0 {
+        changes.push((finance_dir.join("rules.json"), Some(to_json(&rules)?)));
+    }
+    if budgets_updated > 0 {
+        changes.push((finance_dir.join("budgets.json"), Some(to_json(&budgets)?)));
+    }
+    if loans_updated > 0 {
+        changes.push((accounts_path, Some(to_json(&accounts)?)));
+    }
+    changes.push((
+        finance_dir.join("categories.json"),
+        Some(to_json(&categories)?),
+    ));
+
+    let backups = apply_file_changes(&changes)?;
+    if let Err(e) = merge_categories_in_database(
+        conn,
+        &source_id,
+        &target_id,
+        target_parent_id.as_deref(),
+        remove,
+    ) {
+        restore_files(&backups);
+        return Err(e);
+    }
+
+    // 7. Los gastos movidos pueden llevar la categoría destino sobre un umbral
+    let app_data_dir = state.workspace_app_data_dir.lock().unwrap().clone();
+    check_budget_alerts_quietly(&app, conn, workspace_path, app_data_dir.as_deref(), &months);
+
+    Ok(CategoryMergeReportDto {
+        records_moved,
+        rules_updated,
+        budgets_updated,
+        loans_updated,
+        subcategories_moved,
+        merged_removed: remove,
+    })
+}
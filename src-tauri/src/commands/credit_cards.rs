@@ -0,0 +1,136 @@
+use crate::domain::config_models::InstallmentPlan;
+use crate::domain::error::AppError;
+use crate::dto::create_record_dto::CreateRecordDto;
+use crate::dto::credit_card_dto::{CreditCardStatementDto, CreditCardSummaryDto};
+use crate::dto::installment_dto::{InstallmentDto, InstallmentPurchaseDto};
+use crate::fs::record_file_management::{build_record_item, save_record_item};
+use crate::helpers::datetime_helpers::timestamp_now;
+use crate::services::records::create_record_in_database;
+use crate::services::{credit_cards, installments};
+use crate::AppState;
+use std::fs;
+
+#[tauri::command]
+pub async fn get_credit_card_summary(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+) -> Result<CreditCardSummaryDto, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    credit_cards::get_credit_card_summary(conn, &account_id, timestamp_now())
+}
+
+#[tauri::command]
+pub async fn get_credit_card_statements(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+    cycles: Option<u32>,
+) -> Result<Vec<CreditCardStatementDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    credit_cards::get_credit_card_statements(
+        conn,
+        &account_id,
+        cycles.unwrap_or(6),
+        timestamp_now(),
+    )
+}
+
+#[tauri::command]
+pub async fn pay_credit_card(
+    state: tauri::State<'_, AppState>,
+    from_account_id: String,
+    card_account_id: String,
+    amount: f64,
+    timestamp: Option<i64>,
+    description: Option<String>,
+) -> Result<String, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    if amount <= 0.0 {
+        return Err(AppError::ConfigError(
+            "El monto del pago debe ser mayor a cero".into(),
+        ));
+    }
+
+    // Validamos que el destino sea realmente una tarjeta configurada y el origen
+    // una cuenta de activo en su moneda
+    credit_cards::get_credit_card_summary(conn, &card_account_id, timestamp_now())?;
+    credit_cards::validate_card_payment_source(conn, &from_account_id, &card_account_id)?;
+
+    // El pago es una transferencia desde la cuenta bancaria hacia la tarjeta
+    let payment = CreateRecordDto {
+        r#type: "transfer".into(),
+        amount,
+        account_id: from_account_id,
+        to_account_id: Some(card_account_id),
+        category_id: None,
+        description: Some(description.unwrap_or_else(|| "Pago tarjeta de crédito".into())),
+        timestamp: timestamp.unwrap_or_else(timestamp_now),
+        apply_rules: None,
+        installments: None,
+        monthly_interest_rate: None,
+    };
+
+    let record_id = uuid::Uuid::new_v4().to_string();
+    let record_item = build_record_item(&payment, &record_id);
+
+    let record_file_path = save_record_item(workspace_path, &record_item)
+        .map_err(|e| AppError::IoError(format!("Error al crear archivo de registro: {}", e)))?;
+
+    // Sin índice no queda el archivo: se borra si falla la base
+    if let Err(e) =
+        create_record_in_database(conn, &record_item, &record_file_path.to_string_lossy())
+    {
+        let _ = fs::remove_file(&record_file_path);
+        return Err(e);
+    }
+
+    Ok(record_id)
+}
+
+#[tauri::command]
+pub async fn preview_installment_schedule(
+    amount: f64,
+    installments: u32,
+    monthly_interest_rate: f64,
+    timestamp: i64,
+) -> Result<Vec<InstallmentDto>, AppError> {
+    installments::validate_installment_plan(installments, monthly_interest_rate)?;
+
+    Ok(installments::build_schedule(
+        amount,
+        &InstallmentPlan {
+            count: installments,
+            monthly_interest_rate,
+        },
+        timestamp,
+    ))
+}
+
+#[tauri::command]
+pub async fn get_outstanding_installments(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+) -> Result<Vec<InstallmentPurchaseDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    installments::get_outstanding_installments(conn, &account_id, timestamp_now())
+}
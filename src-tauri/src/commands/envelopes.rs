@@ -0,0 +1,204 @@
+use crate::domain::config_models::{
+    EnvelopeAssignmentItem, EnvelopeBudgetConfig, EnvelopeMoveItem,
+};
+use crate::domain::error::AppError;
+use crate::dto::envelope_dto::EnvelopeMonthDto;
+use crate::fs::budget_file_management::{load_budgets, save_budgets};
+use crate::helpers::datetime_helpers::timestamp_now;
+use crate::services::budgets::{current_month, format_month, parse_month, validate_budget};
+use crate::services::envelopes::{
+    active_envelopes, active_envelopes_mut, get_envelope_month as build_envelope_month,
+    validate_envelope_month,
+};
+use crate::AppState;
+
+// Habilita o deshabilita el presupuesto por sobres. Las asignaciones se
+// conservan al deshabilitarlo; start_month solo cambia si se indica.
+#[tauri::command]
+pub async fn set_envelope_budgeting(
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+    start_month: Option<String>,
+) -> Result<(), AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let start_month = match start_month {
+        Some(month) => {
+            let (year, month_number) = parse_month(&month)?;
+            Some(format_month(year, month_number))
+        }
+        None => None,
+    };
+
+    let mut budgets = load_budgets(workspace_path)?;
+    match budgets.envelopes.as_mut() {
+        Some(envelopes) => {
+            envelopes.enabled = enabled;
+            if let Some(start_month) = start_month {
+                envelopes.start_month = start_month;
+            }
+        }
+        None => {
+            budgets.envelopes = Some(EnvelopeBudgetConfig {
+                enabled,
+                start_month: start_month.unwrap_or_else(current_month),
+                assignments: Vec::new(),
+                moves: Vec::new(),
+            })
+        }
+    }
+
+    save_budgets(workspace_path, &budgets)
+}
+
+// Fija lo asignado al sobre en el mes (reemplaza la asignación anterior)
+#[tauri::command]
+pub async fn assign_to_envelope(
+    state: tauri::State<'_, AppState>,
+    month: String,
+    category_id: String,
+    amount: f64,
+) -> Result<(), AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Validar mes, sobre y monto
+    let mut budgets = load_budgets(workspace_path)?;
+    let month = validate_envelope_month(active_envelopes(&budgets)?, &month)?;
+    validate_budget(conn, &category_id, amount)?;
+
+    // 2. Reemplazar o agregar la asignación
+    let envelopes = active_envelopes_mut(&mut budgets)?;
+    match envelopes
+        .assignments
+        .iter_mut()
+        .find(|a| a.month == month && a.category_id == category_id)
+    {
+        Some(assignment) => {
+            assignment.amount = amount;
+            assignment.updated_at = timestamp_now();
+        }
+        None => envelopes.assignments.push(EnvelopeAssignmentItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            month,
+            category_id,
+            amount,
+            updated_at: timestamp_now(),
+        }),
+    }
+
+    save_budgets(workspace_path, &budgets)
+}
+
+// Traslada dinero entre sobres dentro del mes; devuelve el id del traslado
+#[tauri::command]
+pub async fn move_between_envelopes(
+    state: tauri::State<'_, AppState>,
+    month: String,
+    from_category_id: String,
+    to_category_id: String,
+    amount: f64,
+    notes: Option<String>,
+) -> Result<String, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Validar mes, sobres y monto
+    let mut budgets = load_budgets(workspace_path)?;
+    let month = validate_envelope_month(active_envelopes(&budgets)?, &month)?;
+    if from_category_id == to_category_id {
+        return Err(AppError::ConfigError(
+            "El sobre de destino debe ser distinto del de origen".into(),
+        ));
+    }
+    if amount <= 0.0 {
+        return Err(AppError::ConfigError(
+            "El monto a trasladar debe ser mayor que cero".into(),
+        ));
+    }
+    validate_budget(conn, &from_category_id, amount)?;
+    validate_budget(conn, &to_category_id, amount)?;
+
+    // 2. Guardar el traslado
+    let envelope_move = EnvelopeMoveItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        month,
+        from_category_id,
+        to_category_id,
+        amount,
+        notes: notes.filter(|n| !n.trim().is_empty()),
+        created_at: timestamp_now(),
+    };
+    let move_id = envelope_move.id.clone();
+
+    active_envelopes_mut(&mut budgets)?
+        .moves
+        .push(envelope_move);
+    save_budgets(workspace_path, &budgets)?;
+
+    Ok(move_id)
+}
+
+#[tauri::command]
+pub async fn delete_envelope_move(
+    state: tauri::State<'_, AppState>,
+    move_id: String,
+) -> Result<(), AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let mut budgets = load_budgets(workspace_path)?;
+    let envelopes = active_envelopes_mut(&mut budgets)?;
+    let before = envelopes.moves.len();
+    envelopes.moves.retain(|m| m.id != move_id);
+
+    if envelopes.moves.len() == before {
+        return Err(AppError::NotFound(format!(
+            "Traslado {} no encontrado",
+            move_id
+        )));
+    }
+
+    save_budgets(workspace_path, &budgets)
+}
+
+// Saldos de los sobres y dinero por asignar del mes (por defecto el actual)
+#[tauri::command]
+pub async fn get_envelope_month(
+    state: tauri::State<'_, AppState>,
+    month: Option<String>,
+) -> Result<EnvelopeMonthDto, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let budgets = load_budgets(workspace_path)?;
+    let month = month.unwrap_or_else(current_month);
+
+    build_envelope_month(conn, active_envelopes(&budgets)?, &month)
+}
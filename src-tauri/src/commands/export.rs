@@ -0,0 +1,54 @@
+use crate::domain::config_models::{AccountsConfig, AppConfig, CategoriesConfig, RecordItem};
+use crate::domain::error::AppError;
+use crate::dto::export_report_dto::ExportReportDto;
+use crate::fs::record_file_management::load_all_records;
+use crate::helpers::json_helpers::load_json;
+use crate::services::plain_text_export::{build_journal, PlainTextFormat};
+use crate::AppState;
+use std::fs;
+use std::path::PathBuf;
+
+#[tauri::command]
+pub async fn export_plain_text_accounting(
+    state: tauri::State<'_, AppState>,
+    format: PlainTextFormat,
+    output_path: String,
+) -> Result<ExportReportDto, AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Leer la fuente de verdad (los JSON del workspace)
+    let finance_dir = workspace_path.join(".finance");
+    let app_config: AppConfig = load_json(&finance_dir.join("app.json"))?;
+    let categories: CategoriesConfig = load_json(&finance_dir.join("categories.json"))?;
+    let accounts: AccountsConfig = load_json(&finance_dir.join("accounts.json"))?;
+    let records: Vec<RecordItem> = load_all_records(workspace_path)?
+        .into_iter()
+        .map(|(_, record)| record)
+        .collect();
+
+    // 2. Generar el journal (los registros sin tasa de cambio se reportan)
+    let (journal, skipped) = build_journal(
+        &app_config,
+        &accounts.accounts,
+        &categories.categories,
+        &records,
+        format,
+    );
+
+    // 3. Si nos pasan una carpeta, generamos el nombre del archivo
+    let mut target = PathBuf::from(output_path);
+    if target.is_dir() {
+        target = target.join(format!("finance.{}", format.extension()));
+    }
+
+    fs::write(&target, journal)
+        .map_err(|e| AppError::IoError(format!("Error escribiendo en {:?}: {}", target, e)))?;
+
+    Ok(ExportReportDto {
+        path: target.to_string_lossy().to_string(),
+        skipped,
+    })
+}
@@ -0,0 +1,107 @@
+use crate::domain::config_models::GoalItem;
+use crate::domain::error::AppError;
+use crate::dto::goal_dto::GoalProgressDto;
+use crate::fs::goal_file_management::{load_goals, save_goals};
+use crate::helpers::datetime_helpers::timestamp_now;
+use crate::services::goals::{get_goals_progress, index_goals, validate_goal};
+use crate::AppState;
+
+// Metas con sus aportes y progreso; por defecto solo las activas
+#[tauri::command]
+pub async fn get_goals(
+    state: tauri::State<'_, AppState>,
+    include_inactive: Option<bool>,
+) -> Result<Vec<GoalProgressDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    get_goals_progress(conn, include_inactive.unwrap_or(false))
+}
+
+// Crea la meta (id vacío) o actualiza la existente; devuelve su id
+#[tauri::command]
+pub async fn save_goal(
+    state: tauri::State<'_, AppState>,
+    mut goal: GoalItem,
+) -> Result<String, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Normalizar y validar
+    goal.name = goal.name.trim().to_string();
+    goal.account_id = goal.account_id.filter(|a| !a.trim().is_empty());
+    goal.tag = goal
+        .tag
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    goal.notes = goal.notes.filter(|n| !n.trim().is_empty());
+    validate_goal(conn, &goal)?;
+
+    // 2. Meta nueva: generamos id y fecha; una existente conserva su fecha de creación
+    let mut config = load_goals(workspace_path)?;
+    let is_new = goal.id.is_empty();
+    if is_new {
+        goal.id = uuid::Uuid::new_v4().to_string();
+        goal.created_at = timestamp_now();
+    }
+    let goal_id = goal.id.clone();
+
+    // 3. Guardar y reindexar
+    match config.goals.iter_mut().find(|g| g.id == goal.id) {
+        Some(existing) => {
+            goal.created_at = existing.created_at;
+            *existing = goal;
+        }
+        None if is_new => config.goals.push(goal),
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Meta {} no encontrada",
+                goal_id
+            )))
+        }
+    }
+
+    save_goals(workspace_path, &config)?;
+    index_goals(conn, &config)?;
+
+    Ok(goal_id)
+}
+
+#[tauri::command]
+pub async fn delete_goal(
+    state: tauri::State<'_, AppState>,
+    goal_id: String,
+) -> Result<(), AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let mut config = load_goals(workspace_path)?;
+    let before = config.goals.len();
+    config.goals.retain(|g| g.id != goal_id);
+
+    if config.goals.len() == before {
+        return Err(AppError::NotFound(format!(
+            "Meta {} no encontrada",
+            goal_id
+        )));
+    }
+
+    save_goals(workspace_path, &config)?;
+    index_goals(conn, &config)
+}
@@ -1,20 +1,44 @@
+use crate::domain::config_models::{AccountsConfig, AppConfig, RecordItem};
+use crate::domain::presets::normalize_currency;
 use crate::dto::account_info_dto::AccountInfoDto;
+use crate::dto::account_reassign_dto::AccountReassignReportDto;
+use crate::dto::category_breakdown_dto::{CategoryBreakdownDto, CategoryBreakdownQueryDto};
 use crate::dto::category_dto::CategoryDto;
 use crate::dto::create_record_dto::CreateRecordDto;
 use crate::dto::pagination_dto::Pagination;
 use crate::dto::record_dto::RecordDto;
 use crate::fs::account_file_management::{
-    add_account_to_list, remove_account_from_list, update_account_in_json,
+    add_account_to_list, remove_account_from_list, set_account_active_in_json,
+    update_account_in_json,
 };
-use crate::fs::record_file_management::create_record_file;
+use crate::fs::category_file_management::load_categories;
+use crate::fs::goal_file_management::load_goals;
+use crate::fs::investment_file_management::load_investments;
+use crate::fs::reconciliation_file_management::load_reconciliations;
+use crate::fs::record_file_management::{build_record_item, load_all_records, save_record_item};
+use crate::fs::rules_file_management::load_rules;
+use crate::helpers::datetime_helpers::{start_of_day_timestamp, timestamp_to_local_date};
+use crate::helpers::json_helpers::{apply_file_changes, load_json, restore_files, to_json};
 use crate::services::accounts::{
-    create_account_in_database, delete_account_if_no_records, update_account_in_database,
+    create_account_in_database, delete_account_if_no_records,
+    reassign_and_delete_account_in_database, set_account_active_in_database,
+    update_account_in_database, validate_account_class_override,
 };
+use crate::services::budget_alerts::{check_budget_alerts_quietly, month_of};
+use crate::services::budgets::current_month;
 use crate::services::categories::get_categories as fetch_categories;
+use crate::services::installments::validate_installment_plan;
+use crate::services::loans::{load_loan_settings, split_loan_payment, validate_loan_settings};
 use crate::services::records::{create_record_in_database, get_records};
+use crate::services::rules::{apply_rules, compile_rules};
 use crate::services::{accounts, stats};
 use crate::AppState;
-use crate::{domain::error::AppError, services::stats::DashboardStats};
+use crate::{
+    domain::error::AppError,
+    services::stats::{CategoryPercentage, DashboardStats},
+};
+use std::fs;
+use std::path::PathBuf;
 
 #[tauri::command]
 pub async fn get_overall_stats(
@@ -29,15 +53,87 @@ pub async fn get_overall_stats(
         AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
     })?;
 
+    // 3. El balance total se expresa en la moneda del workspace
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+    let app_config: AppConfig = load_json(&workspace_path.join(".finance").join("app.json"))?;
+
     let overall_stats =
-        stats::calculate_overall_stats(conn).map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        stats::calculate_overall_stats(conn, &app_config.currency, &app_config.exchange_rates)?;
 
     Ok(overall_stats)
 }
 
+#[tauri::command]
+pub async fn get_expenses_by_category(
+    state: tauri::State<'_, AppState>,
+    days: i64,
+    parent_id: Option<String>,
+) -> Result<Vec<CategoryPercentage>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    stats::get_expenses_by_category(conn, days, parent_id.as_deref())
+}
+
+// Desglose por categoría de gastos o ingresos en un periodo con nombre o
+// entre dos fechas, con filtros de cuentas y moneda
+#[tauri::command]
+pub async fn get_category_breakdown(
+    state: tauri::State<'_, AppState>,
+    query: CategoryBreakdownQueryDto,
+) -> Result<CategoryBreakdownDto, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Resolver el periodo (por defecto el mes actual)
+    let (start, end) = match (&query.period, query.start, query.end) {
+        (Some(period), _, _) => {
+            let app_config: AppConfig =
+                load_json(&workspace_path.join(".finance").join("app.json"))?;
+            stats::resolve_period(period, &app_config.week_start)?
+        }
+        (None, Some(start), Some(end)) => (start, end),
+        (None, None, None) => stats::resolve_period("this_month", "monday")?,
+        _ => {
+            return Err(AppError::ConfigError(
+                "Indique un periodo o ambas fechas (inicio y fin)".into(),
+            ))
+        }
+    };
+
+    // 2. Filtros opcionales
+    let currency = match query.currency.as_deref() {
+        Some(currency) => Some(normalize_currency(currency)?),
+        None => None,
+    };
+
+    stats::get_category_breakdown(
+        conn,
+        &query.r#type,
+        start,
+        end,
+        query.account_ids.as_deref(),
+        currency.as_deref(),
+        query.parent_id.as_deref(),
+    )
+}
+
 #[tauri::command]
 pub async fn get_accounts(
     state: tauri::State<'_, AppState>,
+    include_archived: Option<bool>,
 ) -> Result<Vec<AccountInfoDto>, AppError> {
     // 1. Bloqueamos el Mutex para obtener el Guard
     let mut conn_guard = state.db.lock().unwrap();
@@ -47,17 +143,47 @@ pub async fn get_accounts(
     let conn = conn_guard.as_mut().ok_or_else(|| {
         AppError::DatabaseError("No hay una conexión a la base de datos activa.".into())
     })?;
-    let accounts = accounts::get_accounts_with_balance(conn)
+    // Por defecto las cuentas archivadas no aparecen en listados ni selectores
+    let filter = if include_archived.unwrap_or(false) {
+        None
+    } else {
+        Some(false)
+    };
+    let accounts = accounts::get_accounts_with_balance(conn, filter)
         .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
     Ok(accounts)
 }
 
+#[tauri::command]
+pub async fn get_archived_accounts(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<AccountInfoDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa.".into())
+    })?;
+
+    accounts::get_accounts_with_balance(conn, Some(true))
+}
+
 #[tauri::command]
 pub async fn create_account(
     state: tauri::State<'_, AppState>,
-    new_account: AccountInfoDto,
+    mut new_account: AccountInfoDto,
 ) -> Result<String, AppError> {
+    validate_account_class_override(new_account.account_class_override.as_deref())?;
+    validate_account_loan(&new_account)?;
+
+    normalize_opening_date(&mut new_account);
+
+    // Un préstamo nace debiendo el capital desembolsado
+    if let Some(loan) = &new_account.loan {
+        if new_account.initial_balance.is_none() {
+            new_account.initial_balance = Some(-loan.principal);
+        }
+    }
+
     // 1. Bloqueamos el Mutex para obtener el Guard
     let mut conn_guard = state.db.lock().unwrap();
 
@@ -88,9 +214,12 @@ pub async fn create_account(
 #[tauri::command]
 pub async fn update_account(
     state: tauri::State<'_, AppState>,
-    account: AccountInfoDto,
+    mut account: AccountInfoDto,
 ) -> Result<(), AppError> {
     println!("Received account to update: {:?}", account);
+    normalize_opening_date(&mut account);
+    validate_account_class_override(account.account_class_override.as_deref())?;
+    validate_account_loan(&account)?;
     let mut conn_guard = state.db.lock().unwrap();
     let conn = conn_guard.as_mut().ok_or_else(|| {
         AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
@@ -141,6 +270,272 @@ pub async fn delete_account(
     Ok(result)
 }
 
+#[tauri::command]
+pub async fn delete_account_and_reassign(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+    target_account_id: String,
+) -> Result<AccountReassignReportDto, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Validar ambas cuentas; los montos están en la moneda de la cuenta
+    if account_id == target_account_id {
+        return Err(AppError::ConfigError(
+            "La cuenta destino debe ser distinta de la que se elimina".into(),
+        ));
+    }
+    let account_currency = |id: &str| -> Result<String, AppError> {
+        conn.query_row("SELECT currency FROM accounts WHERE id = ?1", [id], |row| {
+            row.get(0)
+        })
+        .map_err(|_| AppError::NotFound(format!("Cuenta {} no encontrada", id)))
+    };
+    if account_currency(&account_id)? != account_currency(&target_account_id)? {
+        return Err(AppError::ConfigError(
+            "Las cuentas deben tener la misma moneda para mover los registros".into(),
+        ));
+    }
+
+    // 2. Referencias que no pueden pasar a cualquier cuenta
+    let has_loan_payments: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM loan_payments WHERE loan_account_id = ?1)",
+            [&account_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    if has_loan_payments && load_loan_settings(conn, &target_account_id)?.is_none() {
+        return Err(AppError::ConfigError(
+            "Los pagos del préstamo solo pueden pasar a otra cuenta con condiciones de préstamo"
+                .into(),
+        ));
+    }
+
+    let has_installments: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM record_installments i JOIN records r ON r.id = i.record_id WHERE r.account_id = ?1)",
+            [&account_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    let target_type: String = conn
+        .query_row(
+            "SELECT type FROM accounts WHERE id = ?1",
+            [&target_account_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    if has_installments && target_type != "credit" {
+        return Err(AppError::ConfigError(
+            "Las compras a cuotas solo pueden pasar a otra tarjeta de crédito".into(),
+        ));
+    }
+
+    let mut reconciliations = load_reconciliations(workspace_path)?;
+    if reconciliations
+        .sessions
+        .iter()
+        .any(|s| s.account_id == account_id && s.status == "open")
+    {
+        return Err(AppError::ConfigError(
+            "La cuenta tiene una conciliación abierta; finalícela antes de eliminarla".into(),
+        ));
+    }
+
+    // 3. Preparar en memoria todos los archivos afectados (None = borrar)
+    let finance_dir = workspace_path.join(".finance");
+    let mut changes: Vec<(PathBuf, Option<String>)> = Vec::new();
+
+    let mut records_moved = 0;
+    let mut removed_record_ids = Vec::new();
+    for (path, mut record) in load_all_records(workspace_path)? {
+        let is_source = record.account_id == account_id;
+        let is_target = record.to_account_id.as_deref() == Some(account_id.as_str());
+        // Los gastos de intereses apuntan al préstamo en su metadata
+        let loan_metadata = record
+            .metadata
+            .as_mut()
+            .and_then(|m| m.get_mut("loan_account_id"))
+            .filter(|v| v.as_str() == Some(account_id.as_str()));
+        let is_loan_interest = loan_metadata.is_some();
+        if let Some(value) = loan_metadata {
+            *value = serde_json::Value::String(target_account_id.clone());
+        }
+        if !is_source && !is_target && !is_loan_interest {
+            continue;
+        }
+
+        if is_source {
+            record.account_id = target_account_id.clone();
+        }
+        if is_target {
+            record.to_account_id = Some(target_account_id.clone());
+        }
+
+        if record.to_account_id.as_deref() == Some(record.account_id.as_str()) {
+            changes.push((path, None));
+            removed_record_ids.push(record.id);
+        } else {
+            changes.push((path, Some(to_json(&record)?)));
+            if is_source || is_target {
+                records_moved += 1;
+            }
+        }
+    }
+
+    let mut investments = load_investments(workspace_path)?;
+    let mut investment_transactions_moved = 0;
+    for transaction in investments
+        .transactions
+        .iter_mut()
+        .filter(|t| t.account_id == account_id)
+    {
+        transaction.account_id = target_account_id.clone();
+        investment_transactions_moved += 1;
+    }
+    if investment_transactions_moved > 0 {
+        changes.push((
+            finance_dir.join("investments.json"),
+            Some(to_json(&investments)?),
+        ));
+    }
+
+    let mut sessions_moved = false;
+    for session in reconciliations
+        .sessions
+        .iter_mut()
+        .filter(|s| s.account_id == account_id)
+    {
+        session.account_id = target_account_id.clone();
+        sessions_moved = true;
+    }
+    if sessions_moved {
+        changes.push((
+            finance_dir.join("reconciliations.json"),
+            Some(to_json(&reconciliations)?),
+        ));
+    }
+
+    let mut goals = load_goals(workspace_path)?;
+    let mut goals_moved = false;
+    for goal in goals
+        .goals
+        .iter_mut()
+        .filter(|g| g.account_id.as_deref() == Some(account_id.as_str()))
+    {
+        goal.account_id = Some(target_account_id.clone());
+        goals_moved = true;
+    }
+    if goals_moved {
+        changes.push((finance_dir.join("goals.json"), Some(to_json(&goals)?)));
+    }
+
+    let mut rules = load_rules(workspace_path)?;
+    let mut rules_moved = false;
+    for rule in rules
+        .rules
+        .iter_mut()
+        .filter(|r| r.conditions.account_id.as_deref() == Some(account_id.as_str()))
+    {
+        rule.conditions.account_id = Some(target_account_id.clone());
+        rules_moved = true;
+    }
+    if rules_moved {
+        changes.push((finance_dir.join("rules.json"), Some(to_json(&rules)?)));
+    }
+
+    let accounts_path = finance_dir.join("accounts.json");
+    let mut accounts_config: AccountsConfig = load_json(&accounts_path)?;
+    accounts_config.accounts.retain(|a| a.id != account_id);
+    changes.push((accounts_path, Some(to_json(&accounts_config)?)));
+
+    // 4. SQLite en una transacción que solo se confirma si todos los archivos se escribieron;
+    // si un archivo falla se restauran los ya escritos y la transacción se descarta
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    reassign_and_delete_account_in_database(
+        &tx,
+        &account_id,
+        &target_account_id,
+        &removed_record_ids,
+    )?;
+    let backups = apply_file_changes(&changes)?;
+    if let Err(e) = tx.commit() {
+        restore_files(&backups);
+        return Err(AppError::DatabaseError(e.to_string()));
+    }
+
+    Ok(AccountReassignReportDto {
+        records_moved,
+        self_transfers_removed: removed_record_ids.len(),
+        investment_transactions_moved,
+    })
+}
+
+#[tauri::command]
+pub async fn archive_account(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+) -> Result<(), AppError> {
+    set_account_active(&state, &account_id, false)
+}
+
+#[tauri::command]
+pub async fn unarchive_account(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+) -> Result<(), AppError> {
+    set_account_active(&state, &account_id, true)
+}
+
+// El saldo inicial rige desde el comienzo del día de apertura
+fn normalize_opening_date(account: &mut AccountInfoDto) {
+    if let Some(opening_date) = account.opening_date {
+        let day = timestamp_to_local_date(opening_date);
+        account.opening_date = Some(start_of_day_timestamp(day));
+    }
+}
+
+fn validate_account_loan(account: &AccountInfoDto) -> Result<(), AppError> {
+    let Some(loan) = &account.loan else {
+        return Ok(());
+    };
+    if account.account_type.as_deref() != Some("loan") {
+        return Err(AppError::ConfigError(
+            "Las condiciones de préstamo solo aplican a cuentas tipo loan".into(),
+        ));
+    }
+    validate_loan_settings(loan)
+}
+
+fn set_account_active(state: &AppState, account_id: &str, is_active: bool) -> Result<(), AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. El JSON es la fuente de verdad; 2. luego el índice
+    let accounts_path = workspace_path.join(".finance").join("accounts.json");
+    set_account_active_in_json(accounts_path, account_id, is_active)?;
+    set_account_active_in_database(conn, account_id, is_active)?;
+
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_paginated_records(
     state: tauri::State<'_, AppState>,
@@ -163,6 +558,7 @@ pub async fn get_paginated_records(
 
 #[tauri::command]
 pub async fn create_record(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     record: CreateRecordDto,
 ) -> Result<String, AppError> {
@@ -176,16 +572,134 @@ pub async fn create_record(
         "No hay un workspace activo en el estado".into(),
     ))?;
 
+    if let Some(count) = record.installments {
+        validate_installment_plan(count, record.monthly_interest_rate.unwrap_or(0.0))?;
+    }
+
     let record_id = uuid::Uuid::new_v4().to_string();
-    let records_dir = workspace_path.join("records");
-    let record_file_path = records_dir.join(format!("{}.json", record_id));
+    let mut record_item = build_record_item(&record, &record_id);
+
+    // Las compras a cuotas solo aplican a gastos con tarjeta de crédito
+    if record_item.installments.is_some() {
+        let account_type: String = conn
+            .query_row(
+                "SELECT type FROM accounts WHERE id = ?1",
+                [&record_item.account_id],
+                |row| row.get(0),
+            )
+            .map_err(|_| AppError::NotFound(format!("Cuenta {}", record_item.account_id)))?;
+
+        if record_item.r#type != "expense" || account_type != "credit" {
+            return Err(AppError::ConfigError(
+                "Las cuotas solo se permiten en gastos con tarjeta de crédito".into(),
+            ));
+        }
+    }
+
+    // Reglas de auto-categorización (opcional)
+    if record.apply_rules.unwrap_or(false) {
+        let rules = compile_rules(
+            load_rules(workspace_path)?.rules,
+            &load_categories(workspace_path)?.categories,
+        )?;
+        apply_rules(&rules, &mut record_item);
+    }
+
+    // Una transferencia a un préstamo se reparte en gasto de intereses y abono a capital
+    let loan = match (&record_item.r#type[..], &record_item.to_account_id) {
+        ("transfer", Some(to_account_id)) => load_loan_settings(conn, to_account_id)?
+            .map(|settings| (to_account_id.clone(), settings)),
+        _ => None,
+    };
+    let mut interest_record = None;
+    if let Some((loan_account_id, settings)) = loan {
+        let mut split = split_loan_payment(
+            conn,
+            &loan_account_id,
+            &settings,
+            record_item.amount,
+            record_item.timestamp,
+        )?;
+
+        if split.interest > 0.0 {
+            let interest_item = RecordItem {
+                id: uuid::Uuid::new_v4().to_string(),
+                r#type: "expense".into(),
+                amount: split.interest,
+                currency: record_item.currency.clone(),
+                timestamp: record_item.timestamp,
+                category_id: settings.interest_category_id.clone(),
+                account_id: record_item.account_id.clone(),
+                description: Some(format!(
+                    "Intereses {}",
+                    record_item.description.as_deref().unwrap_or("préstamo")
+                )),
+                metadata: Some(serde_json::json!({
+                    "loan_account_id": loan_account_id,
+                    "principal_record_id": record_id,
+                })),
+                ..Default::default()
+            };
+            split.interest_record_id = Some(interest_item.id.clone());
+            interest_record = Some(interest_item);
+        }
+
+        record_item.amount = split.principal;
+        record_item.loan_payment = Some(split);
+    }
+
+    // El gasto de intereses y el abono a capital se escriben juntos: si falla
+    // el segundo archivo se borra el primero, y el índice va en una transacción
+    let interest_path = match &interest_record {
+        Some(item) => Some(save_record_item(workspace_path, item)?),
+        None => None,
+    };
+    let record_file_path = match save_record_item(workspace_path, &record_item) {
+        Ok(path) => path,
+        Err(e) => {
+            if let Some(path) = &interest_path {
+                let _ = fs::remove_file(path);
+            }
+            return Err(AppError::IoError(format!(
+                "Error al crear archivo de registro: {}",
+                e
+            )));
+        }
+    };
     let file_path_str = record_file_path.to_string_lossy().to_string();
 
-    create_record_file(workspace_path, &record, &record_id)
-        .map_err(|e| AppError::IoError(format!("Error al crear archivo de registro: {}", e)))?;
+    let indexed = (|| {
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if let (Some(item), Some(path)) = (&interest_record, &interest_path) {
+            create_record_in_database(&tx, item, &path.to_string_lossy())?;
+        }
+        create_record_in_database(&tx, &record_item, &file_path_str)?;
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    })();
+    if let Err(e) = indexed {
+        for path in interest_path.iter().chain([&record_file_path]) {
+            let _ = fs::remove_file(path);
+        }
+        return Err(AppError::DatabaseError(format!(
+            "Error creando registro en DB: {}",
+            e
+        )));
+    }
 
-    create_record_in_database(conn, &record, &record_id, &file_path_str)
-        .map_err(|e| AppError::DatabaseError(format!("Error creando registro en DB: {}", e)))?;
+    // Umbrales de presupuesto del mes del registro (y del actual, por las cuotas)
+    if record_item.r#type == "expense" || record_item.loan_payment.is_some() {
+        let app_data_dir = state.workspace_app_data_dir.lock().unwrap().clone();
+        check_budget_alerts_quietly(
+            &app,
+            conn,
+            workspace_path,
+            app_data_dir.as_deref(),
+            &[month_of(record_item.timestamp), current_month()],
+        );
+    }
 
     Ok(record_id)
 }
@@ -193,13 +707,14 @@ pub async fn create_record(
 #[tauri::command]
 pub async fn get_categories(
     state: tauri::State<'_, AppState>,
+    include_inactive: Option<bool>,
 ) -> Result<Vec<CategoryDto>, AppError> {
     let mut conn_guard = state.db.lock().unwrap();
     let conn = conn_guard.as_mut().ok_or_else(|| {
         AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
     })?;
 
-    let categories = fetch_categories(conn)
+    let categories = fetch_categories(conn, include_inactive.unwrap_or(false))
         .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
     Ok(categories)
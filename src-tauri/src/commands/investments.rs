@@ -0,0 +1,485 @@
+use crate::domain::config_models::{
+    InvestmentTransactionItem, RecordItem, SecurityItem, SecurityPriceItem,
+};
+use crate::domain::error::AppError;
+use crate::dto::investment_dto::{
+    HoldingDto, InvestmentCurrencyWarningDto, InvestmentGainsReportDto, InvestmentTransactionDto,
+    PriceImportReportDto,
+};
+use crate::fs::investment_file_management::{load_investments, save_investments};
+use crate::fs::record_file_management::save_record_item;
+use crate::helpers::datetime_helpers::{
+    end_of_day_timestamp, start_of_day_timestamp, timestamp_now, timestamp_to_local_date,
+};
+use crate::services::investments::{
+    self, index_investments, lowest_quantity_held, parse_price_csv, upsert_price, CostBasisMethod,
+};
+use crate::services::records::create_record_in_database;
+use crate::AppState;
+use std::fs;
+use std::path::PathBuf;
+
+#[tauri::command]
+pub async fn get_securities(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<SecurityItem>, AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    Ok(load_investments(workspace_path)?.securities)
+}
+
+// Crea el título (id vacío) o actualiza el existente
+#[tauri::command]
+pub async fn save_security(
+    state: tauri::State<'_, AppState>,
+    mut security: SecurityItem,
+) -> Result<SecurityItem, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    security.symbol = security.symbol.trim().to_uppercase();
+    if security.symbol.is_empty() {
+        return Err(AppError::ConfigError(
+            "El título necesita un símbolo".into(),
+        ));
+    }
+
+    let mut config = load_investments(workspace_path)?;
+    if config
+        .securities
+        .iter()
+        .any(|s| s.symbol == security.symbol && s.id != security.id)
+    {
+        return Err(AppError::ConfigError(format!(
+            "Ya existe un título con el símbolo {}",
+            security.symbol
+        )));
+    }
+
+    if security.id.is_empty() {
+        security.id = uuid::Uuid::new_v4().to_string();
+    }
+    // Las operaciones ya registradas quedaron en la moneda de su cuenta
+    let currency_changed = config
+        .securities
+        .iter()
+        .any(|s| s.id == security.id && !s.currency.eq_ignore_ascii_case(&security.currency));
+    if currency_changed
+        && config
+            .transactions
+            .iter()
+            .any(|t| t.security_id == security.id)
+    {
+        return Err(AppError::ConfigError(format!(
+            "No se puede cambiar la moneda de {}: ya tiene operaciones",
+            security.symbol
+        )));
+    }
+
+    match config.securities.iter_mut().find(|s| s.id == security.id) {
+        Some(existing) => *existing = security.clone(),
+        None => config.securities.push(security.clone()),
+    }
+
+    save_investments(workspace_path, &config)?;
+    index_investments(conn, &config)?;
+
+    Ok(security)
+}
+
+#[tauri::command]
+pub async fn record_investment_transaction(
+    state: tauri::State<'_, AppState>,
+    transaction: InvestmentTransactionDto,
+) -> Result<String, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Validar cuenta y título
+    let (account_type, account_currency): (String, String) = conn
+        .query_row(
+            "SELECT type, currency FROM accounts WHERE id = ?1",
+            [&transaction.account_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| AppError::NotFound(format!("Cuenta {}", transaction.account_id)))?;
+    if account_type != "investment" {
+        return Err(AppError::ConfigError(
+            "Las operaciones de inversión solo se registran en cuentas tipo investment".into(),
+        ));
+    }
+
+    let mut config = load_investments(workspace_path)?;
+    let security = config
+        .securities
+        .iter()
+        .find(|s| s.id == transaction.security_id)
+        .cloned()
+        .ok_or_else(|| {
+            AppError::NotFound(format!("Título {} no encontrado", transaction.security_id))
+        })?;
+
+    // El efectivo y el valor de mercado suman al saldo de la cuenta en su moneda
+    if !security.currency.eq_ignore_ascii_case(&account_currency) {
+        return Err(AppError::ConfigError(format!(
+            "El título {} cotiza en {} y la cuenta está en {}",
+            security.symbol, security.currency, account_currency
+        )));
+    }
+
+    let mut item = InvestmentTransactionItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        account_id: transaction.account_id.clone(),
+        security_id: security.id.clone(),
+        kind: transaction.kind.clone(),
+        quantity: transaction.quantity.unwrap_or(0.0),
+        price: transaction.price.unwrap_or(0.0),
+        fees: transaction.fees.unwrap_or(0.0),
+        amount: None,
+        timestamp: transaction.timestamp,
+        record_id: None,
+        created_at: timestamp_now(),
+    };
+
+    // 2. Validar según el tipo de operación
+    let mut income_record: Option<RecordItem> = None;
+    match item.kind.as_str() {
+        "buy" | "sell" => {
+            if item.quantity <= 0.0 || item.price < 0.0 || item.fees < 0.0 {
+                return Err(AppError::ConfigError(
+                    "La operación necesita cantidad positiva y precio válido".into(),
+                ));
+            }
+            // Con la venta la posición no puede quedar negativa en ningún momento,
+            // tampoco por ventas posteriores ya registradas
+            if item.kind == "sell" {
+                config.transactions.push(item.clone());
+                let lowest = lowest_quantity_held(&config, &item.account_id, &item.security_id);
+                config.transactions.pop();
+                if lowest < -1e-9 {
+                    return Err(AppError::ConfigError(format!(
+                        "No hay suficientes unidades de {} para vender (disponibles: {})",
+                        security.symbol,
+                        (item.quantity + lowest).max(0.0)
+                    )));
+                }
+            }
+        }
+        "dividend" | "interest" => {
+            let amount = transaction.amount.unwrap_or(0.0);
+            if amount <= 0.0 {
+                return Err(AppError::ConfigError(
+                    "El dividendo o interés necesita un monto positivo".into(),
+                ));
+            }
+            item.amount = Some(amount);
+
+            // El dinero recibido entra como ingreso en la cuenta de inversión
+            let label = if item.kind == "dividend" {
+                "Dividendo"
+            } else {
+                "Intereses"
+            };
+            // Misma moneda que la cuenta (validado arriba): el registro no la repite
+            let record = RecordItem {
+                id: uuid::Uuid::new_v4().to_string(),
+                r#type: "income".into(),
+                amount,
+                timestamp: item.timestamp,
+                category_id: transaction.category_id.clone(),
+                account_id: item.account_id.clone(),
+                description: Some(
+                    transaction
+                        .description
+                        .clone()
+                        .unwrap_or_else(|| format!("{} {}", label, security.symbol)),
+                ),
+                metadata: Some(serde_json::json!({ "investment_transaction_id": item.id })),
+                ..Default::default()
+            };
+
+            item.record_id = Some(record.id.clone());
+            income_record = Some(record);
+        }
+        other => {
+            return Err(AppError::ConfigError(format!(
+                "Tipo de operación desconocido: {}",
+                other
+            )))
+        }
+    }
+
+    // 3. Guardar primero los archivos (el ingreso y la operación que lo referencia)
+    let id = item.id.clone();
+    config.transactions.push(item);
+    let record_path = match &income_record {
+        Some(record) => Some(save_record_item(workspace_path, record)?),
+        None => None,
+    };
+    if let Err(e) = save_investments(workspace_path, &config) {
+        if let Some(path) = &record_path {
+            let _ = fs::remove_file(path);
+        }
+        return Err(e);
+    }
+
+    // 4. Indexar en una transacción; si falla se deshacen los archivos
+    let indexed = (|| {
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if let (Some(record), Some(path)) = (&income_record, &record_path) {
+            create_record_in_database(&tx, record, &path.to_string_lossy())?;
+        }
+        index_investments(&tx, &config)?;
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    })();
+    if let Err(e) = indexed {
+        config.transactions.pop();
+        let _ = save_investments(workspace_path, &config);
+        if let Some(path) = &record_path {
+            let _ = fs::remove_file(path);
+        }
+        return Err(e);
+    }
+
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn delete_investment_transaction(
+    state: tauri::State<'_, AppState>,
+    transaction_id: String,
+) -> Result<(), AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let mut config = load_investments(workspace_path)?;
+    let index = config
+        .transactions
+        .iter()
+        .position(|t| t.id == transaction_id)
+        .ok_or_else(|| AppError::NotFound(format!("Operación {} no encontrada", transaction_id)))?;
+    let removed = config.transactions.remove(index);
+
+    // Sin la compra, alguna venta (aunque sea anterior al final) quedaría sin unidades
+    if removed.kind == "buy" {
+        let lowest = lowest_quantity_held(&config, &removed.account_id, &removed.security_id);
+        if lowest < -1e-9 {
+            return Err(AppError::ConfigError(
+                "No se puede borrar una compra cuyas unidades ya se vendieron".into(),
+            ));
+        }
+    }
+
+    // El ingreso generado por dividendos o intereses se borra con la operación
+    if let Some(record_id) = &removed.record_id {
+        let file_path: Option<String> = conn
+            .query_row(
+                "SELECT file_path FROM records WHERE id = ?1",
+                [record_id],
+                |row| row.get(0),
+            )
+            .ok();
+        if let Some(file_path) = file_path {
+            let path = PathBuf::from(file_path);
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        conn.execute("DELETE FROM records WHERE id = ?1", [record_id])
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        conn.execute("DELETE FROM record_tags WHERE record_id = ?1", [record_id])
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    }
+
+    save_investments(workspace_path, &config)?;
+    index_investments(conn, &config)
+}
+
+#[tauri::command]
+pub async fn set_security_price(
+    state: tauri::State<'_, AppState>,
+    security_id: String,
+    date: i64,
+    price: f64,
+) -> Result<(), AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    if price <= 0.0 {
+        return Err(AppError::ConfigError(
+            "El precio debe ser mayor que cero".into(),
+        ));
+    }
+
+    let mut config = load_investments(workspace_path)?;
+    if !config.securities.iter().any(|s| s.id == security_id) {
+        return Err(AppError::NotFound(format!(
+            "Título {} no encontrado",
+            security_id
+        )));
+    }
+
+    upsert_price(
+        &mut config,
+        SecurityPriceItem {
+            security_id,
+            date: start_of_day_timestamp(timestamp_to_local_date(date)),
+            price,
+            source: "manual".into(),
+        },
+    );
+
+    save_investments(workspace_path, &config)?;
+    index_investments(conn, &config)
+}
+
+#[tauri::command]
+pub async fn import_security_prices_csv(
+    state: tauri::State<'_, AppState>,
+    csv_path: String,
+) -> Result<PriceImportReportDto, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let content = fs::read_to_string(&csv_path)
+        .map_err(|e| AppError::IoError(format!("Error leyendo {}: {}", csv_path, e)))?;
+    let (prices, skipped) = parse_price_csv(&content);
+
+    let mut config = load_investments(workspace_path)?;
+    let mut imported = 0;
+    let mut unknown_symbols: Vec<String> = Vec::new();
+
+    for (symbol, date, price) in prices {
+        let Some(security_id) = config
+            .securities
+            .iter()
+            .find(|s| s.symbol.eq_ignore_ascii_case(&symbol))
+            .map(|s| s.id.clone())
+        else {
+            if !unknown_symbols.contains(&symbol) {
+                unknown_symbols.push(symbol);
+            }
+            continue;
+        };
+
+        upsert_price(
+            &mut config,
+            SecurityPriceItem {
+                security_id,
+                date,
+                price,
+                source: "csv".into(),
+            },
+        );
+        imported += 1;
+    }
+
+    save_investments(workspace_path, &config)?;
+    index_investments(conn, &config)?;
+
+    Ok(PriceImportReportDto {
+        imported,
+        unknown_symbols,
+        skipped,
+    })
+}
+
+#[tauri::command]
+pub async fn get_holdings(
+    state: tauri::State<'_, AppState>,
+    account_id: Option<String>,
+    method: Option<CostBasisMethod>,
+) -> Result<Vec<HoldingDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    investments::get_holdings(
+        conn,
+        account_id.as_deref(),
+        method.unwrap_or(CostBasisMethod::Fifo),
+        timestamp_now(),
+    )
+}
+
+#[tauri::command]
+pub async fn get_investment_gains(
+    state: tauri::State<'_, AppState>,
+    account_id: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+    method: Option<CostBasisMethod>,
+) -> Result<InvestmentGainsReportDto, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let from = from
+        .map(|f| start_of_day_timestamp(timestamp_to_local_date(f)))
+        .unwrap_or(i64::MIN);
+    let to = end_of_day_timestamp(timestamp_to_local_date(to.unwrap_or_else(timestamp_now)));
+
+    investments::get_investment_gains(
+        conn,
+        account_id.as_deref(),
+        from,
+        to,
+        method.unwrap_or(CostBasisMethod::Fifo),
+    )
+}
+
+// Títulos en otra moneda que no suman al saldo de su cuenta
+#[tauri::command]
+pub async fn get_investment_currency_warnings(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<InvestmentCurrencyWarningDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    investments::get_investment_currency_warnings(conn)
+}
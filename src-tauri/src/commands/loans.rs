@@ -0,0 +1,47 @@
+use crate::domain::config_models::LoanSettings;
+use crate::domain::error::AppError;
+use crate::dto::loan_dto::{ExtraPaymentDto, LoanScheduleRowDto, LoanSummaryDto};
+use crate::helpers::datetime_helpers::timestamp_now;
+use crate::services::loans;
+use crate::AppState;
+
+#[tauri::command]
+pub async fn get_loan_summary(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+) -> Result<LoanSummaryDto, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    loans::get_loan_summary(conn, &account_id, timestamp_now())
+}
+
+// Tabla del préstamo con los abonos ya hechos más abonos hipotéticos
+#[tauri::command]
+pub async fn simulate_loan_schedule(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+    extra_payments: Vec<ExtraPaymentDto>,
+) -> Result<Vec<LoanScheduleRowDto>, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let settings = loans::load_loan(conn, &account_id)?;
+    let mut payments = loans::get_recorded_extra_payments(conn, &account_id)?;
+    payments.extend(extra_payments);
+
+    Ok(loans::build_loan_schedule(&settings, &payments))
+}
+
+// Vista previa antes de crear la cuenta
+#[tauri::command]
+pub async fn preview_loan_schedule(
+    settings: LoanSettings,
+) -> Result<Vec<LoanScheduleRowDto>, AppError> {
+    loans::validate_loan_settings(&settings)?;
+    Ok(loans::build_loan_schedule(&settings, &[]))
+}
@@ -1,2 +1,13 @@
 pub mod workspace;
-pub mod home;
\ No newline at end of file
+pub mod home;
+pub mod export;
+pub mod rules;
+pub mod credit_cards;
+pub mod reconciliation;
+pub mod accounts;
+pub mod loans;
+pub mod investments;
+pub mod categories;
+pub mod budgets;
+pub mod envelopes;
+pub mod goals;
\ No newline at end of file
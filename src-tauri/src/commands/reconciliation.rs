@@ -0,0 +1,285 @@
+use crate::domain::config_models::{ReconciliationItem, RecordItem};
+use crate::domain::error::AppError;
+use crate::dto::reconciliation_dto::{AccountReconciliationSummaryDto, ReconciliationStatusDto};
+use crate::fs::reconciliation_file_management::{
+    load_reconciliations, reconciliations_path, set_session, upsert_reconciliation,
+};
+use crate::fs::record_file_management::record_file_path;
+use crate::helpers::datetime_helpers::{
+    end_of_day_timestamp, timestamp_now, timestamp_to_local_date,
+};
+use crate::helpers::json_helpers::{apply_file_changes, load_json, restore_files, to_json};
+use crate::services::reconciliation::{build_status, mark_records_cleared, record_file_paths};
+use crate::services::records::create_record_in_database;
+use crate::AppState;
+use std::path::{Path, PathBuf};
+
+fn find_session(workspace_path: &Path, session_id: &str) -> Result<ReconciliationItem, AppError> {
+    load_reconciliations(workspace_path)?
+        .sessions
+        .into_iter()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| AppError::NotFound(format!("Conciliación {} no encontrada", session_id)))
+}
+
+#[tauri::command]
+pub async fn start_reconciliation(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+    statement_date: i64,
+    statement_balance: f64,
+) -> Result<ReconciliationStatusDto, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. La cuenta debe existir antes de guardar una sesión para ella
+    let account_exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM accounts WHERE id = ?1)",
+            [&account_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    if !account_exists {
+        return Err(AppError::NotFound(format!(
+            "Cuenta {} no encontrada",
+            account_id
+        )));
+    }
+
+    // 2. Solo una sesión abierta por cuenta: la reutilizamos con los nuevos datos del extracto
+    let existing = load_reconciliations(workspace_path)?
+        .sessions
+        .into_iter()
+        .find(|s| s.account_id == account_id && s.status == "open");
+
+    let mut session = existing.unwrap_or_else(|| ReconciliationItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        account_id: account_id.clone(),
+        statement_date: 0,
+        statement_balance: 0.0,
+        status: "open".into(),
+        selected_record_ids: Vec::new(),
+        difference: None,
+        adjustment_record_id: None,
+        created_at: timestamp_now(),
+        completed_at: None,
+    });
+    // El extracto incluye todo el día de corte
+    session.statement_date = end_of_day_timestamp(timestamp_to_local_date(statement_date));
+    session.statement_balance = statement_balance;
+
+    // 3. Calcular el estado antes de escribir: si falla no queda una sesión huérfana
+    let status = build_status(conn, session.clone())?;
+    upsert_reconciliation(workspace_path, session)?;
+
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn set_reconciliation_selection(
+    state: tauri::State<'_, AppState>,
+    session_id: String,
+    record_ids: Vec<String>,
+) -> Result<ReconciliationStatusDto, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let mut session = find_session(workspace_path, &session_id)?;
+    if session.status != "open" {
+        return Err(AppError::ConfigError(
+            "La conciliación ya fue finalizada".into(),
+        ));
+    }
+
+    session.selected_record_ids = record_ids;
+    upsert_reconciliation(workspace_path, session.clone())?;
+
+    build_status(conn, session)
+}
+
+#[tauri::command]
+pub async fn finish_reconciliation(
+    state: tauri::State<'_, AppState>,
+    session_id: String,
+    create_adjustment: bool,
+) -> Result<ReconciliationItem, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let session = find_session(workspace_path, &session_id)?;
+    if session.status != "open" {
+        return Err(AppError::ConfigError(
+            "La conciliación ya fue finalizada".into(),
+        ));
+    }
+
+    // 1. Solo cuentan los registros marcados que siguen pendientes
+    let status = build_status(conn, session)?;
+    let mut session = status.session;
+    let difference = status.difference;
+    let now = timestamp_now();
+
+    if difference.abs() > 0.005 && !create_adjustment {
+        return Err(AppError::ConfigError(format!(
+            "El saldo conciliado no coincide con el extracto (diferencia {:.2})",
+            difference
+        )));
+    }
+
+    // 2. Ajuste por la diferencia (ya conciliado)
+    let mut adjustment: Option<RecordItem> = None;
+    if difference.abs() > 0.005 {
+        let item = RecordItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            r#type: if difference > 0.0 {
+                "income"
+            } else {
+                "expense"
+            }
+            .into(),
+            amount: difference.abs(),
+            timestamp: session.statement_date,
+            account_id: session.account_id.clone(),
+            description: Some("Ajuste de conciliación".into()),
+            metadata: Some(serde_json::json!({ "reconciliation_id": session.id })),
+            cleared_at: Some(now),
+            ..Default::default()
+        };
+        session.adjustment_record_id = Some(item.id.clone());
+        adjustment = Some(item);
+    }
+
+    // 3. Preparar los archivos: el ajuste, los registros marcados y la sesión cerrada
+    let selected: Vec<String> = status
+        .records
+        .iter()
+        .filter(|r| r.selected)
+        .map(|r| r.id.clone())
+        .collect();
+
+    let mut changes: Vec<(PathBuf, Option<String>)> = Vec::new();
+    if let Some(item) = &adjustment {
+        changes.push((
+            record_file_path(workspace_path, &item.id),
+            Some(to_json(item)?),
+        ));
+    }
+    for file_path in record_file_paths(conn, &selected)? {
+        let path = PathBuf::from(file_path);
+        let mut record: RecordItem = load_json(&path)?;
+        record.cleared_at = Some(now);
+        changes.push((path, Some(to_json(&record)?)));
+    }
+
+    session.selected_record_ids = selected;
+    session.status = "completed".into();
+    session.difference = Some(difference);
+    session.completed_at = Some(now);
+    let mut reconciliations = load_reconciliations(workspace_path)?;
+    set_session(&mut reconciliations, session.clone());
+    changes.push((
+        reconciliations_path(workspace_path),
+        Some(to_json(&reconciliations)?),
+    ));
+
+    // 4. Primero los archivos y luego el índice; si el índice falla los
+    // archivos vuelven a como estaban (el ajuste se borra)
+    let backups = apply_file_changes(&changes)?;
+    let indexed = (|| {
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if let Some(item) = &adjustment {
+            let path = record_file_path(workspace_path, &item.id);
+            create_record_in_database(&tx, item, &path.to_string_lossy())?;
+        }
+        mark_records_cleared(&tx, &session.selected_record_ids, now)?;
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    })();
+    if let Err(e) = indexed {
+        restore_files(&backups);
+        return Err(e);
+    }
+
+    Ok(session)
+}
+
+#[tauri::command]
+pub async fn get_reconciliation_history(
+    state: tauri::State<'_, AppState>,
+    account_id: String,
+) -> Result<Vec<ReconciliationItem>, AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let mut sessions: Vec<ReconciliationItem> = load_reconciliations(workspace_path)?
+        .sessions
+        .into_iter()
+        .filter(|s| s.account_id == account_id)
+        .collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.statement_date));
+
+    Ok(sessions)
+}
+
+#[tauri::command]
+pub async fn get_last_reconciliations(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<AccountReconciliationSummaryDto>, AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // Última sesión completada por cuenta
+    let mut summaries: Vec<AccountReconciliationSummaryDto> = Vec::new();
+    for session in load_reconciliations(workspace_path)?.sessions {
+        let Some(completed_at) = session.completed_at else {
+            continue;
+        };
+        match summaries
+            .iter_mut()
+            .find(|s| s.account_id == session.account_id)
+        {
+            Some(summary) if summary.statement_date >= session.statement_date => {}
+            Some(summary) => {
+                summary.last_reconciled_at = completed_at;
+                summary.statement_date = session.statement_date;
+                summary.statement_balance = session.statement_balance;
+            }
+            None => summaries.push(AccountReconciliationSummaryDto {
+                account_id: session.account_id,
+                last_reconciled_at: completed_at,
+                statement_date: session.statement_date,
+                statement_balance: session.statement_balance,
+            }),
+        }
+    }
+
+    Ok(summaries)
+}
@@ -0,0 +1,156 @@
+use crate::domain::config_models::RuleItem;
+use crate::domain::error::AppError;
+use crate::dto::rule_match_dto::RuleMatchDto;
+use crate::fs::category_file_management::load_categories;
+use crate::fs::record_file_management::{load_all_records, save_record_item};
+use crate::fs::rules_file_management::{load_rules, remove_rule, upsert_rule};
+use crate::helpers::datetime_helpers::timestamp_now;
+use crate::services::budget_alerts::{check_budget_alerts_quietly, month_of};
+use crate::services::records::upsert_record_in_database;
+use crate::services::rules::{apply_actions, apply_rules, compile_rule, compile_rules, rule_matches};
+use crate::AppState;
+
+#[tauri::command]
+pub async fn get_rules(state: tauri::State<'_, AppState>) -> Result<Vec<RuleItem>, AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    Ok(load_rules(workspace_path)?.rules)
+}
+
+#[tauri::command]
+pub async fn save_rule(
+    state: tauri::State<'_, AppState>,
+    mut rule: RuleItem,
+) -> Result<String, AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // Regla nueva: generamos id y fecha
+    if rule.id.is_empty() {
+        rule.id = uuid::Uuid::new_v4().to_string();
+        rule.created_at = timestamp_now();
+    }
+
+    // La categoría asignada debe coincidir con el tipo de registro de la condición
+    let categories = load_categories(workspace_path)?.categories;
+    if let (Some(category_id), Some(record_type)) =
+        (&rule.actions.category_id, &rule.conditions.record_type)
+    {
+        let category = categories
+            .iter()
+            .find(|c| &c.id == category_id)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Categoría {} no encontrada", category_id))
+            })?;
+        if &category.r#type != record_type {
+            return Err(AppError::ConfigError(format!(
+                "La regla aplica a registros de tipo {} pero asigna una categoría de tipo {}",
+                record_type, category.r#type
+            )));
+        }
+    }
+    // Validamos la regex antes de guardar
+    let rule_id = rule.id.clone();
+    let compiled = compile_rule(rule, &categories)?;
+    upsert_rule(workspace_path, compiled.rule)?;
+
+    Ok(rule_id)
+}
+
+#[tauri::command]
+pub async fn delete_rule(
+    state: tauri::State<'_, AppState>,
+    rule_id: String,
+) -> Result<(), AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    remove_rule(workspace_path, &rule_id)
+}
+
+#[tauri::command]
+pub async fn test_rule(
+    state: tauri::State<'_, AppState>,
+    rule: RuleItem,
+) -> Result<Vec<RuleMatchDto>, AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let compiled = compile_rule(rule, &load_categories(workspace_path)?.categories)?;
+    let mut matches = Vec::new();
+
+    // Simulamos sobre los registros existentes sin escribir nada
+    for (_, mut record) in load_all_records(workspace_path)? {
+        if !rule_matches(&compiled, &record) {
+            continue;
+        }
+        let current_category_id = record.category_id.clone();
+        let would_change = apply_actions(&compiled, &mut record);
+
+        matches.push(RuleMatchDto {
+            record_id: record.id,
+            timestamp: record.timestamp,
+            amount: record.amount,
+            description: record.description,
+            current_category_id,
+            new_category_id: record.category_id,
+            would_change,
+        });
+    }
+
+    Ok(matches)
+}
+
+#[tauri::command]
+pub async fn reapply_rules(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    only_uncategorized: Option<bool>,
+) -> Result<usize, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let rules = compile_rules(
+        load_rules(workspace_path)?.rules,
+        &load_categories(workspace_path)?.categories,
+    )?;
+    let only_uncategorized = only_uncategorized.unwrap_or(false);
+    let mut updated = 0;
+    let mut months = Vec::new();
+
+    for (path, mut record) in load_all_records(workspace_path)? {
+        if only_uncategorized && record.category_id.is_some() {
+            continue;
+        }
+
+        // 1. Primero el archivo (fuente de verdad), luego el índice
+        if apply_rules(&rules, &mut record) {
+            save_record_item(workspace_path, &record)?;
+            upsert_record_in_database(conn, &record, &path.to_string_lossy())?;
+            months.push(month_of(record.timestamp));
+            updated += 1;
+        }
+    }
+
+    // 2. Recategorizar puede llevar gastos a categorías presupuestadas
+    let app_data_dir = state.workspace_app_data_dir.lock().unwrap().clone();
+    check_budget_alerts_quietly(&app, conn, workspace_path, app_data_dir.as_deref(), &months);
+
+    Ok(updated)
+}
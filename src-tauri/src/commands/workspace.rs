@@ -1,8 +1,26 @@
-use crate::domain::config_models::{AppConfig, LastSessionCacheConfig};
+use crate::domain::config_models::{
+    AccountsConfig, AppConfig, ArchiveManifest, CategoriesConfig, LastSessionCacheConfig,
+    RulesConfig,
+};
 use crate::domain::error::AppError;
+use crate::domain::presets::{
+    has_preset_name, normalize_currency, normalize_language, preset_category_name,
+    DEFAULT_CURRENCY, DEFAULT_LANGUAGE,
+};
+use crate::dto::import_report_dto::ImportReportDto;
 use crate::dto::workspace_context_dto::WorkspaceContext;
-use crate::fs::{db_indexer, db_init, local_storage, workspace_init};
-use crate::helpers::json_helpers::load_json;
+use crate::fs::category_file_management::{load_categories, save_categories};
+use crate::fs::record_file_management::save_record_item;
+use crate::fs::{
+    db_indexer, db_init, local_storage, workspace_archive, workspace_init, workspace_schemas,
+};
+use crate::helpers::datetime_helpers::timestamp_now;
+use crate::helpers::json_helpers::{load_json, save_json};
+use crate::services::beancount_import::parse_beancount;
+use crate::services::budget_alerts::check_budget_alerts_quietly;
+use crate::services::budgets::current_month;
+use crate::services::categories::upsert_category_in_database;
+use crate::services::rules::{apply_rules, compile_rules};
 use crate::AppState;
 use rusqlite::Connection;
 use std::path::{Path, PathBuf};
@@ -15,10 +33,14 @@ pub async fn init_workspace(
     state: tauri::State<'_, AppState>,
     base_path: String,
     name: String,
+    language: Option<String>,
+    currency: Option<String>,
 ) -> Result<String, AppError> {
-    // 1. Crear el Workspace sincronizable (los JSON)
+    // 1. Crear el Workspace sincronizable (los JSON) con los presets del idioma
+    let language = normalize_language(language.as_deref().unwrap_or(DEFAULT_LANGUAGE))?;
+    let currency = normalize_currency(currency.as_deref().unwrap_or(DEFAULT_CURRENCY))?;
     let workspace_path = Path::new(&base_path).join(&name);
-    workspace_init::init(base_path.clone(), name.clone())?;
+    workspace_init::init(base_path.clone(), name.clone(), &language, &currency)?;
 
     // 2. Crear el almacenamiento local (SQLite, Logs)
     let local_paths = local_storage::prepare_local_storage(&app, base_path.clone(), name.clone())?;
@@ -55,6 +77,100 @@ pub async fn init_workspace(
     Ok("Workspace e índice local creados correctamente".into())
 }
 
+// Cambia el idioma del workspace. Con retitle_categories, las categorías
+// predeterminadas que conservan su nombre original pasan al nuevo idioma.
+// Devuelve cuántas categorías se renombraron.
+#[tauri::command]
+pub async fn set_workspace_language(
+    state: tauri::State<'_, AppState>,
+    language: String,
+    retitle_categories: Option<bool>,
+) -> Result<usize, AppError> {
+    let mut conn_guard = state.db.lock().unwrap();
+    let conn = conn_guard.as_mut().ok_or_else(|| {
+        AppError::DatabaseError("No hay una conexión a la base de datos activa".into())
+    })?;
+
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    // 1. Guardar el idioma en app.json
+    let language = normalize_language(&language)?;
+    let app_path = workspace_path.join(".finance").join("app.json");
+    let mut app_config: AppConfig = load_json(&app_path)?;
+    app_config.language = language.clone();
+    app_config.updated_at = timestamp_now();
+    save_json(app_path, &app_config)?;
+
+    if !retitle_categories.unwrap_or(false) {
+        return Ok(0);
+    }
+
+    // 2. Renombrar solo las predeterminadas que el usuario no tocó, sin repetir
+    // el nombre de otra categoría del mismo tipo y nivel
+    let mut categories = load_categories(workspace_path)?;
+    let mut retitled_ids = Vec::new();
+    for index in 0..categories.categories.len() {
+        let category = &categories.categories[index];
+        if category.created_by_user || !has_preset_name(category) {
+            continue;
+        }
+        let Some(name) = preset_category_name(&category.id, &language) else {
+            continue;
+        };
+        if category.name == name {
+            continue;
+        }
+        let duplicated = categories.categories.iter().any(|c| {
+            c.id != category.id
+                && c.r#type == category.r#type
+                && c.parent_id == category.parent_id
+                && c.name.trim().to_lowercase() == name.to_lowercase()
+        });
+        if duplicated {
+            continue;
+        }
+        retitled_ids.push(category.id.clone());
+        categories.categories[index].name = name.into();
+    }
+
+    // 3. Primero categories.json (la fuente de verdad), luego la base
+    let retitled = retitled_ids.len();
+    if retitled > 0 {
+        save_categories(workspace_path, &categories)?;
+        for category in categories
+            .categories
+            .iter()
+            .filter(|c| retitled_ids.contains(&c.id))
+        {
+            upsert_category_in_database(conn, category)?;
+        }
+    }
+
+    Ok(retitled)
+}
+
+// Activa o desactiva las notificaciones de escritorio de presupuesto.
+// El evento "budget-alert" se emite siempre.
+#[tauri::command]
+pub async fn set_budget_notifications(
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    let app_path = workspace_path.join(".finance").join("app.json");
+    let mut app_config: AppConfig = load_json(&app_path)?;
+    app_config.budget_notifications = enabled;
+    app_config.updated_at = timestamp_now();
+    save_json(app_path, &app_config)
+}
+
 #[tauri::command]
 pub async fn open_workspace(
     app: AppHandle,
@@ -79,6 +195,10 @@ pub async fn open_workspace(
         ));
     }
 
+    // Validamos los archivos contra sus esquemas antes de indexar
+    workspace_schemas::write_schemas(&finance_dir)?;
+    workspace_schemas::validate_workspace(path)?;
+
     let workspace_name = path
         .file_name()
         .map(|n: &std::ffi::OsStr| n.to_string_lossy().into_owned())
@@ -117,6 +237,17 @@ pub async fn open_workspace(
     let mut app_data_path_guard = state.workspace_app_data_dir.lock().unwrap();
     *app_data_path_guard = Some(app_data_base_path);
 
+    // 3. Avisar los umbrales de presupuesto del mes actual
+    if let Some(conn) = db_guard.as_ref() {
+        check_budget_alerts_quietly(
+            &app,
+            conn,
+            path,
+            app_data_path_guard.as_deref(),
+            &[current_month()],
+        );
+    }
+
     Ok(format!("Workspace '{}' abierto e indexado", workspace_name))
 }
 
@@ -132,6 +263,11 @@ pub async fn get_workspace_context(
     // Aquí leerías el archivo (usando tus helpers)
     let session: LastSessionCacheConfig = load_json(&session_path)?;
 
+    // Igual que al abrir: validamos los archivos contra sus esquemas
+    let workspace_path = Path::new(&session.last_workspace_path);
+    workspace_schemas::write_schemas(&workspace_path.join(".finance"))?;
+    workspace_schemas::validate_workspace(workspace_path)?;
+
     // 2. Leer el app.json del workspace para la moneda y configuración
     let config_path = std::path::Path::new(&session.last_workspace_path)
         .join(".finance")
@@ -146,6 +282,12 @@ pub async fn get_workspace_context(
     let mut conn = Connection::open(&app_data_sql_path)
         .map_err(|e| AppError::IoError(format!("Error al abrir DB: {}", e)))?;
 
+    // La caché puede venir de una versión anterior: migramos y reindexamos
+    // igual que en open_workspace
+    db_init::init_sqlite(&mut conn)
+        .map_err(|e| AppError::IoError(format!("Error al inicializar tablas: {}", e)))?;
+    db_indexer::index_full_workspace(workspace_path, &mut conn)?;
+
     // 1. Guardar la conexión DB en el estado
     let mut db_guard = state.db.lock().unwrap();
     *db_guard = Some(conn);
@@ -158,6 +300,17 @@ pub async fn get_workspace_context(
     let mut app_data_path_guard = state.workspace_app_data_dir.lock().unwrap();
     *app_data_path_guard = Some(app_data_base_path);
 
+    // 3. Avisar los umbrales de presupuesto del mes actual
+    if let (Some(conn), Some(workspace_path)) = (db_guard.as_ref(), path_guard.as_ref()) {
+        check_budget_alerts_quietly(
+            &app,
+            conn,
+            workspace_path,
+            app_data_path_guard.as_deref(),
+            &[current_month()],
+        );
+    }
+
     Ok(WorkspaceContext {
         name: session.last_workspace_name,
         path: session.last_workspace_path,
@@ -165,3 +318,160 @@ pub async fn get_workspace_context(
         theme: config.theme,
     })
 }
+
+
+#[tauri::command]
+pub async fn import_beancount_workspace(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    base_path: String,
+    name: String,
+    beancount_path: String,
+    rules_path: Option<String>,
+) -> Result<ImportReportDto, AppError> {
+    // 1. Leer y parsear el journal antes de tocar el disco
+    let content = std::fs::read_to_string(&beancount_path).map_err(|e| {
+        AppError::IoError(format!("No se pudo leer el archivo {}: {}", beancount_path, e))
+    })?;
+    let mut import = parse_beancount(&content, &AppConfig::default().currency);
+
+    // Reglas de otro workspace (opcional) para categorizar lo importado
+    let rules_config: RulesConfig = match &rules_path {
+        Some(path) => load_json(Path::new(path))?,
+        None => RulesConfig::default(),
+    };
+    let compiled_rules = compile_rules(rules_config.rules.clone(), &import.categories)?;
+    for record in import.records.iter_mut() {
+        let original_category = record.category_id.clone();
+        apply_rules(&compiled_rules, record);
+
+        // Una categoría de otro workspace no existe aquí: conservamos la importada
+        let category_exists = record
+            .category_id
+            .as_ref()
+            .is_none_or(|id| import.categories.iter().any(|c| &c.id == id));
+        if !category_exists {
+            record.category_id = original_category;
+        }
+    }
+
+    // 2. Crear el workspace con la estructura estándar
+    let workspace_path = Path::new(&base_path).join(&name);
+    workspace_init::init(
+        base_path.clone(),
+        name.clone(),
+        DEFAULT_LANGUAGE,
+        DEFAULT_CURRENCY,
+    )?;
+    let config_dir = workspace_path.join(".finance");
+
+    // 3. Reemplazar cuentas, categorías y moneda con lo importado
+    if !import.accounts.is_empty() {
+        save_json(
+            config_dir.join("accounts.json"),
+            &AccountsConfig {
+                accounts: import.accounts,
+            },
+        )?;
+    }
+    let accounts_created = load_json::<AccountsConfig>(&config_dir.join("accounts.json"))?
+        .accounts
+        .len();
+
+    let categories_created = import.categories.len();
+    if !import.categories.is_empty() {
+        save_json(
+            config_dir.join("categories.json"),
+            &CategoriesConfig {
+                categories: import.categories,
+            },
+        )?;
+    }
+
+    if let Some(currency) = import.currency {
+        let mut app_config: AppConfig = load_json(&config_dir.join("app.json"))?;
+        app_config.currency = currency;
+        save_json(config_dir.join("app.json"), &app_config)?;
+    }
+
+    if rules_path.is_some() {
+        save_json(config_dir.join("rules.json"), &rules_config)?;
+    }
+
+    // 4. Escribir un archivo por registro
+    for record in &import.records {
+        save_record_item(&workspace_path, record)?;
+    }
+
+    // 5. Indexar y dejarlo como workspace activo
+    activate_workspace(&app, &state, base_path, workspace_path, name)?;
+
+    Ok(ImportReportDto {
+        accounts_created,
+        categories_created,
+        records_created: import.records.len(),
+        skipped: import.skipped,
+    })
+}
+
+#[tauri::command]
+pub async fn export_workspace_archive(
+    state: tauri::State<'_, AppState>,
+    output_path: String,
+) -> Result<ArchiveManifest, AppError> {
+    let path_guard = state.workspace_path.lock().unwrap();
+    let workspace_path = path_guard.as_ref().ok_or(AppError::IoError(
+        "No hay un workspace activo en el estado".into(),
+    ))?;
+
+    workspace_archive::export_workspace_archive(workspace_path, Path::new(&output_path))
+}
+
+#[tauri::command]
+pub async fn import_workspace_archive(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    archive_path: String,
+    base_path: String,
+    name: String,
+) -> Result<ArchiveManifest, AppError> {
+    // 1. Restaurar los archivos verificando hashes y versión del esquema
+    let workspace_path = Path::new(&base_path).join(&name);
+    let manifest =
+        workspace_archive::import_workspace_archive(Path::new(&archive_path), &workspace_path)?;
+
+    // 2. Solo después de verificar todo, indexamos
+    activate_workspace(&app, &state, base_path, workspace_path, name)?;
+
+    Ok(manifest)
+}
+
+// Prepara el almacenamiento local, indexa el workspace y lo guarda en el estado
+pub(crate) fn activate_workspace(
+    app: &AppHandle,
+    state: &AppState,
+    base_path: String,
+    workspace_path: PathBuf,
+    name: String,
+) -> Result<(), AppError> {
+    workspace_schemas::write_schemas(&workspace_path.join(".finance"))?;
+    workspace_schemas::validate_workspace(&workspace_path)?;
+
+    let local_paths = local_storage::prepare_local_storage(app, base_path, name.clone())?;
+
+    let db_path = local_paths.cache_dir.join("cache.sqlite");
+    let mut conn = Connection::open(db_path).map_err(|e| AppError::IoError(e.to_string()))?;
+
+    db_init::init_sqlite(&mut conn).map_err(|e| AppError::IoError(format!("Error DB: {}", e)))?;
+    db_indexer::index_full_workspace(&workspace_path, &mut conn)?;
+
+    let app_data: PathBuf = app.path().app_data_dir().map_err(|_| {
+        AppError::IoError("No se pudo encontrar el directorio de datos de la app".into())
+    })?;
+
+    *state.db.lock().unwrap() = Some(conn);
+    *state.workspace_path.lock().unwrap() = Some(workspace_path);
+    *state.workspace_app_data_dir.lock().unwrap() = Some(app_data.join("workspaces").join(name));
+
+    Ok(())
+}
@@ -1,8 +1,13 @@
+use crate::domain::presets::{
+    preset_accounts, preset_categories, DEFAULT_CURRENCY, DEFAULT_LANGUAGE,
+};
 use crate::helpers::datetime_helpers::timestamp_now;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // --- Version ---
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct VersionConfig {
     pub schema_version: u32,
     pub created_at: i64,
@@ -19,8 +24,24 @@ impl Default for VersionConfig {
     }
 }
 
-// --- App Global ---
+// --- Archive manifest ---
 #[derive(Serialize, Deserialize, Debug)]
+pub struct ArchiveFileEntry {
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ArchiveManifest {
+    pub workspace_name: String,
+    pub created_at: i64,
+    pub version: VersionConfig,
+    pub files: Vec<ArchiveFileEntry>,
+}
+
+// --- App Global ---
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct AppConfig {
     pub currency: String,
     pub language: String,
@@ -28,24 +49,42 @@ pub struct AppConfig {
     pub week_start: String,
     pub created_at: i64,
     pub updated_at: i64,
+    // Unidades de la moneda del workspace por 1 unidad de cada moneda extranjera
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub exchange_rates: BTreeMap<String, f64>,
+    // Notificaciones de escritorio al cruzar umbrales de presupuesto
+    #[serde(default)]
+    pub budget_notifications: bool,
+}
+
+impl AppConfig {
+    pub fn for_workspace(language: &str, currency: &str) -> Self {
+        Self {
+            language: language.into(),
+            currency: currency.into(),
+            ..Self::default()
+        }
+    }
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         let t = timestamp_now();
         Self {
-            currency: "COP".into(),
-            language: "es".into(),
+            currency: DEFAULT_CURRENCY.into(),
+            language: DEFAULT_LANGUAGE.into(),
             theme: "system".into(),
             week_start: "monday".into(),
             created_at: t,
             updated_at: t,
+            exchange_rates: BTreeMap::new(),
+            budget_notifications: false,
         }
     }
 }
 
 // --- Categories ---
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone)]
 pub struct CategoryItem {
     pub id: String,
     pub name: String,
@@ -55,73 +94,56 @@ pub struct CategoryItem {
     pub created_by_user: bool,
     pub is_active: bool,
     pub created_at: i64,
+    // Categoría padre (None = categoría de primer nivel)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct CategoriesConfig {
     pub categories: Vec<CategoryItem>,
 }
 
-impl Default for CategoriesConfig {
-    fn default() -> Self {
-        let t = timestamp_now();
-        let mut categories = Vec::new();
-
-        // Estructura temporal para definir valores por defecto precisos
-        let expenses = vec![
-            ("cat_food", "Comida", "utensils", "#ef4444"), // Red 500
-            ("cat_transport", "Transporte", "car", "#f59e0b"), // Amber 500
-            ("cat_health", "Salud", "heart-pulse", "#ec4899"), // Pink 500
-            ("cat_edu", "Educación", "graduation-cap", "#8b5cf6"), // Violet 500
-            ("cat_auto", "Automóvil", "wrench", "#64748b"), // Slate 500
-            ("cat_home", "Vivienda", "home", "#06b6d4"),   // Cyan 500
-            ("cat_sports", "Deportes", "dumbbell", "#10b981"), // Emerald 500
-            ("cat_entert", "Entretenimiento", "clapperboard", "#f43f5e"), // Rose 500
-            ("cat_pets", "Mascotas", "dog", "#d946ef"),    // Fuchsia 500
-            ("cat_gifts", "Regalos", "gift", "#fb923c"),   // Orange 400
-            ("cat_clothes", "Ropa", "shirt", "#6366f1"),   // Indigo 500
-            ("cat_services", "Servicios", "zap", "#eab308"), // Yellow 500
-            ("cat_taxes", "Impuestos", "receipt", "#475569"), // Slate 600
-        ];
-
-        for (id, name, icon, color) in expenses {
-            categories.push(CategoryItem {
-                id: id.into(),
-                name: name.into(),
-                r#type: "expense".into(),
-                icon: icon.into(),
-                color: color.into(),
-                created_by_user: false,
-                is_active: true,
-                created_at: t,
-            });
-        }
-
-        let incomes = vec![
-            ("cat_salary", "Salario", "banknote", "#22c55e"), // Green 500
-            ("cat_extra", "Ingresos extra", "trending-up", "#34d399"), // Emerald 400
-            ("cat_others", "Otros ingresos", "wallet", "#2dd4bf"), // Teal 400
-        ];
-
-        for (id, name, icon, color) in incomes {
-            categories.push(CategoryItem {
-                id: id.into(),
-                name: name.into(),
-                r#type: "income".into(),
-                icon: icon.into(),
-                color: color.into(),
-                created_by_user: false,
-                is_active: true,
-                created_at: t,
-            });
+impl CategoriesConfig {
+    pub fn for_language(language: &str) -> Self {
+        Self {
+            categories: preset_categories(language, timestamp_now()),
         }
+    }
+}
 
-        Self { categories }
+impl Default for CategoriesConfig {
+    fn default() -> Self {
+        Self::for_language(DEFAULT_LANGUAGE)
     }
 }
 
 // --- Accounts ---
-#[derive(Serialize, Deserialize, Debug)]
+// Configuración propia de las cuentas tipo "credit"
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone)]
+pub struct CreditCardSettings {
+    pub statement_closing_day: u32,
+    pub payment_due_day: u32,
+    pub minimum_payment_percent: Option<f64>,
+}
+
+// Préstamos e hipotecas (cuentas tipo "loan"); tasa anual en porcentaje
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone)]
+pub struct LoanSettings {
+    pub principal: f64,
+    pub annual_interest_rate: f64,
+    pub term_months: u32,
+    pub start_date: i64,
+    pub payment_day: u32,
+    // "french" (cuota fija, por defecto) | "german" (abono a capital fijo)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amortization: Option<String>,
+    // Categoría para los gastos de intereses generados al pagar
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interest_category_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct AccountItem {
     pub id: String,
     pub name: String,
@@ -131,31 +153,37 @@ pub struct AccountItem {
     pub credit_limit: Option<f64>, // Puede ser null
     pub is_active: bool,
     pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credit_card: Option<CreditCardSettings>,
+    // "asset" | "liability"; si falta se deduce del tipo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_class: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loan: Option<LoanSettings>,
+    // Día desde el que rige initial_balance; los registros anteriores ya están incluidos
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opening_date: Option<i64>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct AccountsConfig {
     pub accounts: Vec<AccountItem>,
 }
 
-impl Default for AccountsConfig {
-    fn default() -> Self {
-        let t = timestamp_now();
+impl AccountsConfig {
+    pub fn for_workspace(language: &str, currency: &str) -> Self {
         Self {
-            accounts: vec![AccountItem {
-                id: "acc_cash".into(),
-                name: "Efectivo".into(),
-                r#type: "cash".into(),
-                currency: "COP".into(),
-                initial_balance: 0.0,
-                credit_limit: None,
-                is_active: true,
-                created_at: t,
-            }],
+            accounts: preset_accounts(language, currency, timestamp_now()),
         }
     }
 }
 
+impl Default for AccountsConfig {
+    fn default() -> Self {
+        Self::for_workspace(DEFAULT_LANGUAGE, DEFAULT_CURRENCY)
+    }
+}
+
 // --- Last session ---
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LastSessionCacheConfig {
@@ -163,8 +191,41 @@ pub struct LastSessionCacheConfig {
     pub last_workspace_name: String,
 }
 
+// --- Budget alerts ---
+// Umbral de presupuesto ya avisado en un mes (caché local, no se sincroniza)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShownBudgetAlert {
+    pub month: String,
+    pub category_id: String,
+    pub threshold: u32,
+    pub shown_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BudgetAlertHistory {
+    #[serde(default)]
+    pub shown: Vec<ShownBudgetAlert>,
+}
+
 // -- Transaction/record --
-#[derive(Debug, Serialize, Deserialize)]
+// Compra a cuotas con tarjeta de crédito (tasa mensual en porcentaje)
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct InstallmentPlan {
+    pub count: u32,
+    pub monthly_interest_rate: f64,
+}
+
+// Reparto de un pago de préstamo (va en la transferencia de capital)
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+pub struct LoanPaymentSplit {
+    pub interest: f64,
+    pub principal: f64,
+    // Parte del capital que excede la cuota programada
+    pub extra_principal: f64,
+    pub interest_record_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default)]
 pub struct RecordItem {
     pub id: String,
     pub r#type: String,
@@ -176,4 +237,205 @@ pub struct RecordItem {
     pub to_account_id: Option<String>,
     pub description: Option<String>,
     pub metadata: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installments: Option<InstallmentPlan>,
+    // Momento en que el registro se marcó como conciliado contra un extracto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cleared_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loan_payment: Option<LoanPaymentSplit>,
+}
+
+// -- Rules --
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default, Clone)]
+pub struct RuleConditions {
+    pub description_regex: Option<String>,
+    pub description_contains: Option<String>,
+    pub payee: Option<String>,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    pub account_id: Option<String>,
+    pub record_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default, Clone)]
+pub struct RuleActions {
+    pub category_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub payee: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct RuleItem {
+    pub id: String,
+    pub name: String,
+    pub is_active: bool,
+    pub conditions: RuleConditions,
+    pub actions: RuleActions,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default)]
+pub struct RulesConfig {
+    pub rules: Vec<RuleItem>,
+}
+
+// -- Reconciliation --
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ReconciliationItem {
+    pub id: String,
+    pub account_id: String,
+    pub statement_date: i64,
+    pub statement_balance: f64,
+    pub status: String, // 'open' o 'completed'
+    #[serde(default)]
+    pub selected_record_ids: Vec<String>,
+    pub difference: Option<f64>,
+    pub adjustment_record_id: Option<String>,
+    pub created_at: i64,
+    pub completed_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default)]
+pub struct ReconciliationsConfig {
+    pub sessions: Vec<ReconciliationItem>,
+}
+
+// -- Investments --
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct SecurityItem {
+    pub id: String,
+    pub symbol: String,
+    pub name: String,
+    pub kind: String, // 'stock' | 'fund' | 'cdt' | 'bond' | 'other'
+    pub currency: String,
+}
+
+// Operación sobre un título dentro de una cuenta de inversión
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct InvestmentTransactionItem {
+    pub id: String,
+    pub account_id: String,
+    pub security_id: String,
+    pub kind: String, // 'buy' | 'sell' | 'dividend' | 'interest'
+    #[serde(default)]
+    pub quantity: f64,
+    #[serde(default)]
+    pub price: f64,
+    #[serde(default)]
+    pub fees: f64,
+    // Monto recibido en dividendos e intereses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+    pub timestamp: i64,
+    // Registro de ingreso generado por dividendos e intereses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_id: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct SecurityPriceItem {
+    pub security_id: String,
+    pub date: i64,
+    pub price: f64,
+    pub source: String, // 'manual' | 'csv'
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default)]
+pub struct InvestmentsConfig {
+    #[serde(default)]
+    pub securities: Vec<SecurityItem>,
+    #[serde(default)]
+    pub transactions: Vec<InvestmentTransactionItem>,
+    #[serde(default)]
+    pub prices: Vec<SecurityPriceItem>,
+}
+
+// -- Budgets --
+// Presupuesto mensual de una categoría de gastos; en una categoría padre
+// cubre también sus subcategorías
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct BudgetItem {
+    pub id: String,
+    pub month: String, // 'YYYY-MM'
+    pub category_id: String,
+    pub amount: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// Dinero asignado a un sobre (categoría de gastos) en un mes
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct EnvelopeAssignmentItem {
+    pub id: String,
+    pub month: String, // 'YYYY-MM'
+    pub category_id: String,
+    pub amount: f64,
+    pub updated_at: i64,
+}
+
+// Traslado entre sobres, p. ej. para cubrir un sobregiro
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct EnvelopeMoveItem {
+    pub id: String,
+    pub month: String,
+    pub from_category_id: String,
+    pub to_category_id: String,
+    pub amount: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    pub created_at: i64,
+}
+
+// Presupuesto por sobres: los ingresos desde start_month se reparten entre sobres
+// y lo no gastado (o sobregirado) pasa al mes siguiente
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct EnvelopeBudgetConfig {
+    pub enabled: bool,
+    pub start_month: String,
+    #[serde(default)]
+    pub assignments: Vec<EnvelopeAssignmentItem>,
+    #[serde(default)]
+    pub moves: Vec<EnvelopeMoveItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default)]
+pub struct BudgetsConfig {
+    #[serde(default)]
+    pub budgets: Vec<BudgetItem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub envelopes: Option<EnvelopeBudgetConfig>,
+}
+
+// -- Goals --
+// Meta de ahorro: los aportes son las transferencias hacia la cuenta vinculada
+// y/o los movimientos con la etiqueta, desde start_date
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct GoalItem {
+    pub id: String,
+    pub name: String,
+    pub target_amount: f64,
+    pub target_date: i64,
+    pub start_date: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    pub is_active: bool,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default)]
+pub struct GoalsConfig {
+    #[serde(default)]
+    pub goals: Vec<GoalItem>,
 }
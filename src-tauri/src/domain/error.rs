@@ -18,6 +18,9 @@ pub enum AppError {
 
     #[error("Error al hacer una operación con la base de datos")]
     DatabaseError(String),
+
+    #[error("Archivos del workspace inválidos: {0}")]
+    SchemaError(String),
 }
 
 impl From<std::io::Error> for AppError {
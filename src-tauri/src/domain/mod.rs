@@ -1,2 +1,3 @@
 pub mod error;
-pub mod config_models;
\ No newline at end of file
+pub mod config_models;
+pub mod presets;
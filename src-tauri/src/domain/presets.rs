@@ -0,0 +1,184 @@
+// Categorías y cuentas iniciales de un workspace, según su idioma
+use crate::domain::config_models::{AccountItem, CategoryItem};
+use crate::domain::error::AppError;
+
+pub const DEFAULT_LANGUAGE: &str = "es";
+pub const DEFAULT_CURRENCY: &str = "COP";
+pub const SUPPORTED_LANGUAGES: [&str; 2] = ["es", "en"];
+
+// (id, tipo, icono, color)
+const CATEGORY_PRESETS: &[(&str, &str, &str, &str)] = &[
+    ("cat_food", "expense", "utensils", "#ef4444"), // Red 500
+    ("cat_transport", "expense", "car", "#f59e0b"), // Amber 500
+    ("cat_health", "expense", "heart-pulse", "#ec4899"), // Pink 500
+    ("cat_edu", "expense", "graduation-cap", "#8b5cf6"), // Violet 500
+    ("cat_auto", "expense", "wrench", "#64748b"),   // Slate 500
+    ("cat_home", "expense", "home", "#06b6d4"),     // Cyan 500
+    ("cat_sports", "expense", "dumbbell", "#10b981"), // Emerald 500
+    ("cat_entert", "expense", "clapperboard", "#f43f5e"), // Rose 500
+    ("cat_pets", "expense", "dog", "#d946ef"),      // Fuchsia 500
+    ("cat_gifts", "expense", "gift", "#fb923c"),    // Orange 400
+    ("cat_clothes", "expense", "shirt", "#6366f1"), // Indigo 500
+    ("cat_services", "expense", "zap", "#eab308"),  // Yellow 500
+    ("cat_taxes", "expense", "receipt", "#475569"), // Slate 600
+    ("cat_salary", "income", "banknote", "#22c55e"), // Green 500
+    ("cat_extra", "income", "trending-up", "#34d399"), // Emerald 400
+    ("cat_others", "income", "wallet", "#2dd4bf"),  // Teal 400
+];
+
+// (id, español, inglés)
+const CATEGORY_NAMES: &[(&str, &str, &str)] = &[
+    ("cat_food", "Comida", "Food"),
+    ("cat_transport", "Transporte", "Transport"),
+    ("cat_health", "Salud", "Health"),
+    ("cat_edu", "Educación", "Education"),
+    ("cat_auto", "Automóvil", "Car"),
+    ("cat_home", "Vivienda", "Housing"),
+    ("cat_sports", "Deportes", "Sports"),
+    ("cat_entert", "Entretenimiento", "Entertainment"),
+    ("cat_pets", "Mascotas", "Pets"),
+    ("cat_gifts", "Regalos", "Gifts"),
+    ("cat_clothes", "Ropa", "Clothing"),
+    ("cat_services", "Servicios", "Utilities"),
+    ("cat_taxes", "Impuestos", "Taxes"),
+    ("cat_salary", "Salario", "Salary"),
+    ("cat_extra", "Ingresos extra", "Extra income"),
+    ("cat_others", "Otros ingresos", "Other income"),
+];
+
+/// Normaliza el idioma ("en-US" -> "en") y verifica que tenga presets
+pub fn normalize_language(language: &str) -> Result<String, AppError> {
+    let language = language
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    if SUPPORTED_LANGUAGES.contains(&language.as_str()) {
+        Ok(language)
+    } else {
+        Err(AppError::ConfigError(format!(
+            "Idioma no soportado: {} (disponibles: {})",
+            language,
+            SUPPORTED_LANGUAGES.join(", ")
+        )))
+    }
+}
+
+/// Código ISO 4217 de tres letras en mayúsculas
+pub fn normalize_currency(currency: &str) -> Result<String, AppError> {
+    let currency = currency.trim().to_ascii_uppercase();
+
+    if currency.len() == 3 && currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(currency)
+    } else {
+        Err(AppError::ConfigError(format!(
+            "Moneda inválida: '{}' (use un código de tres letras como COP o USD)",
+            currency
+        )))
+    }
+}
+
+fn localized<'a>(es: &'a str, en: &'a str, language: &str) -> &'a str {
+    match language {
+        "en" => en,
+        _ => es,
+    }
+}
+
+/// Nombre predeterminado de una categoría en el idioma dado
+pub fn preset_category_name(category_id: &str, language: &str) -> Option<&'static str> {
+    CATEGORY_NAMES
+        .iter()
+        .find(|n| n.0 == category_id)
+        .map(|n| localized(n.1, n.2, language))
+}
+
+/// La categoría conserva el nombre predeterminado de alguno de los idiomas
+pub fn has_preset_name(category: &CategoryItem) -> bool {
+    CATEGORY_NAMES
+        .iter()
+        .any(|n| n.0 == category.id && (n.1 == category.name || n.2 == category.name))
+}
+
+pub fn preset_categories(language: &str, created_at: i64) -> Vec<CategoryItem> {
+    CATEGORY_PRESETS
+        .iter()
+        .map(|(id, r#type, icon, color)| CategoryItem {
+            id: (*id).into(),
+            name: preset_category_name(id, language).unwrap_or(id).into(),
+            r#type: (*r#type).into(),
+            icon: (*icon).into(),
+            color: (*color).into(),
+            created_by_user: false,
+            is_active: true,
+            created_at,
+            parent_id: None,
+        })
+        .collect()
+}
+
+pub fn preset_accounts(language: &str, currency: &str, created_at: i64) -> Vec<AccountItem> {
+    vec![AccountItem {
+        id: "acc_cash".into(),
+        name: localized("Efectivo", "Cash", language).into(),
+        r#type: "cash".into(),
+        currency: currency.into(),
+        initial_balance: 0.0,
+        credit_limit: None,
+        is_active: true,
+        created_at,
+        credit_card: None,
+        account_class: None,
+        loan: None,
+        opening_date: None,
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_tags_are_reduced_to_their_base_language() {
+        assert_eq!(normalize_language("en-US").unwrap(), "en");
+        assert_eq!(normalize_language(" ES_co ").unwrap(), "es");
+        assert!(matches!(
+            normalize_language("fr"),
+            Err(AppError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn currency_must_be_a_three_letter_code() {
+        assert_eq!(normalize_currency(" usd ").unwrap(), "USD");
+        assert!(normalize_currency("US").is_err());
+        assert!(normalize_currency("U$D").is_err());
+    }
+
+    #[test]
+    fn every_preset_category_has_a_name_in_each_language() {
+        for language in SUPPORTED_LANGUAGES {
+            let categories = preset_categories(language, 0);
+            assert_eq!(categories.len(), CATEGORY_PRESETS.len());
+            assert!(categories
+                .iter()
+                .all(|c| c.name != c.id && !c.created_by_user));
+        }
+        assert_eq!(preset_category_name("cat_home", "en"), Some("Housing"));
+        assert_eq!(preset_accounts("en", "USD", 0)[0].name, "Cash");
+    }
+
+    #[test]
+    fn renamed_categories_lose_their_preset_name() {
+        let mut category = preset_categories("es", 0).remove(0);
+        assert!(has_preset_name(&category));
+
+        category.name = "Food".into();
+        assert!(has_preset_name(&category));
+
+        category.name = "Mercado".into();
+        assert!(!has_preset_name(&category));
+    }
+}
@@ -0,0 +1,44 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct BalancePointDto {
+    // Fecha local (YYYY-MM-DD) del cierre del periodo
+    pub date: String,
+    pub timestamp: i64,
+    pub balance: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountBalanceHistoryDto {
+    pub account_id: String,
+    // Saldo justo antes del inicio del rango
+    pub opening_balance: f64,
+    pub points: Vec<BalancePointDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LedgerEntryDto {
+    pub id: String,
+    pub r#type: String,
+    pub timestamp: i64,
+    pub amount: f64,
+    // Efecto sobre el saldo de la cuenta (negativo si sale dinero)
+    pub signed_amount: f64,
+    pub description: Option<String>,
+    pub category_name: Option<String>,
+    // Cuenta contraria en transferencias
+    pub counterpart_account_name: Option<String>,
+    pub cleared: bool,
+    // Saldo de la cuenta después de aplicar este registro
+    pub running_balance: f64,
+}
+
+// Cuenta con registros fechados antes de su apertura (no cuentan en el saldo)
+#[derive(Debug, Serialize)]
+pub struct OpeningDateWarningDto {
+    pub account_id: String,
+    pub account_name: String,
+    pub opening_date: i64,
+    pub records_before_opening: i64,
+    pub earliest_timestamp: i64,
+}
@@ -1,6 +1,7 @@
+use crate::domain::config_models::{CreditCardSettings, LoanSettings};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct AccountInfoDto {
     pub id: String,
     pub name: String,
@@ -9,4 +10,14 @@ pub struct AccountInfoDto {
     pub currency: Option<String>,
     pub initial_balance: Option<f64>,
     pub credit_limit: Option<f64>,
+    pub is_active: Option<bool>,
+    pub credit_card: Option<CreditCardSettings>,
+    // Clase efectiva (solo lectura)
+    pub account_class: Option<String>,
+    // "asset" | "liability" fija la clase, "auto" vuelve a deducirla del tipo
+    pub account_class_override: Option<String>,
+    pub loan: Option<LoanSettings>,
+    // Valor de mercado de las posiciones (incluido en balance)
+    pub market_value: Option<f64>,
+    pub opening_date: Option<i64>,
 }
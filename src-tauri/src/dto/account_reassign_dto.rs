@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct AccountReassignReportDto {
+    pub records_moved: usize,
+    // Transferencias entre la cuenta borrada y la destino (quedarían de la cuenta a sí misma)
+    pub self_transfers_removed: usize,
+    pub investment_transactions_moved: usize,
+}
@@ -0,0 +1,30 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct BudgetStatusDto {
+    pub budget_id: String,
+    pub month: String,
+    pub category_id: String,
+    pub category_name: String,
+    pub color: String,
+    // La categoría tiene subcategorías y el gasto las incluye
+    pub is_group: bool,
+    pub notes: Option<String>,
+    pub budgeted: f64,
+    pub spent: f64,
+    pub remaining: f64,
+    pub percent_used: f64,
+}
+
+// Payload del evento "budget-alert"
+#[derive(Debug, Serialize, Clone)]
+pub struct BudgetAlertDto {
+    pub month: String,
+    pub category_id: String,
+    pub category_name: String,
+    // 80 o 100 (% del presupuesto)
+    pub threshold: u32,
+    pub budgeted: f64,
+    pub spent: f64,
+    pub percent_used: f64,
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+// Filtros del desglose por categoría. El periodo es `period` (con nombre) o
+// start/end explícitos (timestamps inclusive); sin ninguno, el mes actual.
+#[derive(Debug, Deserialize)]
+pub struct CategoryBreakdownQueryDto {
+    // "expense" o "income"
+    #[serde(rename = "type")]
+    pub r#type: String,
+    pub period: Option<String>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub account_ids: Option<Vec<String>>,
+    // Solo registros en esta moneda (la del registro o la de su cuenta);
+    // obligatoria si el periodo tiene registros en varias monedas
+    pub currency: Option<String>,
+    // Profundizar en las subcategorías de esta categoría
+    pub parent_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryBreakdownItemDto {
+    // None: registros sin categoría (o con una categoría que ya no existe)
+    pub category_id: Option<String>,
+    pub category_name: String,
+    pub color: String,
+    pub is_active: bool,
+    pub amount: f64,
+    pub count: i64,
+    pub percentage: f64,
+    // Incluye montos de subcategorías (se puede profundizar)
+    pub has_children: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryBreakdownDto {
+    #[serde(rename = "type")]
+    pub r#type: String,
+    pub start: i64,
+    pub end: i64,
+    pub currency: Option<String>,
+    pub total: f64,
+    pub count: i64,
+    pub categories: Vec<CategoryBreakdownItemDto>,
+}
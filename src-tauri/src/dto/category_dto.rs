@@ -8,4 +8,28 @@ pub struct CategoryDto {
     pub icon: String,
     pub color: String,
     pub is_active: bool,
+    // Las categorías predeterminadas solo se pueden deshabilitar
+    #[serde(default)]
+    pub created_by_user: bool,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+}
+
+// Categoría con sus subcategorías
+#[derive(Debug, Serialize)]
+pub struct CategoryTreeDto {
+    #[serde(flatten)]
+    pub category: CategoryDto,
+    pub children: Vec<CategoryTreeDto>,
+}
+
+// Datos editables al crear o modificar una categoría
+#[derive(Debug, Deserialize)]
+pub struct SaveCategoryDto {
+    pub name: String,
+    pub r#type: String,
+    pub icon: String,
+    pub color: String,
+    #[serde(default)]
+    pub parent_id: Option<String>,
 }
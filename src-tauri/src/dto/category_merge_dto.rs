@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct CategoryMergeReportDto {
+    pub records_moved: usize,
+    pub rules_updated: usize,
+    // Presupuestos y movimientos de sobres trasladados a la categoría destino
+    pub budgets_updated: usize,
+    // Préstamos que usaban la categoría para los intereses
+    pub loans_updated: usize,
+    pub subcategories_moved: usize,
+    // false: la categoría fusionada quedó deshabilitada
+    pub merged_removed: bool,
+}
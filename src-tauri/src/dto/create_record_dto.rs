@@ -10,4 +10,9 @@ pub struct CreateRecordDto {
     pub category_id: Option<String>,
     pub description: Option<String>,
     pub timestamp: i64,
+    // Aplicar las reglas de auto-categorización antes de guardar
+    pub apply_rules: Option<bool>,
+    // Compra a cuotas (solo gastos en tarjetas de crédito)
+    pub installments: Option<u32>,
+    pub monthly_interest_rate: Option<f64>,
 }
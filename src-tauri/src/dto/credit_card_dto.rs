@@ -0,0 +1,24 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct CreditCardStatementDto {
+    pub period_start: i64,
+    pub period_end: i64,
+    pub due_date: i64,
+    pub purchases: f64,
+    pub payments: f64,
+    pub closing_balance: f64,
+    pub minimum_payment: f64,
+    pub paid_towards_statement: f64,
+    pub minimum_payment_met: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreditCardSummaryDto {
+    pub account_id: String,
+    pub credit_limit: f64,
+    pub owed_balance: f64,
+    pub available_credit: f64,
+    pub current_cycle: CreditCardStatementDto,
+    pub last_statement: CreditCardStatementDto,
+}
@@ -0,0 +1,27 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct EnvelopeBalanceDto {
+    pub category_id: String,
+    pub category_name: String,
+    pub color: String,
+    // Saldo que viene del mes anterior (negativo si quedó sobregirado)
+    pub carried_over: f64,
+    pub assigned: f64,
+    // Traslados recibidos menos traslados enviados
+    pub moved: f64,
+    pub spent: f64,
+    pub available: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnvelopeMonthDto {
+    pub month: String,
+    pub income: f64,
+    pub assigned: f64,
+    // Gastos sin categoría: salen directamente de lo disponible para asignar
+    pub unassigned_spent: f64,
+    // Ingresos acumulados menos todo lo asignado y lo gastado sin sobre
+    pub to_be_assigned: f64,
+    pub envelopes: Vec<EnvelopeBalanceDto>,
+}
@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct SkippedRecordDto {
+    pub record_id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportReportDto {
+    pub path: String,
+    pub skipped: Vec<SkippedRecordDto>,
+}
@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct GoalProgressDto {
+    pub id: String,
+    pub name: String,
+    pub target_amount: f64,
+    pub target_date: i64,
+    pub start_date: i64,
+    pub account_id: Option<String>,
+    pub tag: Option<String>,
+    pub color: Option<String>,
+    pub is_active: bool,
+    // Aportes netos desde start_date
+    pub contributed: f64,
+    pub remaining: f64,
+    pub progress_percent: f64,
+    // Meses que faltan hasta target_date contando el actual (0 si ya pasó)
+    pub months_left: u32,
+    // Aporte mensual necesario para llegar a la meta en la fecha
+    pub required_monthly: f64,
+    pub is_completed: bool,
+}
@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct SkippedEntryDto {
+    pub line: usize,
+    pub reason: String,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReportDto {
+    pub accounts_created: usize,
+    pub categories_created: usize,
+    pub records_created: usize,
+    pub skipped: Vec<SkippedEntryDto>,
+}
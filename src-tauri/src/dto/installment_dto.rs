@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct InstallmentDto {
+    pub number: u32,
+    pub due_timestamp: i64,
+    pub principal: f64,
+    pub interest: f64,
+    pub amount: f64,
+    pub remaining_principal: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstallmentPurchaseDto {
+    pub record_id: String,
+    pub description: Option<String>,
+    pub purchase_timestamp: i64,
+    pub total_amount: f64,
+    pub installments_total: u32,
+    pub installments_billed: u32,
+    pub outstanding_principal: f64,
+    pub pending_installments: Vec<InstallmentDto>,
+}
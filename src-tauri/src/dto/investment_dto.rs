@@ -0,0 +1,76 @@
+use super::import_report_dto::SkippedEntryDto;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvestmentTransactionDto {
+    pub account_id: String,
+    pub security_id: String,
+    pub kind: String,
+    pub quantity: Option<f64>,
+    pub price: Option<f64>,
+    pub fees: Option<f64>,
+    // Dividendos e intereses
+    pub amount: Option<f64>,
+    pub category_id: Option<String>,
+    pub description: Option<String>,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HoldingDto {
+    pub account_id: String,
+    pub security_id: String,
+    pub symbol: String,
+    pub name: String,
+    pub quantity: f64,
+    pub cost_basis: f64,
+    pub average_cost: f64,
+    pub price: f64,
+    // None cuando no hay precio registrado y se usa el de la última operación
+    pub price_date: Option<i64>,
+    pub market_value: f64,
+    pub unrealized_gain: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RealizedGainDto {
+    pub transaction_id: String,
+    pub account_id: String,
+    pub security_id: String,
+    pub symbol: String,
+    pub timestamp: i64,
+    pub quantity: f64,
+    pub proceeds: f64,
+    pub cost_basis: f64,
+    pub gain: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvestmentGainsReportDto {
+    pub method: String,
+    pub realized: Vec<RealizedGainDto>,
+    pub total_realized: f64,
+    pub total_unrealized: f64,
+    pub dividends: f64,
+    pub interest: f64,
+    pub holdings: Vec<HoldingDto>,
+}
+
+// Título en una moneda distinta de la de su cuenta (no suma al saldo)
+#[derive(Debug, Serialize)]
+pub struct InvestmentCurrencyWarningDto {
+    pub account_id: String,
+    pub account_name: String,
+    pub account_currency: String,
+    pub security_id: String,
+    pub symbol: String,
+    pub security_currency: String,
+    pub transaction_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PriceImportReportDto {
+    pub imported: usize,
+    pub unknown_symbols: Vec<String>,
+    pub skipped: Vec<SkippedEntryDto>,
+}
@@ -0,0 +1,35 @@
+use crate::domain::config_models::LoanSettings;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LoanScheduleRowDto {
+    pub number: u32,
+    pub due_date: i64,
+    pub payment: f64,
+    pub interest: f64,
+    pub principal: f64,
+    pub extra_principal: f64,
+    pub remaining_balance: f64,
+}
+
+// Abono extraordinario a capital (registrado o simulado)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtraPaymentDto {
+    pub timestamp: i64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoanSummaryDto {
+    pub account_id: String,
+    pub settings: LoanSettings,
+    pub scheduled_payment: f64,
+    pub outstanding_balance: f64,
+    pub interest_paid: f64,
+    pub principal_paid: f64,
+    pub extra_principal_paid: f64,
+    pub payments_made: u32,
+    pub next_payment: Option<LoanScheduleRowDto>,
+    pub payoff_date: Option<i64>,
+    pub schedule: Vec<LoanScheduleRowDto>,
+}
@@ -1,7 +1,23 @@
+pub mod account_history_dto;
 pub mod account_info_dto;
+pub mod account_reassign_dto;
+pub mod budget_dto;
+pub mod category_breakdown_dto;
 pub mod category_dto;
+pub mod category_merge_dto;
 pub mod create_record_dto;
+pub mod credit_card_dto;
+pub mod envelope_dto;
+pub mod export_report_dto;
+pub mod goal_dto;
+pub mod import_report_dto;
+pub mod installment_dto;
+pub mod investment_dto;
+pub mod loan_dto;
 pub mod local_paths_dto;
+pub mod net_worth_dto;
 pub mod pagination_dto;
+pub mod reconciliation_dto;
 pub mod record_dto;
+pub mod rule_match_dto;
 pub mod workspace_context_dto;
@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct NetWorthByTypeDto {
+    pub account_type: String,
+    pub account_class: String,
+    // Activos: saldo; pasivos: monto adeudado (positivo)
+    pub total: f64,
+    pub account_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NetWorthDto {
+    pub currency: String,
+    pub total_assets: f64,
+    pub total_liabilities: f64,
+    pub net_worth: f64,
+    pub by_type: Vec<NetWorthByTypeDto>,
+    // Monedas sin tasa de cambio; sus cuentas no suman a los totales
+    pub missing_rates: Vec<String>,
+}
@@ -0,0 +1,30 @@
+use crate::domain::config_models::ReconciliationItem;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct ReconcileRecordDto {
+    pub id: String,
+    pub r#type: String,
+    pub amount: f64,
+    // Efecto sobre el saldo de la cuenta conciliada (negativo si sale dinero)
+    pub signed_amount: f64,
+    pub timestamp: i64,
+    pub description: Option<String>,
+    pub selected: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReconciliationStatusDto {
+    pub session: ReconciliationItem,
+    pub cleared_balance: f64,
+    pub difference: f64,
+    pub records: Vec<ReconcileRecordDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountReconciliationSummaryDto {
+    pub account_id: String,
+    pub last_reconciled_at: i64,
+    pub statement_date: i64,
+    pub statement_balance: f64,
+}
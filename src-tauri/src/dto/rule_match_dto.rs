@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct RuleMatchDto {
+    pub record_id: String,
+    pub timestamp: i64,
+    pub amount: f64,
+    pub description: Option<String>,
+    pub current_category_id: Option<String>,
+    pub new_category_id: Option<String>,
+    pub would_change: bool,
+}
@@ -28,6 +28,13 @@ pub fn add_account_to_list(
         credit_limit: new_account.credit_limit.clone(), // El formulario básico no lo pide, se deja en None
         is_active: true,
         created_at: timestamp_now(),
+        credit_card: new_account.credit_card.clone(),
+        account_class: new_account
+            .account_class_override
+            .clone()
+            .filter(|c| c != "auto"),
+        loan: new_account.loan.clone(),
+        opening_date: new_account.opening_date,
     };
 
     // 3. Agregar el nuevo objeto a la lista en memoria
@@ -76,6 +83,42 @@ pub fn update_account_in_json(
         account.credit_limit = Some(credit_limit);
     }
 
+    if let Some(credit_card) = updated_account.credit_card.clone() {
+        account.credit_card = Some(credit_card);
+    }
+
+    if let Some(opening_date) = updated_account.opening_date {
+        account.opening_date = Some(opening_date);
+    }
+
+    if let Some(loan) = updated_account.loan.clone() {
+        account.loan = Some(loan);
+    }
+
+    if let Some(class_override) = &updated_account.account_class_override {
+        account.account_class = Some(class_override.clone()).filter(|c| c != "auto");
+    }
+
+    save_json(file_path, &accounts_config)?;
+
+    Ok(())
+}
+
+pub fn set_account_active_in_json(
+    file_path: PathBuf,
+    account_id: &str,
+    is_active: bool,
+) -> Result<(), AppError> {
+    let mut accounts_config: AccountsConfig = load_json(&file_path)?;
+
+    let account = accounts_config
+        .accounts
+        .iter_mut()
+        .find(|a| a.id == account_id)
+        .ok_or_else(|| AppError::NotFound(format!("Cuenta {} no encontrada", account_id)))?;
+
+    account.is_active = is_active;
+
     save_json(file_path, &accounts_config)?;
 
     Ok(())
@@ -0,0 +1,59 @@
+use crate::domain::config_models::{BudgetItem, BudgetsConfig};
+use crate::domain::error::AppError;
+use crate::helpers::json_helpers::{load_json, save_json};
+use std::path::Path;
+
+// Presupuestos del workspace; los workspaces antiguos tienen un budgets.json vacío ({})
+pub fn load_budgets(workspace_path: &Path) -> Result<BudgetsConfig, AppError> {
+    let file_path = workspace_path.join(".finance").join("budgets.json");
+    if file_path.exists() {
+        load_json(&file_path)
+    } else {
+        Ok(BudgetsConfig::default())
+    }
+}
+
+pub fn save_budgets(workspace_path: &Path, budgets: &BudgetsConfig) -> Result<(), AppError> {
+    save_json(
+        workspace_path.join(".finance").join("budgets.json"),
+        budgets,
+    )
+}
+
+// Un presupuesto por categoría y mes: reemplaza el existente o lo agrega
+pub fn upsert_budget(workspace_path: &Path, budget: BudgetItem) -> Result<(), AppError> {
+    let mut budgets_config = load_budgets(workspace_path)?;
+
+    match budgets_config
+        .budgets
+        .iter_mut()
+        .find(|b| b.month == budget.month && b.category_id == budget.category_id)
+    {
+        Some(existing) => *existing = budget,
+        None => budgets_config.budgets.push(budget),
+    }
+
+    save_budgets(workspace_path, &budgets_config)
+}
+
+pub fn remove_budget(
+    workspace_path: &Path,
+    month: &str,
+    category_id: &str,
+) -> Result<(), AppError> {
+    let mut budgets_config = load_budgets(workspace_path)?;
+
+    let before = budgets_config.budgets.len();
+    budgets_config
+        .budgets
+        .retain(|b| !(b.month == month && b.category_id == category_id));
+
+    if budgets_config.budgets.len() == before {
+        return Err(AppError::NotFound(format!(
+            "No hay presupuesto para la categoría {} en {}",
+            category_id, month
+        )));
+    }
+
+    save_budgets(workspace_path, &budgets_config)
+}
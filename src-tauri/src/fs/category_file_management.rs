@@ -0,0 +1,50 @@
+use crate::domain::config_models::{CategoriesConfig, CategoryItem};
+use crate::domain::error::AppError;
+use crate::helpers::json_helpers::{load_json, save_json};
+use std::path::Path;
+
+pub fn load_categories(workspace_path: &Path) -> Result<CategoriesConfig, AppError> {
+    load_json(&workspace_path.join(".finance").join("categories.json"))
+}
+
+pub fn save_categories(
+    workspace_path: &Path,
+    categories: &CategoriesConfig,
+) -> Result<(), AppError> {
+    save_json(
+        workspace_path.join(".finance").join("categories.json"),
+        categories,
+    )
+}
+
+// Crea la categoría si no existe o reemplaza la que tenga el mismo id
+pub fn upsert_category(workspace_path: &Path, category: CategoryItem) -> Result<(), AppError> {
+    let mut categories_config = load_categories(workspace_path)?;
+
+    match categories_config
+        .categories
+        .iter_mut()
+        .find(|c| c.id == category.id)
+    {
+        Some(existing) => *existing = category,
+        None => categories_config.categories.push(category),
+    }
+
+    save_categories(workspace_path, &categories_config)
+}
+
+pub fn remove_category(workspace_path: &Path, category_id: &str) -> Result<(), AppError> {
+    let mut categories_config = load_categories(workspace_path)?;
+
+    let before = categories_config.categories.len();
+    categories_config.categories.retain(|c| c.id != category_id);
+
+    if categories_config.categories.len() == before {
+        return Err(AppError::NotFound(format!(
+            "Categoría {} no encontrada",
+            category_id
+        )));
+    }
+
+    save_categories(workspace_path, &categories_config)
+}
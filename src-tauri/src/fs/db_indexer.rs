@@ -1,5 +1,13 @@
-use crate::domain::config_models::{AccountsConfig, CategoriesConfig, RecordItem};
+use crate::domain::config_models::{AccountsConfig, CategoriesConfig};
 use crate::domain::error::AppError;
+use crate::fs::goal_file_management::load_goals;
+use crate::fs::investment_file_management::load_investments;
+use crate::fs::record_file_management::load_all_records;
+use crate::services::goals::index_goals;
+use crate::services::installments::index_installments;
+use crate::services::investments::index_investments;
+use crate::services::loans::{index_loan_payment, save_loan_settings};
+use crate::services::records::index_record_tags;
 use rusqlite::{params, Connection};
 use std::path::Path;
 use std::{fs, path};
@@ -10,6 +18,21 @@ pub fn index_full_workspace(workspace_path: &Path, conn: &mut Connection) -> Res
         .map_err(|e| AppError::IoError(e.to_string()))?;
     let finance_dir = workspace_path.join(".finance");
 
+    // 0. La caché se reconstruye desde los archivos: lo que ya no exista en
+    // ellos (p. ej. un registro borrado con la app cerrada) no debe quedar
+    for table in [
+        "records",
+        "record_installments",
+        "loan_payments",
+        "record_tags",
+        "loans",
+        "accounts",
+        "categories",
+    ] {
+        tx.execute(&format!("DELETE FROM {}", table), [])
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    }
+
     // 1. Indexar Categorías
     let cat_json = fs::read_to_string(finance_dir.join("categories.json"))?;
     let cat_data: CategoriesConfig = serde_json::from_str(&cat_json)
@@ -17,8 +40,8 @@ pub fn index_full_workspace(workspace_path: &Path, conn: &mut Connection) -> Res
 
     for cat in cat_data.categories {
         tx.execute(
-            "INSERT OR REPLACE INTO categories (id, name, type, icon, color, is_active) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![cat.id, cat.name, cat.r#type, cat.icon, cat.color, cat.is_active],
+            "INSERT OR REPLACE INTO categories (id, name, type, icon, color, is_active, created_by_user, parent_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![cat.id, cat.name, cat.r#type, cat.icon, cat.color, cat.is_active, cat.created_by_user, cat.parent_id],
         ).map_err(|e| AppError::IoError(e.to_string()))?;
     }
 
@@ -29,31 +52,48 @@ pub fn index_full_workspace(workspace_path: &Path, conn: &mut Connection) -> Res
 
     for acc in acc_data.accounts {
         tx.execute(
-            "INSERT OR REPLACE INTO accounts (id, name, type, currency, initial_balance, credit_limit, is_active, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![acc.id, acc.name, acc.r#type, acc.currency, acc.initial_balance, acc.credit_limit, acc.is_active, acc.created_at],
+            "INSERT OR REPLACE INTO accounts (id, name, type, currency, initial_balance, credit_limit, is_active, created_at, statement_closing_day, payment_due_day, minimum_payment_percent, account_class, opening_date) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                acc.id,
+                acc.name,
+                acc.r#type,
+                acc.currency,
+                acc.initial_balance,
+                acc.credit_limit,
+                acc.is_active,
+                acc.created_at,
+                acc.credit_card.as_ref().map(|c| c.statement_closing_day),
+                acc.credit_card.as_ref().map(|c| c.payment_due_day),
+                acc.credit_card.as_ref().and_then(|c| c.minimum_payment_percent),
+                acc.account_class,
+                acc.opening_date,
+            ],
         ).map_err(|e| AppError::IoError(e.to_string()))?;
-    }
 
-    // 3. Indexar Transacciones (Carpeta records/)
-    let records_dir = workspace_path.join("records");
-    if records_dir.exists() {
-        for entry in fs::read_dir(records_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            // Solo procesamos archivos .json
-            if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                let rec_json = fs::read_to_string(&path)?;
-                if let Ok(record) = serde_json::from_str::<RecordItem>(&rec_json) {
-                    tx.execute(
-                        "INSERT OR REPLACE INTO records (id, type, timestamp, amount, account_id, to_account_id, category_id, description, file_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-                        params![record.id, record.r#type, record.timestamp, record.amount, record.account_id, record.to_account_id,record.category_id, record.description, path.to_str().unwrap()],
-                    ).map_err(|e| AppError::IoError(e.to_string()))?;
-                }
-            }
+        if let Some(loan) = &acc.loan {
+            save_loan_settings(&tx, &acc.id, loan)?;
         }
     }
 
+    // 3. Indexar Transacciones (Carpeta records/); un archivo ilegible detiene el indexado
+    for (path, record) in load_all_records(workspace_path)? {
+        tx.execute(
+            "INSERT OR REPLACE INTO records (id, type, timestamp, amount, account_id, to_account_id, category_id, description, file_path, cleared_at, currency) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![record.id, record.r#type, record.timestamp, record.amount, record.account_id, record.to_account_id,record.category_id, record.description, path.to_string_lossy(), record.cleared_at, record.currency],
+        ).map_err(|e| AppError::IoError(e.to_string()))?;
+
+        // Cuotas de compras con tarjeta
+        index_installments(&tx, &record)?;
+        index_loan_payment(&tx, &record)?;
+        index_record_tags(&tx, &record)?;
+    }
+
+    // 4. Indexar inversiones
+    index_investments(&tx, &load_investments(workspace_path)?)?;
+
+    // 5. Indexar metas de ahorro
+    index_goals(&tx, &load_goals(workspace_path)?)?;
+
     tx.commit().map_err(|e| AppError::IoError(e.to_string()))?;
     Ok(())
 }
@@ -33,6 +33,12 @@ pub fn init_sqlite(conn: &mut Connection) -> Result<()> {
         [],
     )?;
 
+    // Las predeterminadas (0) no se pueden eliminar
+    add_column_if_missing(conn, "categories", "created_by_user", "INTEGER DEFAULT 0")?;
+
+    // Subcategorías: NULL = categoría de primer nivel
+    add_column_if_missing(conn, "categories", "parent_id", "TEXT")?;
+
     // Tabla de Transacciones (Records)
     conn.execute(
         "CREATE TABLE IF NOT EXISTS accounts (
@@ -50,18 +56,18 @@ pub fn init_sqlite(conn: &mut Connection) -> Result<()> {
     )?;
 
     // Add credit_limit column if it doesn't exist (for existing databases)
-    let column_exists: bool = conn
-        .query_row(
-            "SELECT COUNT(*) FROM pragma_table_info('accounts') WHERE name = 'credit_limit'",
-            [],
-            |row| row.get::<_, i32>(0),
-        )
-        .map(|count| count > 0)
-        .unwrap_or(false);
+    add_column_if_missing(conn, "accounts", "credit_limit", "REAL")?;
 
-    if !column_exists {
-        conn.execute("ALTER TABLE accounts ADD COLUMN credit_limit REAL;", [])?;
-    }
+    // Tarjetas de crédito: ciclo de facturación
+    add_column_if_missing(conn, "accounts", "statement_closing_day", "INTEGER")?;
+    add_column_if_missing(conn, "accounts", "payment_due_day", "INTEGER")?;
+    add_column_if_missing(conn, "accounts", "minimum_payment_percent", "REAL")?;
+
+    // Clase contable forzada por el usuario (NULL = según el tipo)
+    add_column_if_missing(conn, "accounts", "account_class", "TEXT")?;
+
+    // Fecha desde la que rige el saldo inicial (NULL = desde siempre)
+    add_column_if_missing(conn, "accounts", "opening_date", "INTEGER")?;
 
     conn.execute(
         "
@@ -91,6 +97,143 @@ pub fn init_sqlite(conn: &mut Connection) -> Result<()> {
         [],
     )?;
 
+    // Conciliación: registros ya verificados contra un extracto
+    add_column_if_missing(conn, "records", "cleared_at", "INTEGER")?;
+
+    // Moneda propia del registro (NULL = la de su cuenta)
+    add_column_if_missing(conn, "records", "currency", "TEXT")?;
+
+    // Cuotas de compras con tarjeta (derivadas del plan del registro)
+    conn.execute(
+        "
+        CREATE TABLE IF NOT EXISTS record_installments (
+        record_id TEXT NOT NULL,
+        number INTEGER NOT NULL,
+        due_timestamp INTEGER NOT NULL,
+        principal REAL NOT NULL,
+        interest REAL NOT NULL,
+        amount REAL NOT NULL,
+        PRIMARY KEY (record_id, number)
+        );
+    ",
+        [],
+    )?;
+
+    // Préstamos: condiciones del crédito por cuenta
+    conn.execute(
+        "
+        CREATE TABLE IF NOT EXISTS loans (
+        account_id TEXT PRIMARY KEY,
+        principal REAL NOT NULL,
+        annual_interest_rate REAL NOT NULL,
+        term_months INTEGER NOT NULL,
+        start_date INTEGER NOT NULL,
+        payment_day INTEGER NOT NULL,
+        amortization TEXT,
+        interest_category_id TEXT
+        );
+    ",
+        [],
+    )?;
+
+    // Pagos de préstamos ya repartidos entre interés y capital
+    conn.execute(
+        "
+        CREATE TABLE IF NOT EXISTS loan_payments (
+        record_id TEXT PRIMARY KEY,
+        loan_account_id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        interest REAL NOT NULL,
+        principal REAL NOT NULL,
+        extra_principal REAL NOT NULL
+        );
+    ",
+        [],
+    )?;
+
+    // Inversiones: títulos, operaciones y precios (fuente: investments.json)
+    conn.execute(
+        "
+        CREATE TABLE IF NOT EXISTS securities (
+        id TEXT PRIMARY KEY,
+        symbol TEXT NOT NULL,
+        name TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        currency TEXT NOT NULL
+        );
+    ",
+        [],
+    )?;
+
+    // cash_amount: efecto de la operación sobre el efectivo de la cuenta
+    conn.execute(
+        "
+        CREATE TABLE IF NOT EXISTS investment_transactions (
+        id TEXT PRIMARY KEY,
+        account_id TEXT NOT NULL,
+        security_id TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        quantity REAL NOT NULL,
+        price REAL NOT NULL,
+        fees REAL NOT NULL,
+        amount REAL,
+        cash_amount REAL NOT NULL,
+        timestamp INTEGER NOT NULL
+        );
+    ",
+        [],
+    )?;
+
+    conn.execute(
+        "
+        CREATE TABLE IF NOT EXISTS security_prices (
+        security_id TEXT NOT NULL,
+        date INTEGER NOT NULL,
+        price REAL NOT NULL,
+        PRIMARY KEY (security_id, date)
+        );
+    ",
+        [],
+    )?;
+
+    // Metas de ahorro (fuente: goals.json)
+    conn.execute(
+        "
+        CREATE TABLE IF NOT EXISTS goals (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        target_amount REAL NOT NULL,
+        target_date INTEGER NOT NULL,
+        start_date INTEGER NOT NULL,
+        account_id TEXT,
+        tag TEXT,
+        color TEXT,
+        is_active INTEGER NOT NULL DEFAULT 1
+        );
+    ",
+        [],
+    )?;
+
+    // Gastos para reportes: una compra a cuotas cuenta por cada cuota (capital más
+    // intereses) en su mes; la tarjeta carga esos intereses en la misma fecha.
+    // Se recrea para actualizar cachés viejas.
+    conn.execute("DROP VIEW IF EXISTS expense_entries", [])?;
+    conn.execute(
+        "
+        CREATE VIEW expense_entries AS
+        SELECT r.id AS record_id, r.timestamp, r.amount, r.category_id, r.account_id, 0 AS is_installment, r.currency
+        FROM records r
+        WHERE r.type = 'expense'
+          AND NOT EXISTS (SELECT 1 FROM record_installments i WHERE i.record_id = r.id)
+        UNION ALL
+        SELECT r.id, i.due_timestamp, i.amount, r.category_id, r.account_id, 1, r.currency
+        FROM record_installments i
+        JOIN records r ON r.id = i.record_id
+        WHERE r.type = 'expense';
+    ",
+        [],
+    )?;
+
     conn.execute(
         "
         CREATE TABLE IF NOT EXISTS record_tags (
@@ -130,3 +273,32 @@ pub fn init_sqlite(conn: &mut Connection) -> Result<()> {
 
     Ok(())
 }
+
+// Migración simple: agrega la columna solo si la tabla aún no la tiene
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let column_exists: bool = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?1",
+                table
+            ),
+            [column],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|count| count > 0)
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {};", table, column, definition),
+            [],
+        )?;
+    }
+
+    Ok(())
+}
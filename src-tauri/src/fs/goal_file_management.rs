@@ -0,0 +1,18 @@
+use crate::domain::config_models::GoalsConfig;
+use crate::domain::error::AppError;
+use crate::helpers::json_helpers::{load_json, save_json};
+use std::path::Path;
+
+// Metas de ahorro; los workspaces antiguos no tienen goals.json
+pub fn load_goals(workspace_path: &Path) -> Result<GoalsConfig, AppError> {
+    let file_path = workspace_path.join(".finance").join("goals.json");
+    if file_path.exists() {
+        load_json(&file_path)
+    } else {
+        Ok(GoalsConfig::default())
+    }
+}
+
+pub fn save_goals(workspace_path: &Path, goals: &GoalsConfig) -> Result<(), AppError> {
+    save_json(workspace_path.join(".finance").join("goals.json"), goals)
+}
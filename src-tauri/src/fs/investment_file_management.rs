@@ -0,0 +1,24 @@
+use crate::domain::config_models::InvestmentsConfig;
+use crate::domain::error::AppError;
+use crate::helpers::json_helpers::{load_json, save_json};
+use std::path::Path;
+
+// Títulos, operaciones y precios; los workspaces antiguos no tienen investments.json
+pub fn load_investments(workspace_path: &Path) -> Result<InvestmentsConfig, AppError> {
+    let file_path = workspace_path.join(".finance").join("investments.json");
+    if file_path.exists() {
+        load_json(&file_path)
+    } else {
+        Ok(InvestmentsConfig::default())
+    }
+}
+
+pub fn save_investments(
+    workspace_path: &Path,
+    investments: &InvestmentsConfig,
+) -> Result<(), AppError> {
+    save_json(
+        workspace_path.join(".finance").join("investments.json"),
+        investments,
+    )
+}
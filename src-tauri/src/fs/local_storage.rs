@@ -1,9 +1,9 @@
-use crate::domain::config_models::LastSessionCacheConfig;
+use crate::domain::config_models::{BudgetAlertHistory, LastSessionCacheConfig};
 use crate::domain::error::AppError;
 use crate::dto::local_paths_dto::LocalPaths;
-use crate::helpers::json_helpers::save_json;
+use crate::helpers::json_helpers::{load_json, save_json};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 use tauri::Manager; // Para acceder a app_data_dir
 
@@ -44,3 +44,21 @@ pub fn prepare_local_storage(
 
     Ok(paths)
 }
+
+// Alertas de presupuesto ya mostradas en este equipo (app_data del workspace)
+pub fn load_budget_alert_history(app_data_dir: &Path) -> Result<BudgetAlertHistory, AppError> {
+    let file_path = app_data_dir.join("budget_alerts.json");
+    if file_path.exists() {
+        load_json(&file_path)
+    } else {
+        Ok(BudgetAlertHistory::default())
+    }
+}
+
+pub fn save_budget_alert_history(
+    app_data_dir: &Path,
+    history: &BudgetAlertHistory,
+) -> Result<(), AppError> {
+    fs::create_dir_all(app_data_dir)?;
+    save_json(app_data_dir.join("budget_alerts.json"), history)
+}
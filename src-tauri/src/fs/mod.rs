@@ -1,7 +1,15 @@
 // Filesystem functionalities
 pub mod account_file_management;
+pub mod budget_file_management;
+pub mod category_file_management;
 pub mod db_indexer;
 pub mod db_init;
+pub mod goal_file_management;
+pub mod investment_file_management;
 pub mod local_storage;
+pub mod reconciliation_file_management;
 pub mod record_file_management;
+pub mod rules_file_management;
+pub mod workspace_archive;
 pub mod workspace_init;
+pub mod workspace_schemas;
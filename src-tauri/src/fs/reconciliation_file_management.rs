@@ -0,0 +1,37 @@
+use crate::domain::config_models::{ReconciliationItem, ReconciliationsConfig};
+use crate::domain::error::AppError;
+use crate::helpers::json_helpers::{load_json, save_json};
+use std::path::{Path, PathBuf};
+
+pub fn reconciliations_path(workspace_path: &Path) -> PathBuf {
+    workspace_path.join(".finance").join("reconciliations.json")
+}
+
+// Sesiones de conciliación del workspace; los workspaces antiguos no tienen el archivo
+pub fn load_reconciliations(workspace_path: &Path) -> Result<ReconciliationsConfig, AppError> {
+    let file_path = reconciliations_path(workspace_path);
+    if file_path.exists() {
+        load_json(&file_path)
+    } else {
+        Ok(ReconciliationsConfig::default())
+    }
+}
+
+// Crea la sesión si no existe o reemplaza la que tenga el mismo id
+pub fn upsert_reconciliation(
+    workspace_path: &Path,
+    session: ReconciliationItem,
+) -> Result<(), AppError> {
+    let mut config = load_reconciliations(workspace_path)?;
+    set_session(&mut config, session);
+
+    save_json(reconciliations_path(workspace_path), &config)
+}
+
+// Reemplaza en memoria la sesión con el mismo id o la agrega
+pub fn set_session(config: &mut ReconciliationsConfig, session: ReconciliationItem) {
+    match config.sessions.iter_mut().find(|s| s.id == session.id) {
+        Some(existing) => *existing = session,
+        None => config.sessions.push(session),
+    }
+}
@@ -1,18 +1,13 @@
-use crate::domain::config_models::RecordItem;
+use crate::domain::config_models::{InstallmentPlan, RecordItem};
 use crate::domain::error::AppError;
 use crate::dto::create_record_dto::CreateRecordDto;
 use crate::helpers::json_helpers::save_json;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-pub fn create_record_file(
-    workspace_path: &PathBuf,
-    dto: &CreateRecordDto,
-    record_id: &str,
-) -> Result<(), AppError> {
-    let records_dir = workspace_path.join("records");
-    let record_file_path = records_dir.join(format!("{}.json", record_id));
-
-    let record_item = RecordItem {
+// Construye el RecordItem que se persiste a partir del formulario
+pub fn build_record_item(dto: &CreateRecordDto, record_id: &str) -> RecordItem {
+    RecordItem {
         id: record_id.to_string(),
         r#type: dto.r#type.clone(),
         amount: dto.amount,
@@ -23,9 +18,82 @@ pub fn create_record_file(
         to_account_id: dto.to_account_id.clone(),
         description: dto.description.clone(),
         metadata: None,
-    };
+        installments: dto
+            .installments
+            .filter(|count| *count > 1)
+            .map(|count| InstallmentPlan {
+                count,
+                monthly_interest_rate: dto.monthly_interest_rate.unwrap_or(0.0),
+            }),
+        ..Default::default()
+    }
+}
+
+// Guarda (o sobrescribe) un registro ya construido en records/<id>.json
+pub fn record_file_path(workspace_path: &Path, record_id: &str) -> PathBuf {
+    workspace_path
+        .join("records")
+        .join(format!("{}.json", record_id))
+}
+
+pub fn save_record_item(workspace_path: &Path, record: &RecordItem) -> Result<PathBuf, AppError> {
+    let record_file_path = record_file_path(workspace_path, &record.id);
+
+    save_json(record_file_path.clone(), record)?;
+
+    Ok(record_file_path)
+}
+
+// Cuántos archivos inválidos se listan en el error
+const MAX_REPORTED_FILES: usize = 20;
+
+// Lee todos los archivos de la carpeta records/ junto con su ruta.
+// Si algún archivo no se puede parsear se devuelve un error que los lista,
+// para no operar (ni indexar) sobre un workspace incompleto.
+pub fn load_all_records(workspace_path: &Path) -> Result<Vec<(PathBuf, RecordItem)>, AppError> {
+    let records_dir = workspace_path.join("records");
+    let mut records = Vec::new();
+    let mut invalid: Vec<String> = Vec::new();
+
+    if !records_dir.exists() {
+        return Ok(records);
+    }
+
+    for entry in fs::read_dir(records_dir)? {
+        let path = entry?.path();
+
+        if path.extension().and_then(|s| s.to_str()) == Some("json") {
+            let rec_json = fs::read_to_string(&path)?;
+            match serde_json::from_str::<RecordItem>(&rec_json) {
+                Ok(record) => records.push((path, record)),
+                Err(e) => invalid.push(format!(
+                    "{} (línea {}, columna {}): {}",
+                    path.file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    e.line(),
+                    e.column(),
+                    e
+                )),
+            }
+        }
+    }
+
+    if !invalid.is_empty() {
+        let total = invalid.len();
+        invalid.sort();
+        invalid.truncate(MAX_REPORTED_FILES);
+        if total > MAX_REPORTED_FILES {
+            invalid.push(format!("... y {} archivos más", total - MAX_REPORTED_FILES));
+        }
+        return Err(AppError::SchemaError(format!(
+            "Registros que no se pudieron leer:\n{}",
+            invalid.join("\n")
+        )));
+    }
 
-    save_json(record_file_path, &record_item)?;
+    // Orden cronológico para que los consumidores no dependan del orden del directorio
+    records.sort_by_key(|(_, record)| record.timestamp);
 
-    Ok(())
+    Ok(records)
 }
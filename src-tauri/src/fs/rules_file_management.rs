@@ -0,0 +1,43 @@
+use crate::domain::config_models::{RuleItem, RulesConfig};
+use crate::domain::error::AppError;
+use crate::helpers::json_helpers::{load_json, save_json};
+use std::path::Path;
+
+// Carga las reglas del workspace; los workspaces antiguos no tienen rules.json
+pub fn load_rules(workspace_path: &Path) -> Result<RulesConfig, AppError> {
+    let file_path = workspace_path.join(".finance").join("rules.json");
+    if file_path.exists() {
+        load_json(&file_path)
+    } else {
+        Ok(RulesConfig::default())
+    }
+}
+
+pub fn save_rules(workspace_path: &Path, rules: &RulesConfig) -> Result<(), AppError> {
+    save_json(workspace_path.join(".finance").join("rules.json"), rules)
+}
+
+// Crea la regla si no existe o reemplaza la que tenga el mismo id
+pub fn upsert_rule(workspace_path: &Path, rule: RuleItem) -> Result<(), AppError> {
+    let mut rules_config = load_rules(workspace_path)?;
+
+    match rules_config.rules.iter_mut().find(|r| r.id == rule.id) {
+        Some(existing) => *existing = rule,
+        None => rules_config.rules.push(rule),
+    }
+
+    save_rules(workspace_path, &rules_config)
+}
+
+pub fn remove_rule(workspace_path: &Path, rule_id: &str) -> Result<(), AppError> {
+    let mut rules_config = load_rules(workspace_path)?;
+
+    let before = rules_config.rules.len();
+    rules_config.rules.retain(|r| r.id != rule_id);
+
+    if rules_config.rules.len() == before {
+        return Err(AppError::NotFound(format!("Regla {} no encontrada", rule_id)));
+    }
+
+    save_rules(workspace_path, &rules_config)
+}
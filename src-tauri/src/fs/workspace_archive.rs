@@ -0,0 +1,338 @@
+use crate::domain::config_models::{ArchiveFileEntry, ArchiveManifest, VersionConfig};
+use crate::domain::error::AppError;
+use crate::helpers::datetime_helpers::timestamp_now;
+use crate::helpers::hash_helpers::sha256_hex;
+use crate::helpers::json_helpers::load_json;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MANIFEST_NAME: &str = "manifest.json";
+const ARCHIVED_DIRS: [&str; 3] = [".finance", "records", "attachments"];
+
+/// Empaqueta el workspace completo en un .zip con manifiesto de hashes
+pub fn export_workspace_archive(
+    workspace_path: &Path,
+    output_path: &Path,
+) -> Result<ArchiveManifest, AppError> {
+    // 1. Recolectar los archivos del workspace
+    let mut files: Vec<PathBuf> = Vec::new();
+    for dir in ARCHIVED_DIRS {
+        collect_files(&workspace_path.join(dir), &mut files)?;
+    }
+
+    let file = File::create(output_path)?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut entries = Vec::new();
+
+    // 2. Escribir cada archivo y calcular su hash
+    for path in files {
+        let relative = relative_zip_path(workspace_path, &path)?;
+        let bytes = fs::read(&path)?;
+
+        zip.start_file(relative.as_str(), options)
+            .map_err(|e| AppError::IoError(format!("Error escribiendo zip: {}", e)))?;
+        zip.write_all(&bytes)?;
+
+        entries.push(ArchiveFileEntry {
+            path: relative,
+            sha256: sha256_hex(&bytes),
+            size: bytes.len() as u64,
+        });
+    }
+
+    // 3. El manifiesto va al final con la versión del esquema
+    let manifest = ArchiveManifest {
+        workspace_name: workspace_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        created_at: timestamp_now(),
+        version: load_json(&workspace_path.join(".finance").join("version.json"))?,
+        files: entries,
+    };
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| AppError::ConfigError(format!("Error serializando: {}", e)))?;
+    zip.start_file(MANIFEST_NAME, options)
+        .map_err(|e| AppError::IoError(format!("Error escribiendo zip: {}", e)))?;
+    zip.write_all(&manifest_json)?;
+
+    zip.finish()
+        .map_err(|e| AppError::IoError(format!("Error cerrando zip: {}", e)))?;
+
+    Ok(manifest)
+}
+
+/// Restaura un archivo .zip en una carpeta nueva, verificando hashes y versión
+pub fn import_workspace_archive(
+    archive_path: &Path,
+    target_path: &Path,
+) -> Result<ArchiveManifest, AppError> {
+    if target_path.exists() {
+        return Err(AppError::WorkspaceExists(
+            target_path.to_string_lossy().into_owned(),
+        ));
+    }
+
+    let file = File::open(archive_path)?;
+    let mut zip = ZipArchive::new(file)
+        .map_err(|e| AppError::IoError(format!("Archivo zip inválido: {}", e)))?;
+
+    // 1. Leer y validar el manifiesto antes de extraer nada
+    let manifest: ArchiveManifest = {
+        let mut entry = zip
+            .by_name(MANIFEST_NAME)
+            .map_err(|_| AppError::ConfigError("El archivo no contiene manifest.json".into()))?;
+        let mut content = String::new();
+        entry.read_to_string(&mut content)?;
+        serde_json::from_str(&content)
+            .map_err(|e| AppError::ConfigError(format!("Manifiesto inválido: {}", e)))?
+    };
+
+    let supported = VersionConfig::default().schema_version;
+    if manifest.version.schema_version > supported {
+        return Err(AppError::ConfigError(format!(
+            "El workspace usa el esquema {} y esta versión soporta hasta el {}",
+            manifest.version.schema_version, supported
+        )));
+    }
+
+    // 2. Extraer verificando cada hash y la versión extraída; si algo falla,
+    // borramos la carpeta
+    let extracted = extract_files(&mut zip, &manifest, target_path)
+        .and_then(|_| check_extracted_version(&manifest, target_path, supported));
+    if let Err(e) = extracted {
+        let _ = fs::remove_dir_all(target_path);
+        return Err(e);
+    }
+
+    Ok(manifest)
+}
+
+// El version.json extraído debe ser el que declara el manifiesto: un manifiesto
+// editado a mano no puede saltarse la verificación del esquema
+fn check_extracted_version(
+    manifest: &ArchiveManifest,
+    target_path: &Path,
+    supported: u32,
+) -> Result<(), AppError> {
+    let version: VersionConfig = load_json(&target_path.join(".finance").join("version.json"))?;
+
+    if version.schema_version != manifest.version.schema_version
+        || version.created_at != manifest.version.created_at
+        || version.app_version != manifest.version.app_version
+    {
+        return Err(AppError::ConfigError(
+            "El version.json del archivo no coincide con el manifiesto".into(),
+        ));
+    }
+    if version.schema_version > supported {
+        return Err(AppError::ConfigError(format!(
+            "El workspace usa el esquema {} y esta versión soporta hasta el {}",
+            version.schema_version, supported
+        )));
+    }
+
+    Ok(())
+}
+
+fn extract_files(
+    zip: &mut ZipArchive<File>,
+    manifest: &ArchiveManifest,
+    target_path: &Path,
+) -> Result<(), AppError> {
+    for dir in ARCHIVED_DIRS {
+        fs::create_dir_all(target_path.join(dir))?;
+    }
+
+    for entry in &manifest.files {
+        let mut file = zip
+            .by_name(&entry.path)
+            .map_err(|_| AppError::NotFound(format!("{} listado en el manifiesto", entry.path)))?;
+
+        // enclosed_name evita rutas que escapen de la carpeta destino
+        let relative = file
+            .enclosed_name()
+            .ok_or_else(|| AppError::IoError(format!("Ruta inválida en el zip: {}", entry.path)))?;
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        if sha256_hex(&bytes) != entry.sha256 {
+            return Err(AppError::ConfigError(format!(
+                "El hash de {} no coincide con el manifiesto",
+                entry.path
+            )));
+        }
+
+        let destination = target_path.join(relative);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(destination, bytes)?;
+    }
+
+    Ok(())
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), AppError> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+// Rutas con "/" para que el zip sea portable entre sistemas
+fn relative_zip_path(root: &Path, path: &Path) -> Result<String, AppError> {
+    let relative = path
+        .strip_prefix(root)
+        .map_err(|e| AppError::IoError(e.to_string()))?;
+
+    Ok(relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("finance-archive-{}-{}", name, uuid::Uuid::new_v4()))
+    }
+
+    fn version(schema_version: u32) -> VersionConfig {
+        VersionConfig {
+            schema_version,
+            created_at: 1,
+            app_version: "0.1.0".into(),
+        }
+    }
+
+    fn manifest(files: &[(&str, Vec<u8>)], version: VersionConfig) -> ArchiveManifest {
+        ArchiveManifest {
+            workspace_name: "test".into(),
+            created_at: 1,
+            version,
+            files: files
+                .iter()
+                .map(|(name, bytes)| ArchiveFileEntry {
+                    path: name.to_string(),
+                    sha256: sha256_hex(bytes),
+                    size: bytes.len() as u64,
+                })
+                .collect(),
+        }
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, Vec<u8>)], manifest: &ArchiveManifest) {
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        let options = SimpleFileOptions::default();
+        for (name, bytes) in files {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(bytes).unwrap();
+        }
+        zip.start_file(MANIFEST_NAME, options).unwrap();
+        zip.write_all(&serde_json::to_vec(manifest).unwrap())
+            .unwrap();
+        zip.finish().unwrap();
+    }
+
+    // Zip con los archivos dados y un manifiesto con sus hashes reales
+    fn write_archive(path: &Path, files: &[(&str, Vec<u8>)], manifest_version: VersionConfig) {
+        write_zip(path, files, &manifest(files, manifest_version));
+    }
+
+    #[test]
+    fn export_and_import_round_trip() {
+        let workspace = temp_path("source");
+        let archive = temp_path("round-trip.zip");
+        let target = temp_path("restored");
+        fs::create_dir_all(workspace.join(".finance")).unwrap();
+        fs::create_dir_all(workspace.join("records").join("2024")).unwrap();
+        fs::write(
+            workspace.join(".finance").join("version.json"),
+            serde_json::to_vec(&version(1)).unwrap(),
+        )
+        .unwrap();
+        fs::write(
+            workspace.join("records").join("2024").join("r1.json"),
+            b"{}",
+        )
+        .unwrap();
+
+        let exported = export_workspace_archive(&workspace, &archive).unwrap();
+        let imported = import_workspace_archive(&archive, &target).unwrap();
+
+        assert_eq!(exported.files.len(), 2);
+        assert_eq!(imported.files.len(), 2);
+        assert!(exported
+            .files
+            .iter()
+            .any(|f| f.path == "records/2024/r1.json"));
+        assert_eq!(
+            fs::read(target.join("records").join("2024").join("r1.json")).unwrap(),
+            b"{}"
+        );
+        let _ = fs::remove_dir_all(workspace);
+        let _ = fs::remove_dir_all(target);
+        let _ = fs::remove_file(archive);
+    }
+
+    #[test]
+    fn hash_mismatch_rejects_the_archive() {
+        let archive = temp_path("tampered.zip");
+        let target = temp_path("tampered");
+        let version_json = serde_json::to_vec(&version(1)).unwrap();
+        let mut tampered = manifest(
+            &[(".finance/version.json", version_json.clone())],
+            version(1),
+        );
+        tampered.files[0].sha256 = sha256_hex(b"otro contenido");
+        write_zip(
+            &archive,
+            &[(".finance/version.json", version_json)],
+            &tampered,
+        );
+
+        let result = import_workspace_archive(&archive, &target);
+
+        assert!(matches!(result, Err(AppError::ConfigError(_))));
+        assert!(!target.exists());
+        let _ = fs::remove_file(archive);
+    }
+
+    #[test]
+    fn extracted_version_must_match_the_manifest() {
+        let archive = temp_path("version.zip");
+        let target = temp_path("version");
+        let version_json = serde_json::to_vec(&version(99)).unwrap();
+        write_archive(
+            &archive,
+            &[(".finance/version.json", version_json)],
+            version(1),
+        );
+
+        let result = import_workspace_archive(&archive, &target);
+
+        assert!(matches!(result, Err(AppError::ConfigError(_))));
+        assert!(!target.exists());
+        let _ = fs::remove_file(archive);
+    }
+}
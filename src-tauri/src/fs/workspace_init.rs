@@ -1,10 +1,17 @@
 use crate::domain::config_models::*;
 use crate::domain::error::AppError;
+use crate::fs::workspace_schemas::write_schemas;
 use crate::helpers::json_helpers::save_json;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-pub fn init(base_path: String, name: String) -> Result<(), AppError> {
+// language y currency ya normalizados (ver domain::presets)
+pub fn init(
+    base_path: String,
+    name: String,
+    language: &str,
+    currency: &str,
+) -> Result<(), AppError> {
     // 1. Construir ruta del workspace
     let workspace_path = Path::new(&base_path).join(&name);
 
@@ -13,7 +20,7 @@ pub fn init(base_path: String, name: String) -> Result<(), AppError> {
         return Err(AppError::WorkspaceExists(name));
     }
 
-    if let Err(e) = create_structure(&workspace_path) {
+    if let Err(e) = create_structure(&workspace_path, language, currency) {
         // ROLLBACK: Si algo falló, intentamos borrar la carpeta principal
         let _ = fs::remove_dir_all(&workspace_path);
         return Err(e);
@@ -22,7 +29,7 @@ pub fn init(base_path: String, name: String) -> Result<(), AppError> {
     Ok(())
 }
 
-fn create_structure(root: &PathBuf) -> Result<(), AppError> {
+fn create_structure(root: &PathBuf, language: &str, currency: &str) -> Result<(), AppError> {
     fs::create_dir_all(root)?;
     // Carpetas
     let dirs = [".finance", "records", "attachments"];
@@ -33,16 +40,35 @@ fn create_structure(root: &PathBuf) -> Result<(), AppError> {
 
     // Guardar archivos usando los nuevos modelos
     save_json(config_dir.join("version.json"), &VersionConfig::default())?;
-    save_json(config_dir.join("app.json"), &AppConfig::default())?;
+    save_json(
+        config_dir.join("app.json"),
+        &AppConfig::for_workspace(language, currency),
+    )?;
     save_json(
         config_dir.join("categories.json"),
-        &CategoriesConfig::default(),
+        &CategoriesConfig::for_language(language),
+    )?;
+    save_json(
+        config_dir.join("accounts.json"),
+        &AccountsConfig::for_workspace(language, currency),
     )?;
-    save_json(config_dir.join("accounts.json"), &AccountsConfig::default())?;
 
     // Archivos adicionales vacíos
-    save_json(config_dir.join("budgets.json"), &serde_json::json!({}))?;
+    save_json(config_dir.join("budgets.json"), &BudgetsConfig::default())?;
     save_json(config_dir.join("tags.json"), &serde_json::json!([]))?;
+    save_json(config_dir.join("rules.json"), &RulesConfig::default())?;
+    save_json(
+        config_dir.join("reconciliations.json"),
+        &ReconciliationsConfig::default(),
+    )?;
+    save_json(
+        config_dir.join("investments.json"),
+        &InvestmentsConfig::default(),
+    )?;
+    save_json(config_dir.join("goals.json"), &GoalsConfig::default())?;
+
+    // Esquemas para herramientas de sincronización y edición manual
+    write_schemas(&config_dir)?;
 
     Ok(())
 }
@@ -0,0 +1,213 @@
+use crate::domain::config_models::*;
+use crate::domain::error::AppError;
+use crate::helpers::json_helpers::save_json;
+use jsonschema::Validator;
+use schemars::{schema_for, JsonSchema};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+// Máximo de errores reportados para no devolver mensajes gigantes
+const MAX_REPORTED_ERRORS: usize = 20;
+
+fn schema_value<T: JsonSchema>() -> Value {
+    serde_json::to_value(schema_for!(T)).unwrap_or(Value::Null)
+}
+
+// (archivo de esquema, esquema, archivo de .finance que valida)
+fn workspace_schemas() -> Vec<(&'static str, Value, Option<&'static str>)> {
+    vec![
+        ("version.schema.json", schema_value::<VersionConfig>(), Some("version.json")),
+        ("app.schema.json", schema_value::<AppConfig>(), Some("app.json")),
+        ("categories.schema.json", schema_value::<CategoriesConfig>(), Some("categories.json")),
+        ("accounts.schema.json", schema_value::<AccountsConfig>(), Some("accounts.json")),
+        ("rules.schema.json", schema_value::<RulesConfig>(), Some("rules.json")),
+        (
+            "reconciliations.schema.json",
+            schema_value::<ReconciliationsConfig>(),
+            Some("reconciliations.json"),
+        ),
+        (
+            "investments.schema.json",
+            schema_value::<InvestmentsConfig>(),
+            Some("investments.json"),
+        ),
+        ("budgets.schema.json", schema_value::<BudgetsConfig>(), Some("budgets.json")),
+        ("goals.schema.json", schema_value::<GoalsConfig>(), Some("goals.json")),
+        ("record.schema.json", schema_value::<RecordItem>(), None),
+    ]
+}
+
+/// Escribe (o refresca) los JSON Schema dentro de .finance/schemas/
+pub fn write_schemas(finance_dir: &Path) -> Result<(), AppError> {
+    let schemas_dir = finance_dir.join("schemas");
+    fs::create_dir_all(&schemas_dir)?;
+
+    for (file_name, schema, _) in workspace_schemas() {
+        save_json(schemas_dir.join(file_name), &schema)?;
+    }
+
+    Ok(())
+}
+
+/// Valida los archivos de configuración y los registros contra sus esquemas
+pub fn validate_workspace(workspace_path: &Path) -> Result<(), AppError> {
+    let finance_dir = workspace_path.join(".finance");
+    let mut problems: Vec<String> = Vec::new();
+    let mut record_validator = None;
+
+    // 1. Archivos de configuración
+    for (schema_name, schema, target) in workspace_schemas() {
+        let validator = jsonschema::validator_for(&schema).map_err(|e| {
+            AppError::ConfigError(format!("Esquema inválido {}: {}", schema_name, e))
+        })?;
+        match target {
+            Some(file_name) => {
+                validate_file(&finance_dir.join(file_name), &validator, &mut problems)?
+            }
+            None => record_validator = Some(validator),
+        }
+    }
+
+    // 2. Registros (records/*.json)
+    let records_dir = workspace_path.join("records");
+    if let (true, Some(validator)) = (records_dir.exists(), record_validator.as_ref()) {
+        for entry in fs::read_dir(records_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                validate_file(&path, validator, &mut problems)?;
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    let total = problems.len();
+    problems.truncate(MAX_REPORTED_ERRORS);
+    if total > MAX_REPORTED_ERRORS {
+        problems.push(format!("... y {} errores más", total - MAX_REPORTED_ERRORS));
+    }
+
+    Err(AppError::SchemaError(problems.join("\n")))
+}
+
+fn validate_file(
+    path: &Path,
+    validator: &Validator,
+    problems: &mut Vec<String>,
+) -> Result<(), AppError> {
+    // Los archivos opcionales (p. ej. rules.json en workspaces antiguos) pueden no existir
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let file_label = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let content = fs::read_to_string(path)
+        .map_err(|e| AppError::IoError(format!("No se pudo leer el archivo {:?}: {}", path, e)))?;
+
+    let instance: Value = match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(e) => {
+            problems.push(format!(
+                "{} (línea {}, columna {}): JSON inválido: {}",
+                file_label,
+                e.line(),
+                e.column(),
+                e
+            ));
+            return Ok(());
+        }
+    };
+
+    for error in validator.iter_errors(&instance) {
+        let location = error.instance_path().to_string();
+        problems.push(format!(
+            "{} en {}: {}",
+            file_label,
+            if location.is_empty() { "/" } else { &location },
+            error
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Workspace mínimo con un version.json válido
+    fn workspace() -> PathBuf {
+        let path = std::env::temp_dir().join(format!("finance-schemas-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(path.join(".finance")).unwrap();
+        fs::create_dir_all(path.join("records")).unwrap();
+        save_json(
+            path.join(".finance").join("version.json"),
+            &VersionConfig::default(),
+        )
+        .unwrap();
+        path
+    }
+
+    fn problems(workspace: &Path) -> String {
+        match validate_workspace(workspace) {
+            Err(AppError::SchemaError(message)) => message,
+            other => panic!("se esperaba un SchemaError: {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn valid_workspace_passes() {
+        let path = workspace();
+
+        assert!(validate_workspace(&path).is_ok());
+        let _ = fs::remove_dir_all(path);
+    }
+
+    #[test]
+    fn wrong_types_are_reported_with_their_location() {
+        let path = workspace();
+        fs::write(
+            path.join(".finance").join("version.json"),
+            r#"{ "schema_version": "uno", "created_at": 0, "app_version": "0.1.0" }"#,
+        )
+        .unwrap();
+
+        let message = problems(&path);
+
+        assert!(message.contains("version.json en /schema_version"));
+        let _ = fs::remove_dir_all(path);
+    }
+
+    #[test]
+    fn invalid_json_reports_line_and_column() {
+        let path = workspace();
+        fs::write(path.join("records").join("r1.json"), "{\n  \"id\": ").unwrap();
+
+        let message = problems(&path);
+
+        assert!(message.starts_with("r1.json (línea 2"));
+        let _ = fs::remove_dir_all(path);
+    }
+
+    #[test]
+    fn reported_errors_are_capped() {
+        let path = workspace();
+        for i in 0..MAX_REPORTED_ERRORS + 5 {
+            fs::write(path.join("records").join(format!("r{}.json", i)), "{}").unwrap();
+        }
+
+        let message = problems(&path);
+
+        assert_eq!(message.lines().count(), MAX_REPORTED_ERRORS + 1);
+        assert!(message.ends_with("errores más"));
+        let _ = fs::remove_dir_all(path);
+    }
+}
@@ -1,6 +1,70 @@
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 // Helper para obtener el timestamp actual
 pub fn timestamp_now() -> i64 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs().try_into().unwrap()
-}
\ No newline at end of file
+}
+
+// Convierte un timestamp (segundos) a fecha local en formato YYYY-MM-DD
+pub fn timestamp_to_date_string(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "1970-01-01".to_string())
+}
+
+// Convierte una fecha YYYY-MM-DD a timestamp (medianoche local)
+pub fn date_string_to_timestamp(date: &str) -> Option<i64> {
+    let naive = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Local
+        .from_local_datetime(&naive.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+// Fecha local (sin hora) de un timestamp
+pub fn timestamp_to_local_date(timestamp: i64) -> NaiveDate {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|dt| dt.date_naive())
+        .unwrap_or_default()
+}
+
+// Último segundo del día local (23:59:59) como timestamp
+pub fn end_of_day_timestamp(date: NaiveDate) -> i64 {
+    date.and_hms_opt(23, 59, 59)
+        .and_then(|dt| Local.from_local_datetime(&dt).latest())
+        .map(|dt| dt.timestamp())
+        .unwrap_or_default()
+}
+
+// Primer segundo del día local (00:00:00) como timestamp
+pub fn start_of_day_timestamp(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|dt| Local.from_local_datetime(&dt).earliest())
+        .map(|dt| dt.timestamp())
+        .unwrap_or_default()
+}
+
+// Día del mes ajustado al último día (p. ej. 31 en febrero -> 28/29)
+pub fn clamped_date(year: i32, month: u32, day: u32) -> NaiveDate {
+    let last_day = last_day_of_month(year, month);
+    NaiveDate::from_ymd_opt(year, month, day.clamp(1, last_day)).unwrap_or_default()
+}
+
+pub fn last_day_of_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|d| d.pred_opt())
+        .map(|d| d.day())
+        .unwrap_or(28)
+}
+
+// Suma (o resta) meses conservando el día de referencia
+pub fn shift_month(year: i32, month: u32, delta: i32) -> (i32, u32) {
+    let index = year * 12 + (month as i32 - 1) + delta;
+    (index.div_euclid(12), (index.rem_euclid(12) + 1) as u32)
+}
@@ -0,0 +1,9 @@
+use sha2::{Digest, Sha256};
+
+// Helper para obtener el hash SHA-256 en hexadecimal
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -4,9 +4,16 @@ use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+// Contenido anterior de un archivo tocado por `apply_file_changes` (None si no existía)
+pub type FileBackup = (PathBuf, Option<Vec<u8>>);
+
+pub fn to_json<T: Serialize>(data: &T) -> Result<String, AppError> {
+    serde_json::to_string_pretty(data)
+        .map_err(|e| AppError::ConfigError(format!("Error serializando: {}", e)))
+}
+
 pub fn save_json<T: Serialize>(path: PathBuf, data: &T) -> Result<(), AppError> {
-    let contents = serde_json::to_string_pretty(data)
-        .map_err(|e| AppError::ConfigError(format!("Error serializando: {}", e)))?;
+    let contents = to_json(data)?;
 
     fs::write(&path, contents)
         .map_err(|e| AppError::ConfigError(format!("Error escribiendo en {:?}: {}", path, e)))?;
@@ -27,3 +34,52 @@ pub fn load_json<T: DeserializeOwned>(path: &Path) -> Result<T, AppError> {
 
     Ok(data)
 }
+
+// Escribe (Some) o borra (None) cada archivo guardando su contenido anterior.
+// Si uno falla se restauran los ya tocados y se devuelve el error.
+pub fn apply_file_changes(
+    changes: &[(PathBuf, Option<String>)],
+) -> Result<Vec<FileBackup>, AppError> {
+    let mut backups = Vec::new();
+    for (path, contents) in changes {
+        let original = if path.exists() {
+            match fs::read(path) {
+                Ok(original) => Some(original),
+                Err(e) => {
+                    restore_files(&backups);
+                    return Err(AppError::IoError(format!(
+                        "Error leyendo {:?}: {}",
+                        path, e
+                    )));
+                }
+            }
+        } else {
+            None
+        };
+        backups.push((path.clone(), original));
+
+        let result = match contents {
+            Some(contents) => fs::write(path, contents),
+            None if path.exists() => fs::remove_file(path),
+            None => Ok(()),
+        };
+        if let Err(e) = result {
+            restore_files(&backups);
+            return Err(AppError::IoError(format!(
+                "Error escribiendo {:?}: {}",
+                path, e
+            )));
+        }
+    }
+    Ok(backups)
+}
+
+// Deja cada archivo como estaba (los que no existían se borran)
+pub fn restore_files(backups: &[FileBackup]) {
+    for (path, original) in backups {
+        let _ = match original {
+            Some(original) => fs::write(path, original),
+            None => fs::remove_file(path),
+        };
+    }
+}
@@ -1,2 +1,3 @@
 pub mod datetime_helpers;
+pub mod hash_helpers;
 pub mod json_helpers;
\ No newline at end of file
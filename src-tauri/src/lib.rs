@@ -24,19 +24,86 @@ pub fn run() {
         })
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             commands::workspace::init_workspace,
+            commands::workspace::set_workspace_language,
+            commands::workspace::set_budget_notifications,
             commands::workspace::open_workspace,
             commands::workspace::get_workspace_context,
+            commands::workspace::import_beancount_workspace,
+            commands::workspace::export_workspace_archive,
+            commands::workspace::import_workspace_archive,
             commands::home::get_overall_stats,
+            commands::home::get_expenses_by_category,
+            commands::home::get_category_breakdown,
             commands::home::get_accounts,
             commands::home::get_categories,
+            commands::categories::get_category_tree,
+            commands::categories::create_category,
+            commands::categories::update_category,
+            commands::categories::disable_category,
+            commands::categories::enable_category,
+            commands::categories::delete_category,
+            commands::categories::merge_categories,
+            commands::budgets::get_budgets,
+            commands::budgets::set_budget,
+            commands::budgets::delete_budget,
+            commands::budgets::copy_budgets,
+            commands::budgets::get_budget_status,
+            commands::budgets::check_budget_alerts,
+            commands::envelopes::set_envelope_budgeting,
+            commands::envelopes::assign_to_envelope,
+            commands::envelopes::move_between_envelopes,
+            commands::envelopes::delete_envelope_move,
+            commands::envelopes::get_envelope_month,
+            commands::goals::get_goals,
+            commands::goals::save_goal,
+            commands::goals::delete_goal,
             commands::home::create_account,
             commands::home::update_account,
             commands::home::delete_account,
+            commands::home::delete_account_and_reassign,
+            commands::home::get_archived_accounts,
+            commands::home::archive_account,
+            commands::home::unarchive_account,
             commands::home::get_paginated_records,
+            commands::accounts::get_account_balance_history,
+            commands::accounts::get_account_balance_on,
+            commands::accounts::get_account_ledger,
+            commands::accounts::get_opening_date_warnings,
+            commands::accounts::get_net_worth,
+            commands::accounts::set_exchange_rate,
+            commands::loans::get_loan_summary,
+            commands::loans::simulate_loan_schedule,
+            commands::loans::preview_loan_schedule,
+            commands::investments::get_securities,
+            commands::investments::save_security,
+            commands::investments::record_investment_transaction,
+            commands::investments::delete_investment_transaction,
+            commands::investments::set_security_price,
+            commands::investments::import_security_prices_csv,
+            commands::investments::get_holdings,
+            commands::investments::get_investment_gains,
+            commands::investments::get_investment_currency_warnings,
             commands::home::create_record,
+            commands::export::export_plain_text_accounting,
+            commands::credit_cards::get_credit_card_summary,
+            commands::credit_cards::get_credit_card_statements,
+            commands::credit_cards::pay_credit_card,
+            commands::credit_cards::preview_installment_schedule,
+            commands::credit_cards::get_outstanding_installments,
+            commands::reconciliation::start_reconciliation,
+            commands::reconciliation::set_reconciliation_selection,
+            commands::reconciliation::finish_reconciliation,
+            commands::reconciliation::get_reconciliation_history,
+            commands::reconciliation::get_last_reconciliations,
+            commands::rules::get_rules,
+            commands::rules::save_rule,
+            commands::rules::delete_rule,
+            commands::rules::test_rule,
+            commands::rules::reapply_rules,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
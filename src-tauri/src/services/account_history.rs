@@ -0,0 +1,514 @@
+// Historial de saldos por cuenta
+
+use crate::domain::error::AppError;
+use crate::dto::account_history_dto::{
+    AccountBalanceHistoryDto, BalancePointDto, LedgerEntryDto, OpeningDateWarningDto,
+};
+use crate::dto::pagination_dto::Pagination;
+use crate::helpers::datetime_helpers::timestamp_now;
+use crate::helpers::datetime_helpers::{
+    end_of_day_timestamp, last_day_of_month, start_of_day_timestamp, timestamp_to_local_date,
+};
+use crate::services::accounts::{
+    get_account_balance_at, AFTER_OPENING_SQL, INSTALLMENT_INTEREST_SQL, SIGNED_AMOUNT_SQL,
+};
+use chrono::{Datelike, NaiveDate, Weekday};
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BalanceInterval {
+    Day,
+    Week,
+    Month,
+}
+
+// Cierre del periodo que contiene la fecha (las semanas terminan en domingo)
+fn period_end(date: NaiveDate, interval: BalanceInterval) -> NaiveDate {
+    match interval {
+        BalanceInterval::Day => date,
+        BalanceInterval::Week => {
+            let days_left =
+                Weekday::Sun.num_days_from_monday() - date.weekday().num_days_from_monday();
+            date + chrono::Duration::days(days_left as i64)
+        }
+        BalanceInterval::Month => NaiveDate::from_ymd_opt(
+            date.year(),
+            date.month(),
+            last_day_of_month(date.year(), date.month()),
+        )
+        .unwrap_or(date),
+    }
+}
+
+// Fechas de cierre entre start y end; la última se recorta a end
+fn period_ends(start: NaiveDate, end: NaiveDate, interval: BalanceInterval) -> Vec<NaiveDate> {
+    let mut ends = Vec::new();
+    let mut current = start;
+
+    while current <= end {
+        let close = period_end(current, interval).min(end);
+        ends.push(close);
+        match close.succ_opt() {
+            Some(next) => current = next,
+            None => break,
+        }
+    }
+
+    ends
+}
+
+fn active_account_ids(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM accounts WHERE is_active = 1 ORDER BY name")
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut ids = Vec::new();
+    for row in rows {
+        ids.push(row.map_err(|e| AppError::DatabaseError(e.to_string()))?);
+    }
+
+    Ok(ids)
+}
+
+// Movimientos de la cuenta en [from, to] con su efecto sobre el saldo
+// (incluye el efectivo de compras y ventas de inversiones, los intereses de
+// cuotas y el saldo inicial el día de apertura)
+fn signed_movements(
+    conn: &Connection,
+    account_id: &str,
+    from: i64,
+    to: i64,
+) -> Result<Vec<(i64, f64)>, AppError> {
+    let sql = format!(
+        "SELECT timestamp, amount FROM (
+            SELECT r.timestamp AS timestamp, {signed} AS amount
+            FROM records r
+            WHERE (r.account_id = ?1 OR r.to_account_id = ?1)
+              AND r.timestamp >= ?2 AND r.timestamp <= ?3
+              AND {after_opening}
+            UNION ALL
+            SELECT t.timestamp, t.cash_amount
+            FROM investment_transactions t
+            JOIN accounts a ON a.id = t.account_id
+            WHERE t.account_id = ?1 AND t.timestamp >= ?2 AND t.timestamp <= ?3
+              AND (a.opening_date IS NULL OR t.timestamp >= a.opening_date)
+            UNION ALL
+            SELECT ii.due_timestamp, -ii.interest
+            FROM ({interest}) ii
+            WHERE ii.due_timestamp >= ?2 AND ii.due_timestamp <= ?3
+            UNION ALL
+            -- El saldo inicial entra el día de apertura si cae dentro del rango
+            SELECT a.opening_date, a.initial_balance
+            FROM accounts a
+            WHERE a.id = ?1 AND a.opening_date >= ?2 AND a.opening_date <= ?3
+         )
+         ORDER BY timestamp ASC",
+        signed = SIGNED_AMOUNT_SQL,
+        after_opening = AFTER_OPENING_SQL,
+        interest = INSTALLMENT_INTEREST_SQL
+    );
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map(params![account_id, from, to], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut movements = Vec::new();
+    for row in rows {
+        movements.push(row.map_err(|e| AppError::DatabaseError(e.to_string()))?);
+    }
+
+    Ok(movements)
+}
+
+/// Serie de saldos al cierre de cada día, semana o mes entre `from` y `to`.
+/// Sin cuentas indicadas se usan todas las activas.
+pub fn get_account_balance_history(
+    conn: &Connection,
+    account_ids: &[String],
+    from: i64,
+    to: i64,
+    interval: BalanceInterval,
+) -> Result<Vec<AccountBalanceHistoryDto>, AppError> {
+    if from > to {
+        return Err(AppError::ConfigError(
+            "La fecha inicial debe ser anterior a la final".into(),
+        ));
+    }
+
+    let account_ids = if account_ids.is_empty() {
+        active_account_ids(conn)?
+    } else {
+        account_ids.to_vec()
+    };
+
+    let start = timestamp_to_local_date(from);
+    let end = timestamp_to_local_date(to);
+    let range_start = start_of_day_timestamp(start);
+    let ends = period_ends(start, end, interval);
+
+    let mut history = Vec::new();
+    for account_id in account_ids {
+        // 1. Saldo de apertura: saldo inicial más todo lo anterior al rango
+        let opening_balance = get_account_balance_at(conn, &account_id, Some(range_start - 1))?;
+
+        // 2. Recorrer los movimientos en orden acumulando hasta cada cierre
+        let movements =
+            signed_movements(conn, &account_id, range_start, end_of_day_timestamp(end))?;
+        let mut balance = opening_balance;
+        let mut index = 0;
+        let mut points = Vec::with_capacity(ends.len());
+
+        for close in &ends {
+            let close_timestamp = end_of_day_timestamp(*close);
+            while index < movements.len() && movements[index].0 <= close_timestamp {
+                balance += movements[index].1;
+                index += 1;
+            }
+            points.push(BalancePointDto {
+                date: close.format("%Y-%m-%d").to_string(),
+                timestamp: close_timestamp,
+                balance,
+            });
+        }
+
+        history.push(AccountBalanceHistoryDto {
+            account_id,
+            opening_balance,
+            points,
+        });
+    }
+
+    Ok(history)
+}
+
+/// Libro de la cuenta (más reciente primero) con el saldo después de cada registro.
+/// Los registros anteriores a la fecha de apertura no aparecen: ya están en el saldo inicial.
+pub fn get_account_ledger(
+    conn: &Connection,
+    account_id: &str,
+    page: i16,
+    size: i16,
+) -> Result<Pagination<LedgerEntryDto>, AppError> {
+    let initial_balance: f64 = conn
+        .query_row(
+            "SELECT initial_balance FROM accounts WHERE id = ?1",
+            [account_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| AppError::NotFound(format!("Cuenta {} no encontrada", account_id)))?;
+
+    let offset: i64 = (page as i64 - 1) * size as i64;
+
+    // Movimientos de la cuenta: registros, el efectivo de compras y ventas de
+    // inversiones y los intereses de cuotas ya vencidas (?2)
+    let movements = format!(
+        "SELECT r.id, r.type, r.timestamp, r.amount, {signed} AS signed_amount,
+                r.description, r.category_id, r.cleared_at,
+                CASE WHEN r.account_id = ?1 THEN r.to_account_id ELSE r.account_id END AS counterpart_id
+         FROM records r
+         WHERE (r.account_id = ?1 OR r.to_account_id = ?1) AND {after_opening}
+         UNION ALL
+         SELECT t.id, t.kind, t.timestamp, ABS(t.cash_amount), t.cash_amount,
+                COALESCE(s.symbol, t.security_id) || ' x ' || t.quantity, NULL, NULL, NULL
+         FROM investment_transactions t
+         JOIN accounts a ON a.id = t.account_id
+         LEFT JOIN securities s ON s.id = t.security_id
+         WHERE t.account_id = ?1 AND t.cash_amount <> 0
+           AND (a.opening_date IS NULL OR t.timestamp >= a.opening_date)
+         UNION ALL
+         SELECT ii.record_id, 'installment_interest', ii.due_timestamp, ii.interest, -ii.interest,
+                'Intereses cuota ' || ii.number, ii.category_id, NULL, NULL
+         FROM ({interest}) ii
+         WHERE ii.due_timestamp <= ?2",
+        signed = SIGNED_AMOUNT_SQL,
+        after_opening = AFTER_OPENING_SQL,
+        interest = INSTALLMENT_INTEREST_SQL
+    );
+    let now = timestamp_now();
+
+    // El saldo acumulado se calcula en orden cronológico (id desempata registros del mismo segundo)
+    // y luego se pagina en orden inverso
+    let sql = format!(
+        "WITH ledger AS (
+            SELECT m.*, SUM(m.signed_amount) OVER (
+                       ORDER BY m.timestamp, m.id ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW
+                   ) AS running
+            FROM ({movements}) m
+         )
+         SELECT l.id, l.type, l.timestamp, l.amount, l.signed_amount, l.description,
+                c.name, ca.name, l.cleared_at, l.running
+         FROM ledger l
+         LEFT JOIN categories c ON l.category_id = c.id
+         LEFT JOIN accounts ca ON l.counterpart_id = ca.id
+         ORDER BY l.timestamp DESC, l.id DESC
+         LIMIT ?3 OFFSET ?4",
+        movements = movements
+    );
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map(params![account_id, now, size, offset], |row| {
+            let running: f64 = row.get(9)?;
+            Ok(LedgerEntryDto {
+                id: row.get(0)?,
+                r#type: row.get(1)?,
+                timestamp: row.get(2)?,
+                amount: row.get(3)?,
+                signed_amount: row.get(4)?,
+                description: row.get(5)?,
+                category_name: row.get(6)?,
+                counterpart_account_name: row.get(7)?,
+                cleared: row.get::<_, Option<i64>>(8)?.is_some(),
+                running_balance: initial_balance + running,
+            })
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| AppError::DatabaseError(e.to_string()))?);
+    }
+
+    let total_items: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM ({})", movements),
+            params![account_id, now],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    let total_pages = if size > 0 {
+        (total_items as f64 / size as f64).ceil() as i64
+    } else {
+        0
+    };
+
+    Ok(Pagination {
+        items,
+        total_items,
+        current_page: page,
+        size,
+        total_pages,
+    })
+}
+
+/// Cuentas con registros anteriores a su fecha de apertura
+pub fn get_opening_date_warnings(
+    conn: &Connection,
+) -> Result<Vec<OpeningDateWarningDto>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.id, a.name, a.opening_date, COUNT(r.id), MIN(r.timestamp)
+             FROM accounts a
+             JOIN records r ON (r.account_id = a.id OR r.to_account_id = a.id)
+                AND r.timestamp < a.opening_date
+             WHERE a.opening_date IS NOT NULL
+             GROUP BY a.id
+             ORDER BY a.name",
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(OpeningDateWarningDto {
+                account_id: row.get(0)?,
+                account_name: row.get(1)?,
+                opening_date: row.get(2)?,
+                records_before_opening: row.get(3)?,
+                earliest_timestamp: row.get(4)?,
+            })
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut warnings = Vec::new();
+    for row in rows {
+        warnings.push(row.map_err(|e| AppError::DatabaseError(e.to_string()))?);
+    }
+
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::db_init::init_sqlite;
+    use crate::helpers::datetime_helpers::date_string_to_timestamp;
+
+    fn day(date: &str) -> i64 {
+        date_string_to_timestamp(date).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    // Cuenta con saldo inicial 100
+    fn bank_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn.execute(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, is_active, created_at)
+             VALUES ('bank', 'Banco', 'bank', 'USD', 100, 1, 0)",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn insert_record(conn: &Connection, id: &str, kind: &str, amount: f64, timestamp: i64) {
+        conn.execute(
+            "INSERT INTO records (id, type, timestamp, amount, account_id, file_path)
+             VALUES (?1, ?2, ?3, ?4, 'bank', '')",
+            params![id, kind, timestamp, amount],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn weeks_close_on_sunday_and_the_last_period_is_cut_at_the_end() {
+        let ends = period_ends(
+            date("2024-01-03"),
+            date("2024-01-20"),
+            BalanceInterval::Week,
+        );
+
+        assert_eq!(
+            ends,
+            vec![date("2024-01-07"), date("2024-01-14"), date("2024-01-20")]
+        );
+    }
+
+    #[test]
+    fn months_close_on_their_last_day() {
+        let ends = period_ends(
+            date("2024-01-15"),
+            date("2024-03-10"),
+            BalanceInterval::Month,
+        );
+
+        assert_eq!(
+            ends,
+            vec![date("2024-01-31"), date("2024-02-29"), date("2024-03-10")]
+        );
+    }
+
+    #[test]
+    fn history_starts_from_the_balance_before_the_range() {
+        let conn = bank_db();
+        insert_record(&conn, "r1", "expense", 30.0, day("2024-01-05"));
+        insert_record(&conn, "r2", "income", 50.0, day("2024-01-20"));
+        insert_record(&conn, "r3", "expense", 10.0, day("2024-02-10"));
+
+        let history = get_account_balance_history(
+            &conn,
+            &["bank".to_string()],
+            day("2024-01-10"),
+            day("2024-02-15"),
+            BalanceInterval::Month,
+        )
+        .unwrap();
+
+        let balances: Vec<f64> = history[0].points.iter().map(|p| p.balance).collect();
+        assert!((history[0].opening_balance - 70.0).abs() < 1e-9);
+        assert_eq!(balances, vec![120.0, 110.0]);
+        assert_eq!(history[0].points[1].date, "2024-02-15");
+    }
+
+    #[test]
+    fn reversed_range_is_rejected() {
+        let conn = bank_db();
+
+        let result = get_account_balance_history(
+            &conn,
+            &[],
+            day("2024-02-01"),
+            day("2024-01-01"),
+            BalanceInterval::Day,
+        );
+
+        assert!(matches!(result, Err(AppError::ConfigError(_))));
+    }
+
+    #[test]
+    fn ledger_pages_run_newest_first_with_the_balance_after_each_record() {
+        let conn = bank_db();
+        insert_record(&conn, "r1", "expense", 30.0, day("2024-01-05"));
+        insert_record(&conn, "r2", "income", 50.0, day("2024-01-20"));
+        insert_record(&conn, "r3", "expense", 10.0, day("2024-02-10"));
+
+        let first = get_account_ledger(&conn, "bank", 1, 2).unwrap();
+        let second = get_account_ledger(&conn, "bank", 2, 2).unwrap();
+
+        let summary = |page: &Pagination<LedgerEntryDto>| -> Vec<(String, f64)> {
+            page.items
+                .iter()
+                .map(|e| (e.id.clone(), e.running_balance))
+                .collect()
+        };
+        assert_eq!(
+            summary(&first),
+            vec![("r3".to_string(), 110.0), ("r2".to_string(), 120.0)]
+        );
+        assert_eq!(summary(&second), vec![("r1".to_string(), 70.0)]);
+        assert_eq!(first.total_items, 3);
+        assert_eq!(first.total_pages, 2);
+        assert!((first.items[0].signed_amount + 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ledger_of_a_missing_account_is_not_found() {
+        let conn = bank_db();
+
+        assert!(matches!(
+            get_account_ledger(&conn, "missing", 1, 10),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn opening_date_starts_the_history_and_warns_about_older_records() {
+        let conn = bank_db();
+        conn.execute(
+            "UPDATE accounts SET opening_date = ?1 WHERE id = 'bank'",
+            [day("2024-01-10")],
+        )
+        .unwrap();
+        insert_record(&conn, "old", "expense", 40.0, day("2024-01-05"));
+        insert_record(&conn, "r1", "expense", 30.0, day("2024-01-12"));
+
+        let history = get_account_balance_history(
+            &conn,
+            &["bank".to_string()],
+            day("2024-01-01"),
+            day("2024-01-14"),
+            BalanceInterval::Week,
+        )
+        .unwrap();
+        let balances: Vec<f64> = history[0].points.iter().map(|p| p.balance).collect();
+        assert!(history[0].opening_balance.abs() < 1e-9);
+        assert_eq!(balances, vec![0.0, 70.0]);
+
+        let ledger = get_account_ledger(&conn, "bank", 1, 10).unwrap();
+        assert_eq!(ledger.total_items, 1);
+
+        let warnings = get_opening_date_warnings(&conn).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].records_before_opening, 1);
+        assert_eq!(warnings[0].earliest_timestamp, day("2024-01-05"));
+    }
+}
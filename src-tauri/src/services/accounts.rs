@@ -1,10 +1,64 @@
 // Accounts functionality
 
+use crate::domain::config_models::CreditCardSettings;
 use crate::dto::account_info_dto::AccountInfoDto;
+use crate::services::investments::get_investment_totals;
+use crate::services::loans::{load_loan_settings, save_loan_settings};
 use crate::{domain::error::AppError, helpers::datetime_helpers::timestamp_now};
-use rusqlite::{Connection, Result};
+use rusqlite::{params, Connection, Result};
 
-pub fn get_accounts_with_balance(conn: &mut Connection) -> Result<Vec<AccountInfoDto>, AppError> {
+// Efecto de un registro (alias r) sobre el saldo de la cuenta ?1
+pub const SIGNED_AMOUNT_SQL: &str = "CASE
+    WHEN r.account_id = ?1 AND r.type = 'income' THEN r.amount
+    WHEN r.account_id = ?1 AND r.type IN ('expense', 'transfer') THEN -r.amount
+    WHEN r.to_account_id = ?1 AND r.type = 'transfer' THEN r.amount
+    ELSE 0
+END";
+
+// Los registros anteriores a la fecha de apertura de la cuenta ?1 ya están
+// incluidos en su saldo inicial
+pub const AFTER_OPENING_SQL: &str = "r.timestamp >= COALESCE((SELECT oa.opening_date FROM accounts oa WHERE oa.id = ?1), r.timestamp)";
+
+// Intereses de las cuotas de compras de la cuenta ?1: cada uno se carga a la
+// tarjeta en la fecha de su cuota, igual que en los reportes de gastos
+pub const INSTALLMENT_INTEREST_SQL: &str = "SELECT r.id AS record_id, r.category_id, i.number, i.due_timestamp, i.interest
+    FROM record_installments i
+    JOIN records r ON r.id = i.record_id
+    WHERE r.account_id = ?1 AND r.type = 'expense' AND i.interest > 0
+      AND r.timestamp >= COALESCE((SELECT oa.opening_date FROM accounts oa WHERE oa.id = ?1), r.timestamp)";
+
+// Clase contable por defecto según el tipo de cuenta
+pub fn default_account_class(account_type: &str) -> &'static str {
+    match account_type {
+        "credit" | "loan" => "liability",
+        _ => "asset",
+    }
+}
+
+pub fn resolve_account_class(account_type: &str, class_override: Option<&str>) -> String {
+    match class_override {
+        Some(class) => class.to_string(),
+        None => default_account_class(account_type).to_string(),
+    }
+}
+
+pub fn validate_account_class_override(value: Option<&str>) -> Result<(), AppError> {
+    match value {
+        None | Some("asset") | Some("liability") | Some("auto") => Ok(()),
+        Some(other) => Err(AppError::ConfigError(format!(
+            "Clase de cuenta inválida: {} (use asset, liability o auto)",
+            other
+        ))),
+    }
+}
+
+// archived: None = todas, Some(false) = activas, Some(true) = archivadas
+pub fn get_accounts_with_balance(
+    conn: &mut Connection,
+    archived: Option<bool>,
+) -> Result<Vec<AccountInfoDto>, AppError> {
+    let is_active = archived.map(|a| if a { 0 } else { 1 });
+    let now = timestamp_now();
     let mut stmt = conn
         .prepare(
             r#"
@@ -16,14 +70,29 @@ pub fn get_accounts_with_balance(conn: &mut Connection) -> Result<Vec<AccountInf
                 a.initial_balance,
                 SUM(
                     CASE
-                        WHEN r.type = 'income' THEN r.amount
-                        WHEN r.type = 'expense' THEN -r.amount
+                        WHEN r.account_id = a.id AND r.type = 'income' THEN r.amount
+                        WHEN r.account_id = a.id AND r.type IN ('expense', 'transfer') THEN -r.amount
+                        WHEN r.to_account_id = a.id AND r.type = 'transfer' THEN r.amount
                         ELSE 0
                     END
                 ) as balance,
-                 a.credit_limit
+                 a.credit_limit,
+                 a.is_active,
+                 a.statement_closing_day,
+                 a.payment_due_day,
+                 a.minimum_payment_percent,
+                 a.account_class,
+                 a.opening_date,
+                 (SELECT COALESCE(SUM(i.interest), 0)
+                  FROM record_installments i
+                  JOIN records ir ON ir.id = i.record_id
+                  WHERE ir.account_id = a.id AND ir.type = 'expense' AND i.due_timestamp <= ?2
+                    AND (a.opening_date IS NULL OR ir.timestamp >= a.opening_date)
+                 ) AS installment_interest
             FROM accounts a
-            LEFT JOIN records r ON r.account_id = a.id
+            LEFT JOIN records r ON (r.account_id = a.id OR r.to_account_id = a.id)
+                AND (a.opening_date IS NULL OR r.timestamp >= a.opening_date)
+            WHERE ?1 IS NULL OR a.is_active = ?1
             GROUP BY a.id, a.name, a.type, a.currency, a.initial_balance
             "#,
         )
@@ -32,18 +101,42 @@ pub fn get_accounts_with_balance(conn: &mut Connection) -> Result<Vec<AccountInf
         })?;
 
     let rows = stmt
-        .query_map([], |row| {
+        .query_map(params![is_active, now], |row| {
             let initial_balance: f64 = row.get(4)?;
             let movement_sum: Option<f64> = row.get(5)?;
+            let installment_interest: f64 = row.get(13)?;
+            let closing_day: Option<u32> = row.get(8)?;
+            let due_day: Option<u32> = row.get(9)?;
+            let account_type: String = row.get(2)?;
+            let class_override: Option<String> = row.get(11)?;
 
             Ok(AccountInfoDto {
                 id: row.get(0)?,
                 name: row.get(1)?,
-                balance: initial_balance + movement_sum.unwrap_or(0.0),
-                account_type: row.get(2)?,
+                balance: initial_balance + movement_sum.unwrap_or(0.0) - installment_interest,
+                account_class: Some(resolve_account_class(
+                    &account_type,
+                    class_override.as_deref(),
+                )),
+                account_class_override: class_override,
+                account_type: Some(account_type),
                 currency: row.get(3)?,
                 initial_balance: row.get(4)?,
                 credit_limit: row.get(6)?,
+                is_active: Some(row.get::<_, i32>(7)? == 1),
+                credit_card: match (closing_day, due_day) {
+                    (Some(statement_closing_day), Some(payment_due_day)) => {
+                        Some(CreditCardSettings {
+                            statement_closing_day,
+                            payment_due_day,
+                            minimum_payment_percent: row.get(10)?,
+                        })
+                    }
+                    _ => None,
+                },
+                loan: None,
+                market_value: None,
+                opening_date: row.get(12)?,
             })
         })
         .map_err(|e| {
@@ -56,6 +149,22 @@ pub fn get_accounts_with_balance(conn: &mut Connection) -> Result<Vec<AccountInf
             AppError::DatabaseError(format!("Error leyendo fila get_accounts_with_balance: {e}"))
         })?);
     }
+    drop(stmt);
+
+    // Las cuentas de inversión suman el efectivo de compras/ventas y el valor de mercado
+    let investment_totals = get_investment_totals(conn, timestamp_now())?;
+
+    for account in accounts.iter_mut() {
+        if account.account_type.as_deref() == Some("loan") {
+            account.loan = load_loan_settings(conn, &account.id)?;
+        }
+        if let Some((cash, market_value)) = investment_totals.get(&account.id) {
+            account.balance += cash + market_value;
+            account.market_value = Some(*market_value);
+        } else if account.account_type.as_deref() == Some("investment") {
+            account.market_value = Some(0.0);
+        }
+    }
 
     Ok(accounts)
 }
@@ -70,9 +179,14 @@ pub fn create_account_in_database(
     let currency = account.currency.unwrap_or_else(|| "USD".to_string());
     let initial_balance = account.initial_balance.unwrap_or(0.0);
     let credit_limit = account.credit_limit;
+    let credit_card = account.credit_card.as_ref();
+    let account_class = account
+        .account_class_override
+        .clone()
+        .filter(|c| c != "auto");
 
     conn.execute(
-        "INSERT INTO accounts (id, name, type, currency, initial_balance, credit_limit, is_active, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        "INSERT INTO accounts (id, name, type, currency, initial_balance, credit_limit, is_active, created_at, statement_closing_day, payment_due_day, minimum_payment_percent, account_class, opening_date) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
         (
             &account_id,
             &account.name,
@@ -82,10 +196,19 @@ pub fn create_account_in_database(
             credit_limit,
             1, // is_active
             created_at,
+            credit_card.map(|c| c.statement_closing_day),
+            credit_card.map(|c| c.payment_due_day),
+            credit_card.and_then(|c| c.minimum_payment_percent),
+            account_class,
+            account.opening_date,
         ),
     )
     .map_err(|e| AppError::DatabaseError(format!("Error creando cuenta: {e}")))?;
 
+    if let Some(loan) = &account.loan {
+        save_loan_settings(conn, account_id, loan)?;
+    }
+
     Ok(())
 }
 
@@ -123,6 +246,47 @@ pub fn update_account_in_database(
     )
     .map_err(|e| AppError::DatabaseError(format!("Error actualizando cuenta: {e}")))?;
 
+    // Igual que en el JSON, la configuración de tarjeta solo se reemplaza si viene
+    if let Some(credit_card) = &account.credit_card {
+        conn.execute(
+            "UPDATE accounts
+             SET statement_closing_day = ?1,
+                 payment_due_day = ?2,
+                 minimum_payment_percent = ?3
+             WHERE id = ?4",
+            (
+                credit_card.statement_closing_day,
+                credit_card.payment_due_day,
+                credit_card.minimum_payment_percent,
+                account.id.as_str(),
+            ),
+        )
+        .map_err(|e| AppError::DatabaseError(format!("Error actualizando cuenta: {e}")))?;
+    }
+
+    if let Some(opening_date) = account.opening_date {
+        conn.execute(
+            "UPDATE accounts SET opening_date = ?1 WHERE id = ?2",
+            (opening_date, account.id.as_str()),
+        )
+        .map_err(|e| AppError::DatabaseError(format!("Error actualizando cuenta: {e}")))?;
+    }
+
+    if let Some(loan) = &account.loan {
+        save_loan_settings(conn, &account.id, loan)?;
+    }
+
+    if let Some(class_override) = &account.account_class_override {
+        conn.execute(
+            "UPDATE accounts SET account_class = ?1 WHERE id = ?2",
+            (
+                Some(class_override.as_str()).filter(|c| *c != "auto"),
+                account.id.as_str(),
+            ),
+        )
+        .map_err(|e| AppError::DatabaseError(format!("Error actualizando cuenta: {e}")))?;
+    }
+
     Ok(())
 }
 
@@ -162,3 +326,253 @@ pub fn delete_account_if_no_records(
 
     Ok(true)
 }
+
+// Mueve los registros de una cuenta a otra, borra las transferencias que
+// quedarían entre la misma cuenta y elimina la cuenta original.
+// `tx` es la transacción del llamador, que la confirma al terminar con los archivos.
+pub fn reassign_and_delete_account_in_database(
+    tx: &Connection,
+    account_id: &str,
+    target_account_id: &str,
+    removed_record_ids: &[String],
+) -> Result<(), AppError> {
+    for record_id in removed_record_ids {
+        for sql in [
+            "DELETE FROM records WHERE id = ?1",
+            "DELETE FROM record_installments WHERE record_id = ?1",
+            "DELETE FROM loan_payments WHERE record_id = ?1",
+            "DELETE FROM record_tags WHERE record_id = ?1",
+        ] {
+            tx.execute(sql, [record_id])
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        }
+    }
+
+    for sql in [
+        "UPDATE records SET account_id = ?2 WHERE account_id = ?1",
+        "UPDATE records SET to_account_id = ?2 WHERE to_account_id = ?1",
+        "UPDATE loan_payments SET loan_account_id = ?2 WHERE loan_account_id = ?1",
+        "UPDATE investment_transactions SET account_id = ?2 WHERE account_id = ?1",
+        "UPDATE goals SET account_id = ?2 WHERE account_id = ?1",
+    ] {
+        tx.execute(sql, (account_id, target_account_id))
+            .map_err(|e| AppError::DatabaseError(format!("Error reasignando registros: {e}")))?;
+    }
+
+    tx.execute("DELETE FROM loans WHERE account_id = ?1", [account_id])
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    tx.execute("DELETE FROM accounts WHERE id = ?1", [account_id])
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    Ok(())
+}
+
+// Archivar (false) o restaurar (true) una cuenta sin tocar su historial
+pub fn set_account_active_in_database(
+    conn: &mut Connection,
+    account_id: &str,
+    is_active: bool,
+) -> Result<bool, AppError> {
+    let affected = conn
+        .execute(
+            "UPDATE accounts SET is_active = ?1 WHERE id = ?2",
+            (is_active as i32, account_id),
+        )
+        .map_err(|e| AppError::DatabaseError(format!("Error archivando cuenta: {e}")))?;
+
+    Ok(affected > 0)
+}
+
+// Saldo de la cuenta incluyendo los registros con timestamp <= until (None = todos).
+// Antes de la fecha de apertura el saldo es 0 y los registros anteriores a ella no cuentan.
+// En cuentas de inversión es el efectivo: suma compras y ventas pero no el valor de mercado.
+pub fn get_account_balance_at(
+    conn: &Connection,
+    account_id: &str,
+    until: Option<i64>,
+) -> Result<f64, AppError> {
+    let sql = format!(
+        "SELECT
+            CASE
+                WHEN a.opening_date IS NULL OR ?2 IS NULL OR a.opening_date <= ?2 THEN a.initial_balance
+                ELSE 0
+            END
+            + COALESCE((
+                SELECT SUM(
+                    CASE
+                        WHEN r.account_id = a.id AND r.type = 'income' THEN r.amount
+                        WHEN r.account_id = a.id AND r.type IN ('expense', 'transfer') THEN -r.amount
+                        WHEN r.to_account_id = a.id AND r.type = 'transfer' THEN r.amount
+                        ELSE 0
+                    END
+                )
+                FROM records r
+                WHERE (r.account_id = a.id OR r.to_account_id = a.id)
+                  AND (?2 IS NULL OR r.timestamp <= ?2)
+                  AND (a.opening_date IS NULL OR r.timestamp >= a.opening_date)
+            ), 0)
+            + COALESCE((
+                SELECT SUM(t.cash_amount)
+                FROM investment_transactions t
+                WHERE t.account_id = a.id AND (?2 IS NULL OR t.timestamp <= ?2)
+                  AND (a.opening_date IS NULL OR t.timestamp >= a.opening_date)
+            ), 0)
+            -- Intereses de cuotas vencidas hasta la fecha (sin fecha: hasta hoy)
+            - COALESCE((
+                SELECT SUM(ii.interest) FROM ({interest}) ii
+                WHERE ii.due_timestamp <= COALESCE(?2, ?3)
+            ), 0)
+        FROM accounts a
+        WHERE a.id = ?1",
+        interest = INSTALLMENT_INTEREST_SQL
+    );
+
+    conn.query_row(&sql, params![account_id, until, timestamp_now()], |row| {
+        row.get(0)
+    })
+    .map_err(|e| AppError::NotFound(format!("Cuenta {}: {}", account_id, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::db_init::init_sqlite;
+    use crate::helpers::datetime_helpers::date_string_to_timestamp;
+
+    fn day(date: &str) -> i64 {
+        date_string_to_timestamp(date).unwrap()
+    }
+
+    fn accounts_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn
+    }
+
+    fn insert_account(conn: &Connection, id: &str, account_type: &str, initial_balance: f64) {
+        conn.execute(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, is_active, created_at)
+             VALUES (?1, ?1, ?2, 'USD', ?3, 1, 0)",
+            params![id, account_type, initial_balance],
+        )
+        .unwrap();
+    }
+
+    fn insert_record(
+        conn: &Connection,
+        id: &str,
+        kind: &str,
+        amount: f64,
+        account_id: &str,
+        timestamp: i64,
+    ) {
+        conn.execute(
+            "INSERT INTO records (id, type, timestamp, amount, account_id, file_path)
+             VALUES (?1, ?2, ?3, ?4, ?5, '')",
+            params![id, kind, timestamp, amount, account_id],
+        )
+        .unwrap();
+    }
+
+    fn ids(accounts: &[AccountInfoDto]) -> Vec<&str> {
+        accounts.iter().map(|a| a.id.as_str()).collect()
+    }
+
+    #[test]
+    fn archived_accounts_keep_their_balance_and_are_listed_apart() {
+        let mut conn = accounts_db();
+        insert_account(&conn, "bank", "bank", 100.0);
+        insert_account(&conn, "old", "cash", 50.0);
+        insert_record(&conn, "r1", "expense", 20.0, "old", day("2024-01-10"));
+
+        assert!(set_account_active_in_database(&mut conn, "old", false).unwrap());
+        assert!(!set_account_active_in_database(&mut conn, "missing", false).unwrap());
+
+        let active = get_accounts_with_balance(&mut conn, Some(false)).unwrap();
+        let archived = get_accounts_with_balance(&mut conn, Some(true)).unwrap();
+        assert_eq!(ids(&active), vec!["bank"]);
+        assert_eq!(ids(&archived), vec!["old"]);
+        assert_eq!(archived[0].is_active, Some(false));
+        assert!((archived[0].balance - 30.0).abs() < 1e-9);
+
+        assert!(set_account_active_in_database(&mut conn, "old", true).unwrap());
+        assert_eq!(get_accounts_with_balance(&mut conn, None).unwrap().len(), 2);
+        assert!(get_accounts_with_balance(&mut conn, Some(true))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn account_class_follows_the_type_unless_overridden() {
+        assert_eq!(resolve_account_class("credit", None), "liability");
+        assert_eq!(resolve_account_class("loan", None), "liability");
+        assert_eq!(resolve_account_class("bank", None), "asset");
+        assert_eq!(resolve_account_class("loan", Some("asset")), "asset");
+
+        assert!(validate_account_class_override(Some("auto")).is_ok());
+        assert!(matches!(
+            validate_account_class_override(Some("equity")),
+            Err(AppError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn reassigning_moves_the_history_and_deletes_the_account() {
+        let conn = accounts_db();
+        insert_account(&conn, "old", "cash", 0.0);
+        insert_account(&conn, "bank", "bank", 0.0);
+        insert_account(&conn, "wallet", "cash", 0.0);
+        insert_record(&conn, "r1", "expense", 20.0, "old", day("2024-01-10"));
+        insert_record(&conn, "t1", "transfer", 5.0, "old", day("2024-01-11"));
+        insert_record(&conn, "t2", "transfer", 8.0, "wallet", day("2024-01-12"));
+        conn.execute_batch(
+            "UPDATE records SET to_account_id = 'bank' WHERE id = 't1';
+             UPDATE records SET to_account_id = 'old' WHERE id = 't2';
+             INSERT INTO record_tags (record_id, tag_id) VALUES ('t1', 'tag');
+             INSERT INTO goals (id, name, target_amount, target_date, start_date, account_id)
+             VALUES ('g1', 'Viaje', 100, 0, 0, 'old');",
+        )
+        .unwrap();
+
+        // t1 quedaría como una transferencia de bank a bank
+        reassign_and_delete_account_in_database(&conn, "old", "bank", &["t1".to_string()]).unwrap();
+
+        let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(count("SELECT COUNT(*) FROM accounts WHERE id = 'old'"), 0);
+        assert_eq!(count("SELECT COUNT(*) FROM records WHERE id = 't1'"), 0);
+        assert_eq!(count("SELECT COUNT(*) FROM record_tags"), 0);
+        assert_eq!(
+            count("SELECT COUNT(*) FROM records WHERE account_id = 'bank' AND id = 'r1'"),
+            1
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM records WHERE to_account_id = 'bank' AND id = 't2'"),
+            1
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM goals WHERE account_id = 'bank'"),
+            1
+        );
+    }
+
+    #[test]
+    fn records_before_the_opening_date_are_already_in_the_initial_balance() {
+        let mut conn = accounts_db();
+        insert_account(&conn, "bank", "bank", 100.0);
+        conn.execute(
+            "UPDATE accounts SET opening_date = ?1 WHERE id = 'bank'",
+            [day("2024-02-01")],
+        )
+        .unwrap();
+        insert_record(&conn, "before", "expense", 40.0, "bank", day("2024-01-15"));
+        insert_record(&conn, "after", "expense", 10.0, "bank", day("2024-02-10"));
+
+        let balance_at = |until: &str| get_account_balance_at(&conn, "bank", Some(day(until)));
+        assert!(balance_at("2024-01-20").unwrap().abs() < 1e-9);
+        assert!((balance_at("2024-02-05").unwrap() - 100.0).abs() < 1e-9);
+        assert!((balance_at("2024-02-15").unwrap() - 90.0).abs() < 1e-9);
+
+        let accounts = get_accounts_with_balance(&mut conn, None).unwrap();
+        assert!((accounts[0].balance - 90.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,656 @@
+// Importación de journals de beancount hacia el modelo del workspace
+
+use crate::domain::config_models::{AccountItem, CategoryItem, RecordItem};
+use crate::dto::import_report_dto::SkippedEntryDto;
+use crate::helpers::datetime_helpers::{date_string_to_timestamp, timestamp_now};
+use std::collections::HashMap;
+
+pub struct BeancountImport {
+    pub accounts: Vec<AccountItem>,
+    pub categories: Vec<CategoryItem>,
+    pub records: Vec<RecordItem>,
+    pub currency: Option<String>,
+    pub skipped: Vec<SkippedEntryDto>,
+}
+
+struct Posting {
+    account: String,
+    amount: Option<f64>,
+    currency: Option<String>,
+    line: usize,
+}
+
+struct PendingTransaction {
+    line: usize,
+    header: String,
+    timestamp: i64,
+    payee: Option<String>,
+    narration: Option<String>,
+    postings: Vec<Posting>,
+    unmappable: Option<String>,
+}
+
+#[derive(PartialEq)]
+enum Root {
+    Asset,
+    Liability,
+    Income,
+    Expense,
+    Equity,
+}
+
+pub fn parse_beancount(content: &str, default_currency: &str) -> BeancountImport {
+    let mut import = BeancountImport {
+        accounts: Vec::new(),
+        categories: Vec::new(),
+        records: Vec::new(),
+        currency: None,
+        skipped: Vec::new(),
+    };
+
+    // Nombre de la cuenta en beancount -> índice en accounts / categories
+    let mut account_index: HashMap<String, usize> = HashMap::new();
+    let mut category_index: HashMap<String, usize> = HashMap::new();
+    let mut currency_usage: HashMap<String, usize> = HashMap::new();
+    let mut current: Option<PendingTransaction> = None;
+
+    for (idx, raw_line) in content.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = strip_comment(raw_line);
+
+        // Las líneas indentadas pertenecen a la transacción en curso
+        if raw_line.starts_with(' ') || raw_line.starts_with('\t') {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if let Some(tx) = current.as_mut() {
+                // Metadata (key: value) no se importa
+                if is_metadata(trimmed) {
+                    continue;
+                }
+                match parse_posting(trimmed, line_no) {
+                    Ok(posting) => tx.postings.push(posting),
+                    Err(reason) => {
+                        if tx.unmappable.is_none() {
+                            tx.unmappable = Some(reason);
+                        }
+                    }
+                }
+            }
+            continue;
+        }
+
+        // Una línea no indentada cierra la transacción anterior
+        if let Some(tx) = current.take() {
+            finish_transaction(
+                tx,
+                &mut import,
+                &mut account_index,
+                &category_index,
+                &mut currency_usage,
+            );
+        }
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let mut tokens = trimmed.split_whitespace();
+        let first = tokens.next().unwrap_or_default();
+        let Some(timestamp) = date_string_to_timestamp(first) else {
+            // option, include, plugin, pushtag, etc.
+            continue;
+        };
+        let directive = tokens.next().unwrap_or_default();
+
+        match directive {
+            "open" => {
+                let Some(name) = tokens.next() else { continue };
+                let currencies: Vec<String> = tokens
+                    .flat_map(|t| t.split(','))
+                    .filter(|t| !t.is_empty() && !t.starts_with('"'))
+                    .map(|t| t.to_string())
+                    .collect();
+                open_account(
+                    name,
+                    currencies.first().cloned(),
+                    timestamp,
+                    default_currency,
+                    &mut import,
+                    &mut account_index,
+                    &mut category_index,
+                    &mut currency_usage,
+                );
+            }
+            "close" => {
+                let Some(name) = tokens.next() else { continue };
+                if let Some(&i) = account_index.get(name) {
+                    import.accounts[i].is_active = false;
+                } else if let Some(&i) = category_index.get(name) {
+                    import.categories[i].is_active = false;
+                }
+            }
+            "*" | "!" | "txn" => {
+                let strings = quoted_strings(trimmed);
+                let (payee, narration) = match strings.len() {
+                    0 => (None, None),
+                    1 => (None, Some(strings[0].clone())),
+                    _ => (Some(strings[0].clone()), Some(strings[1].clone())),
+                };
+                current = Some(PendingTransaction {
+                    line: line_no,
+                    header: trimmed.to_string(),
+                    timestamp,
+                    payee,
+                    narration,
+                    postings: Vec::new(),
+                    unmappable: None,
+                });
+            }
+            "price" => import.skipped.push(SkippedEntryDto {
+                line: line_no,
+                reason: "Directiva de precio no soportada".into(),
+                text: trimmed.to_string(),
+            }),
+            "commodity" => import.skipped.push(SkippedEntryDto {
+                line: line_no,
+                reason: "Declaración de commodity ignorada".into(),
+                text: trimmed.to_string(),
+            }),
+            // balance, pad, note, document, event, query, custom
+            _ => {}
+        }
+    }
+
+    if let Some(tx) = current.take() {
+        finish_transaction(
+            tx,
+            &mut import,
+            &mut account_index,
+            &category_index,
+            &mut currency_usage,
+        );
+    }
+
+    import.currency = currency_usage
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(currency, _)| currency);
+
+    import
+}
+
+#[allow(clippy::too_many_arguments)]
+fn open_account(
+    name: &str,
+    currency: Option<String>,
+    timestamp: i64,
+    default_currency: &str,
+    import: &mut BeancountImport,
+    account_index: &mut HashMap<String, usize>,
+    category_index: &mut HashMap<String, usize>,
+    currency_usage: &mut HashMap<String, usize>,
+) {
+    if account_index.contains_key(name) || category_index.contains_key(name) {
+        return;
+    }
+
+    match root_of(name) {
+        Some(Root::Asset) | Some(Root::Liability) => {
+            let currency = currency.unwrap_or_else(|| default_currency.to_string());
+            *currency_usage.entry(currency.clone()).or_insert(0) += 1;
+            let account_type = if root_of(name) == Some(Root::Liability) {
+                "credit"
+            } else if name.to_lowercase().contains("cash") || name.to_lowercase().contains("efectivo")
+            {
+                "cash"
+            } else {
+                "debit"
+            };
+            account_index.insert(name.to_string(), import.accounts.len());
+            import.accounts.push(AccountItem {
+                id: uuid::Uuid::new_v4().to_string(),
+                name: display_name(name),
+                r#type: account_type.into(),
+                currency,
+                initial_balance: 0.0,
+                credit_limit: None,
+                is_active: true,
+                created_at: timestamp,
+                credit_card: None,
+                account_class: None,
+                loan: None,
+                opening_date: None,
+            });
+        }
+        Some(Root::Income) | Some(Root::Expense) => {
+            let category_type = if root_of(name) == Some(Root::Income) {
+                "income"
+            } else {
+                "expense"
+            };
+            category_index.insert(name.to_string(), import.categories.len());
+            import.categories.push(CategoryItem {
+                id: uuid::Uuid::new_v4().to_string(),
+                name: display_name(name),
+                r#type: category_type.into(),
+                icon: "tag".into(),
+                color: "#64748b".into(),
+                created_by_user: true,
+                is_active: true,
+                created_at: timestamp_now(),
+                parent_id: None,
+            });
+        }
+        // Equity solo se usa para saldos iniciales
+        _ => {}
+    }
+}
+
+fn finish_transaction(
+    tx: PendingTransaction,
+    import: &mut BeancountImport,
+    account_index: &mut HashMap<String, usize>,
+    category_index: &HashMap<String, usize>,
+    currency_usage: &mut HashMap<String, usize>,
+) {
+    let skip = |import: &mut BeancountImport, reason: &str| {
+        import.skipped.push(SkippedEntryDto {
+            line: tx.line,
+            reason: reason.to_string(),
+            text: tx.header.clone(),
+        });
+    };
+
+    if let Some(reason) = &tx.unmappable {
+        skip(import, reason);
+        return;
+    }
+
+    match tx.postings.len() {
+        2 => {}
+        0 | 1 => {
+            skip(
+                import,
+                "Transacción incompleta: necesita al menos dos postings",
+            );
+            return;
+        }
+        _ => {
+            skip(import, "Transacción con más de dos postings (multi-leg)");
+            return;
+        }
+    }
+
+    // 1. Completar el monto elidido
+    let (a, b) = (&tx.postings[0], &tx.postings[1]);
+    let (amount_a, amount_b) = match (a.amount, b.amount) {
+        (Some(x), Some(y)) => (x, y),
+        (Some(x), None) => (x, -x),
+        (None, Some(y)) => (-y, y),
+        (None, None) => {
+            skip(import, "Transacción sin montos");
+            return;
+        }
+    };
+
+    // Postings en commodities distintas necesitan precios o conversiones
+    if let (Some(currency_a), Some(currency_b)) = (&a.currency, &b.currency) {
+        if currency_a != currency_b {
+            skip(
+                import,
+                &format!(
+                    "Postings en commodities distintas ({} y {}): precios o conversiones no soportados",
+                    currency_a, currency_b
+                ),
+            );
+            return;
+        }
+    }
+
+    if (amount_a + amount_b).abs() > 0.005 {
+        skip(import, "Transacción desbalanceada");
+        return;
+    }
+
+    let currency = a.currency.clone().or_else(|| b.currency.clone());
+
+    // 2. Verificar que las cuentas de activos/pasivos usen la misma moneda
+    for posting in [a, b] {
+        if let (Some(&i), Some(cur)) = (account_index.get(&posting.account), &currency) {
+            if &import.accounts[i].currency != cur {
+                import.skipped.push(SkippedEntryDto {
+                    line: posting.line,
+                    reason: format!(
+                        "Moneda {} distinta a la de la cuenta ({})",
+                        cur, import.accounts[i].currency
+                    ),
+                    text: tx.header.clone(),
+                });
+                return;
+            }
+        }
+    }
+
+    let description = match (&tx.payee, &tx.narration) {
+        (Some(p), Some(n)) if !n.is_empty() => Some(format!("{} - {}", p, n)),
+        (Some(p), _) => Some(p.clone()),
+        (None, n) => n.clone(),
+    };
+    let metadata = tx
+        .payee
+        .as_ref()
+        .map(|payee| serde_json::json!({ "payee": payee, "source": "beancount" }));
+
+    let kind = |name: &str| root_of(name);
+    let mut record = RecordItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        r#type: String::new(),
+        amount: 0.0,
+        currency: currency.clone(),
+        timestamp: tx.timestamp,
+        category_id: None,
+        account_id: String::new(),
+        to_account_id: None,
+        description,
+        metadata,
+        ..Default::default()
+    };
+
+    let is_money = |r: &Option<Root>| matches!(r, Some(Root::Asset) | Some(Root::Liability));
+    let pairs = [(a, amount_a, b, amount_b), (b, amount_b, a, amount_a)];
+
+    for (money, money_amount, other, _) in pairs {
+        let (money_kind, other_kind) = (kind(&money.account), kind(&other.account));
+        if !is_money(&money_kind) {
+            continue;
+        }
+        let Some(&acc_i) = account_index.get(&money.account) else {
+            skip(import, "Cuenta sin directiva open");
+            return;
+        };
+
+        match other_kind {
+            Some(Root::Expense) | Some(Root::Income) => {
+                let Some(&cat_i) = category_index.get(&other.account) else {
+                    skip(import, "Categoría sin directiva open");
+                    return;
+                };
+                let category = &import.categories[cat_i];
+                // Reembolsos y reversos no encajan en el modelo de registro simple
+                let expected_sign = if category.r#type == "income" { 1.0 } else { -1.0 };
+                if money_amount * expected_sign <= 0.0 {
+                    skip(import, "Reverso de ingreso/gasto no soportado");
+                    return;
+                }
+                record.r#type = category.r#type.clone();
+                record.category_id = Some(category.id.clone());
+                record.account_id = import.accounts[acc_i].id.clone();
+                record.amount = money_amount.abs();
+            }
+            Some(Root::Asset) | Some(Root::Liability) => {
+                if money_amount > 0.0 {
+                    continue; // El origen es la cuenta que disminuye
+                }
+                let Some(&to_i) = account_index.get(&other.account) else {
+                    skip(import, "Cuenta sin directiva open");
+                    return;
+                };
+                record.r#type = "transfer".into();
+                record.account_id = import.accounts[acc_i].id.clone();
+                record.to_account_id = Some(import.accounts[to_i].id.clone());
+                record.amount = money_amount.abs();
+            }
+            Some(Root::Equity) => {
+                // Saldo inicial: se acumula en la cuenta en lugar de crear un registro
+                import.accounts[acc_i].initial_balance += money_amount;
+                if let Some(cur) = &currency {
+                    *currency_usage.entry(cur.clone()).or_insert(0) += 1;
+                }
+                return;
+            }
+            None => {
+                skip(import, "Cuenta con raíz desconocida");
+                return;
+            }
+        }
+        break;
+    }
+
+    if record.r#type.is_empty() {
+        skip(import, "Transacción sin cuenta de activos o pasivos");
+        return;
+    }
+
+    import.records.push(record);
+}
+
+fn parse_posting(text: &str, line: usize) -> Result<Posting, String> {
+    let mut tokens: Vec<&str> = text.split_whitespace().collect();
+    // Flag opcional por posting
+    if matches!(tokens.first(), Some(&"*") | Some(&"!")) {
+        tokens.remove(0);
+    }
+
+    let account = tokens
+        .first()
+        .ok_or_else(|| "Posting vacío".to_string())?
+        .to_string();
+
+    if tokens.iter().any(|t| t.starts_with('@') || t.starts_with('{')) {
+        return Err("Posting con costo o precio (commodity)".into());
+    }
+
+    let amount = match tokens.get(1) {
+        Some(raw) => Some(
+            raw.replace(',', "")
+                .parse::<f64>()
+                .map_err(|_| format!("Monto no soportado: {}", raw))?,
+        ),
+        None => None,
+    };
+
+    Ok(Posting {
+        account,
+        amount,
+        currency: tokens.get(2).map(|c| c.to_string()),
+        line,
+    })
+}
+
+fn root_of(name: &str) -> Option<Root> {
+    match name.split(':').next().unwrap_or_default() {
+        "Assets" => Some(Root::Asset),
+        "Liabilities" => Some(Root::Liability),
+        "Income" => Some(Root::Income),
+        "Expenses" => Some(Root::Expense),
+        "Equity" => Some(Root::Equity),
+        _ => None,
+    }
+}
+
+// Assets:Bank:Bancolombia-Ahorros -> "Bank Bancolombia Ahorros"
+fn display_name(name: &str) -> String {
+    let segments: Vec<&str> = name.split(':').skip(1).collect();
+    let joined = if segments.is_empty() {
+        name.to_string()
+    } else {
+        segments.join(" ")
+    };
+    joined.replace('-', " ")
+}
+
+fn is_metadata(text: &str) -> bool {
+    let mut parts = text.splitn(2, ':');
+    let key = parts.next().unwrap_or_default();
+    parts.next().is_some()
+        && !key.is_empty()
+        && key.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            ';' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn quoted_strings(text: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut current: Option<String> = None;
+    let mut escaped = false;
+
+    for c in text.chars() {
+        match current.as_mut() {
+            Some(buf) => {
+                if escaped {
+                    buf.push(c);
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    strings.push(current.take().unwrap_or_default());
+                } else {
+                    buf.push(c);
+                }
+            }
+            None if c == '"' => current = Some(String::new()),
+            None => {}
+        }
+    }
+
+    strings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOURNAL: &str = r#"option "operating_currency" "USD"
+2024-01-01 open Assets:Bank:Checking USD
+2024-01-01 open Assets:Cash USD
+2024-01-01 open Expenses:Food
+2024-01-01 open Income:Salary
+2024-01-01 open Equity:Opening-Balances
+
+2024-01-01 * "Saldo inicial"
+  Assets:Bank:Checking   1000.00 USD
+  Equity:Opening-Balances
+
+2024-01-05 * "Market" "Groceries" ; comentario
+  Expenses:Food   25.50 USD
+  Assets:Bank:Checking
+
+2024-01-10 * "Withdrawal"
+  Assets:Bank:Checking  -100.00 USD
+  Assets:Cash            100.00 USD
+
+2024-01-31 * "Payroll"
+  Income:Salary         -2000.00 USD
+  Assets:Bank:Checking
+"#;
+
+    fn account_id(import: &BeancountImport, name: &str) -> String {
+        import
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| a.id.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn maps_expenses_income_transfers_and_opening_balances() {
+        let import = parse_beancount(JOURNAL, "COP");
+
+        assert!(import.skipped.is_empty(), "{:?}", import.skipped);
+        assert_eq!(import.currency.as_deref(), Some("USD"));
+        assert_eq!(import.accounts.len(), 2);
+        assert_eq!(import.categories.len(), 2);
+
+        let checking = account_id(&import, &display_name("Assets:Bank:Checking"));
+        let cash = account_id(&import, &display_name("Assets:Cash"));
+        let checking_account = import.accounts.iter().find(|a| a.id == checking).unwrap();
+        assert_eq!(checking_account.initial_balance, 1000.0);
+        assert_eq!(checking_account.r#type, "debit");
+
+        let types: Vec<&str> = import.records.iter().map(|r| r.r#type.as_str()).collect();
+        assert_eq!(types, vec!["expense", "transfer", "income"]);
+
+        let expense = &import.records[0];
+        assert_eq!(expense.amount, 25.5);
+        assert_eq!(expense.account_id, checking);
+        assert_eq!(expense.description.as_deref(), Some("Market - Groceries"));
+
+        let transfer = &import.records[1];
+        assert_eq!(transfer.account_id, checking);
+        assert_eq!(transfer.to_account_id.as_deref(), Some(cash.as_str()));
+        assert_eq!(transfer.amount, 100.0);
+
+        assert_eq!(import.records[2].amount, 2000.0);
+    }
+
+    #[test]
+    fn incomplete_and_multi_leg_transactions_are_skipped() {
+        let journal = r#"2024-01-01 open Assets:Bank USD
+2024-01-01 open Expenses:Food
+2024-01-01 open Expenses:Drinks
+
+2024-02-01 * "Solo un posting"
+  Expenses:Food   10.00 USD
+
+2024-02-02 * "Varias patas"
+  Expenses:Food    10.00 USD
+  Expenses:Drinks   5.00 USD
+  Assets:Bank
+"#;
+        let import = parse_beancount(journal, "USD");
+
+        assert!(import.records.is_empty());
+        assert_eq!(import.skipped.len(), 2);
+        assert_eq!(import.skipped[0].line, 5);
+        assert!(import.skipped[0].reason.contains("al menos dos postings"));
+        assert!(import.skipped[1].reason.contains("más de dos postings"));
+    }
+
+    #[test]
+    fn unbalanced_transactions_are_skipped() {
+        let journal = r#"2024-01-01 open Assets:Bank USD
+2024-01-01 open Expenses:Food
+
+2024-02-01 * "Descuadre"
+  Expenses:Food   10.00 USD
+  Assets:Bank     -9.00 USD
+"#;
+        let import = parse_beancount(journal, "USD");
+
+        assert!(import.records.is_empty());
+        assert_eq!(import.skipped.len(), 1);
+    }
+
+    #[test]
+    fn postings_in_different_commodities_are_skipped() {
+        let journal = r#"2024-01-01 open Assets:Bank USD
+2024-01-01 open Expenses:Travel
+
+2024-02-01 * "Hotel"
+  Expenses:Travel   100.00 EUR
+  Assets:Bank      -100.00 USD
+"#;
+        let import = parse_beancount(journal, "USD");
+
+        assert!(import.records.is_empty());
+        assert_eq!(import.skipped.len(), 1);
+        assert!(import.skipped[0].reason.contains("commodities distintas"));
+    }
+}
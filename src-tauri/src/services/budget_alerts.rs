@@ -0,0 +1,273 @@
+// Alertas al cruzar el 80% y el 100% de un presupuesto mensual
+
+use crate::domain::config_models::{AppConfig, BudgetAlertHistory, ShownBudgetAlert};
+use crate::domain::error::AppError;
+use crate::dto::budget_dto::{BudgetAlertDto, BudgetStatusDto};
+use crate::fs::budget_file_management::load_budgets;
+use crate::fs::local_storage::{load_budget_alert_history, save_budget_alert_history};
+use crate::helpers::datetime_helpers::{timestamp_now, timestamp_to_local_date};
+use crate::helpers::json_helpers::load_json;
+use crate::services::budgets::{format_month, get_budget_status};
+use chrono::Datelike;
+use rusqlite::Connection;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+pub const BUDGET_ALERT_EVENT: &str = "budget-alert";
+pub const BUDGET_ALERT_ERROR_EVENT: &str = "budget-alert-error";
+pub const BUDGET_ALERT_THRESHOLDS: [u32; 2] = [80, 100];
+
+/// Mes 'YYYY-MM' al que pertenece un timestamp
+pub fn month_of(timestamp: i64) -> String {
+    let date = timestamp_to_local_date(timestamp);
+    format_month(date.year(), date.month())
+}
+
+/// Umbrales cruzados que aún no se habían avisado. Por presupuesto se avisa
+/// solo el umbral más alto; los que dejan de cumplirse (se borró un gasto o
+/// subió el presupuesto) salen del historial para volver a avisar.
+pub fn detect_budget_alerts(
+    statuses: &[BudgetStatusDto],
+    history: &mut BudgetAlertHistory,
+) -> Vec<BudgetAlertDto> {
+    let now = timestamp_now();
+    let mut alerts = Vec::new();
+
+    for status in statuses {
+        let is_shown = |history: &BudgetAlertHistory, threshold: u32| {
+            history.shown.iter().any(|a| {
+                a.month == status.month
+                    && a.category_id == status.category_id
+                    && a.threshold == threshold
+            })
+        };
+
+        history.shown.retain(|a| {
+            !(a.month == status.month
+                && a.category_id == status.category_id
+                && status.percent_used < a.threshold as f64)
+        });
+
+        let mut highest_new = None;
+        for threshold in BUDGET_ALERT_THRESHOLDS {
+            if status.percent_used < threshold as f64 || is_shown(history, threshold) {
+                continue;
+            }
+            history.shown.push(ShownBudgetAlert {
+                month: status.month.clone(),
+                category_id: status.category_id.clone(),
+                threshold,
+                shown_at: now,
+            });
+            highest_new = Some(threshold);
+        }
+
+        if let Some(threshold) = highest_new {
+            alerts.push(BudgetAlertDto {
+                month: status.month.clone(),
+                category_id: status.category_id.clone(),
+                category_name: status.category_name.clone(),
+                threshold,
+                budgeted: status.budgeted,
+                spent: status.spent,
+                percent_used: status.percent_used,
+            });
+        }
+    }
+
+    alerts
+}
+
+fn notification_text(alert: &BudgetAlertDto, language: &str) -> (String, String) {
+    match (language, alert.threshold >= 100) {
+        ("en", true) => (
+            format!("Budget exceeded: {}", alert.category_name),
+            format!(
+                "You spent {:.0} of {:.0} in {}",
+                alert.spent, alert.budgeted, alert.month
+            ),
+        ),
+        ("en", false) => (
+            format!("{}% of budget: {}", alert.threshold, alert.category_name),
+            format!(
+                "You spent {:.0} of {:.0} in {}",
+                alert.spent, alert.budgeted, alert.month
+            ),
+        ),
+        (_, true) => (
+            format!("Presupuesto superado: {}", alert.category_name),
+            format!(
+                "Llevas {:.0} de {:.0} en {}",
+                alert.spent, alert.budgeted, alert.month
+            ),
+        ),
+        (_, false) => (
+            format!(
+                "{}% del presupuesto: {}",
+                alert.threshold, alert.category_name
+            ),
+            format!(
+                "Llevas {:.0} de {:.0} en {}",
+                alert.spent, alert.budgeted, alert.month
+            ),
+        ),
+    }
+}
+
+/// Revisa los presupuestos de los meses dados, emite un evento por cada umbral
+/// nuevo y, si app.json lo permite, muestra una notificación de escritorio.
+/// Los avisos que fallan se informan con el evento "budget-alert-error".
+pub fn check_budget_alerts(
+    app: &AppHandle,
+    conn: &Connection,
+    workspace_path: &Path,
+    app_data_dir: &Path,
+    months: &[String],
+) -> Result<Vec<BudgetAlertDto>, AppError> {
+    let budgets = load_budgets(workspace_path)?;
+    let mut history = load_budget_alert_history(app_data_dir)?;
+    let mut alerts = Vec::new();
+
+    // 1. Detectar umbrales nuevos
+    let mut checked = Vec::new();
+    for month in months {
+        if checked.contains(month) || !budgets.budgets.iter().any(|b| &b.month == month) {
+            continue;
+        }
+        let statuses = get_budget_status(conn, &budgets, month)?;
+        alerts.extend(detect_budget_alerts(&statuses, &mut history));
+        checked.push(month.clone());
+    }
+
+    if checked.is_empty() {
+        return Ok(alerts);
+    }
+
+    // 2. Avisar al frontend y, opcionalmente, al escritorio. Un aviso que falla
+    // no detiene los demás: si no llegó al frontend sale del historial para
+    // repetirse en la próxima revisión; los fallos se informan al final.
+    let app_config: AppConfig = load_json(&workspace_path.join(".finance").join("app.json"))?;
+    let mut failures = Vec::new();
+    for alert in &alerts {
+        if let Err(e) = app.emit(BUDGET_ALERT_EVENT, alert) {
+            history.shown.retain(|a| {
+                !(a.month == alert.month
+                    && a.category_id == alert.category_id
+                    && a.threshold == alert.threshold)
+            });
+            failures.push(format!("Error emitiendo alerta: {}", e));
+            continue;
+        }
+
+        if app_config.budget_notifications {
+            let (title, body) = notification_text(alert, &app_config.language);
+            if let Err(e) = app.notification().builder().title(title).body(body).show() {
+                failures.push(format!("Error mostrando notificación: {}", e));
+            }
+        }
+    }
+
+    // 3. El historial se guarda después de avisar
+    save_budget_alert_history(app_data_dir, &history)?;
+    if !failures.is_empty() {
+        report_budget_alert_error(app, &failures.join("\n"));
+    }
+
+    Ok(alerts)
+}
+
+// Los fallos de la revisión llegan al frontend como evento, igual que las alertas
+fn report_budget_alert_error(app: &AppHandle, message: &str) {
+    let _ = app.emit(BUDGET_ALERT_ERROR_EVENT, message);
+}
+
+/// Revisión tras guardar cambios: los errores se envían al frontend como evento
+/// "budget-alert-error" para no hacer fallar una operación que ya quedó guardada
+pub fn check_budget_alerts_quietly(
+    app: &AppHandle,
+    conn: &Connection,
+    workspace_path: &Path,
+    app_data_dir: Option<&Path>,
+    months: &[String],
+) {
+    let Some(app_data_dir) = app_data_dir else {
+        return;
+    };
+    if let Err(e) = check_budget_alerts(app, conn, workspace_path, app_data_dir, months) {
+        report_budget_alert_error(
+            app,
+            &format!("No se pudieron revisar las alertas de presupuesto: {}", e),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(category_id: &str, percent_used: f64) -> BudgetStatusDto {
+        BudgetStatusDto {
+            budget_id: format!("budget-{}", category_id),
+            month: "2024-05".into(),
+            category_id: category_id.into(),
+            category_name: category_id.into(),
+            color: "#000000".into(),
+            is_group: false,
+            notes: None,
+            budgeted: 100.0,
+            spent: percent_used,
+            remaining: 100.0 - percent_used,
+            percent_used,
+        }
+    }
+
+    #[test]
+    fn alerts_once_per_threshold() {
+        let mut history = BudgetAlertHistory::default();
+
+        let alerts = detect_budget_alerts(&[status("food", 85.0)], &mut history);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].threshold, 80);
+
+        assert!(detect_budget_alerts(&[status("food", 90.0)], &mut history).is_empty());
+
+        let alerts = detect_budget_alerts(&[status("food", 120.0)], &mut history);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].threshold, 100);
+    }
+
+    #[test]
+    fn jumping_past_both_thresholds_reports_only_the_highest() {
+        let mut history = BudgetAlertHistory::default();
+
+        let alerts = detect_budget_alerts(&[status("food", 150.0)], &mut history);
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].threshold, 100);
+        assert_eq!(history.shown.len(), 2);
+    }
+
+    #[test]
+    fn dropping_below_a_threshold_rearms_it() {
+        let mut history = BudgetAlertHistory::default();
+        detect_budget_alerts(&[status("food", 85.0)], &mut history);
+
+        assert!(detect_budget_alerts(&[status("food", 50.0)], &mut history).is_empty());
+        assert!(history.shown.is_empty());
+
+        let alerts = detect_budget_alerts(&[status("food", 81.0)], &mut history);
+        assert_eq!(alerts.len(), 1);
+    }
+
+    #[test]
+    fn budgets_under_the_first_threshold_do_not_alert() {
+        let mut history = BudgetAlertHistory::default();
+
+        let alerts =
+            detect_budget_alerts(&[status("food", 79.9), status("rent", 0.0)], &mut history);
+
+        assert!(alerts.is_empty());
+        assert!(history.shown.is_empty());
+    }
+}
@@ -0,0 +1,319 @@
+// Presupuestos mensuales por categoría
+
+use crate::domain::config_models::{BudgetItem, BudgetsConfig};
+use crate::domain::error::AppError;
+use crate::dto::budget_dto::BudgetStatusDto;
+use crate::helpers::datetime_helpers::{
+    end_of_day_timestamp, last_day_of_month, start_of_day_timestamp, timestamp_now,
+    timestamp_to_local_date,
+};
+use chrono::{Datelike, NaiveDate};
+use rusqlite::{params, Connection};
+
+/// Mes en formato 'YYYY-MM'
+pub fn parse_month(month: &str) -> Result<(i32, u32), AppError> {
+    let invalid = || AppError::ConfigError(format!("Mes inválido: '{}' (use YYYY-MM)", month));
+
+    let (year, month_number) = month.trim().split_once('-').ok_or_else(invalid)?;
+    let year: i32 = year.parse().map_err(|_| invalid())?;
+    let month_number: u32 = month_number.parse().map_err(|_| invalid())?;
+
+    if !(1..=12).contains(&month_number) {
+        return Err(invalid());
+    }
+
+    Ok((year, month_number))
+}
+
+pub fn format_month(year: i32, month: u32) -> String {
+    format!("{:04}-{:02}", year, month)
+}
+
+pub fn current_month() -> String {
+    let today = timestamp_to_local_date(timestamp_now());
+    format_month(today.year(), today.month())
+}
+
+// Primer y último segundo del mes en hora local
+pub fn month_bounds(year: i32, month: u32) -> (i64, i64) {
+    let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap_or_default();
+    let last =
+        NaiveDate::from_ymd_opt(year, month, last_day_of_month(year, month)).unwrap_or(first);
+
+    (start_of_day_timestamp(first), end_of_day_timestamp(last))
+}
+
+/// Copia los presupuestos de from_month a to_month (ya normalizados) y
+/// devuelve cuántos se copiaron
+pub fn copy_month_budgets(
+    budgets: &mut BudgetsConfig,
+    from_month: &str,
+    to_month: &str,
+    overwrite: bool,
+) -> usize {
+    let sources: Vec<BudgetItem> = budgets
+        .budgets
+        .iter()
+        .filter(|b| b.month == from_month)
+        .cloned()
+        .collect();
+
+    let now = timestamp_now();
+    let mut copied = 0;
+    for source in sources {
+        let existing = budgets
+            .budgets
+            .iter_mut()
+            .find(|b| b.month == to_month && b.category_id == source.category_id);
+
+        match existing {
+            Some(_) if !overwrite => continue,
+            Some(target) => {
+                target.amount = source.amount;
+                target.notes = source.notes;
+                target.updated_at = now;
+            }
+            None => budgets.budgets.push(BudgetItem {
+                id: uuid::Uuid::new_v4().to_string(),
+                month: to_month.to_string(),
+                created_at: now,
+                updated_at: now,
+                ..source
+            }),
+        }
+        copied += 1;
+    }
+
+    copied
+}
+
+/// Solo se presupuestan categorías de gastos existentes y montos no negativos
+pub fn validate_budget(conn: &Connection, category_id: &str, amount: f64) -> Result<(), AppError> {
+    if !amount.is_finite() || amount < 0.0 {
+        return Err(AppError::ConfigError(
+            "El monto del presupuesto no puede ser negativo".into(),
+        ));
+    }
+
+    let category_type: String = conn
+        .query_row(
+            "SELECT type FROM categories WHERE id = ?1",
+            [category_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| AppError::NotFound(format!("Categoría {} no encontrada", category_id)))?;
+
+    if category_type != "expense" {
+        return Err(AppError::ConfigError(
+            "Solo se pueden presupuestar categorías de gastos".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Gasto de la categoría y sus subcategorías en [from, to]; indica si tiene subcategorías
+pub fn category_spent(
+    conn: &Connection,
+    category_id: &str,
+    from: i64,
+    to: i64,
+) -> Result<(f64, bool), AppError> {
+    conn.query_row(
+        "WITH RECURSIVE tree(id) AS (
+            SELECT ?1
+            UNION ALL
+            SELECT c.id FROM categories c JOIN tree t ON c.parent_id = t.id
+         )
+         SELECT
+            COALESCE((SELECT SUM(e.amount) FROM expense_entries e
+                      WHERE e.category_id IN (SELECT id FROM tree)
+                        AND e.timestamp >= ?2 AND e.timestamp <= ?3), 0),
+            (SELECT COUNT(*) FROM tree) > 1",
+        params![category_id, from, to],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .map_err(|e| AppError::DatabaseError(e.to_string()))
+}
+
+/// Presupuestado, gastado, restante y porcentaje usado de cada presupuesto del mes
+pub fn get_budget_status(
+    conn: &Connection,
+    budgets: &BudgetsConfig,
+    month: &str,
+) -> Result<Vec<BudgetStatusDto>, AppError> {
+    let (year, month_number) = parse_month(month)?;
+    let month = format_month(year, month_number);
+    let (from, to) = month_bounds(year, month_number);
+
+    let mut statuses = Vec::new();
+    for budget in budgets.budgets.iter().filter(|b| b.month == month) {
+        let (category_name, color) = conn
+            .query_row(
+                "SELECT name, color FROM categories WHERE id = ?1",
+                [&budget.category_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap_or_else(|_| (budget.category_id.clone(), String::new()));
+
+        let (spent, is_group) = category_spent(conn, &budget.category_id, from, to)?;
+        let percent_used = if budget.amount > 0.0 {
+            spent * 100.0 / budget.amount
+        } else if spent > 0.0 {
+            100.0
+        } else {
+            0.0
+        };
+
+        statuses.push(BudgetStatusDto {
+            budget_id: budget.id.clone(),
+            month: month.clone(),
+            category_id: budget.category_id.clone(),
+            category_name,
+            color,
+            is_group,
+            notes: budget.notes.clone(),
+            budgeted: budget.amount,
+            spent,
+            remaining: budget.amount - spent,
+            percent_used,
+        });
+    }
+
+    statuses.sort_by(|a, b| a.category_name.cmp(&b.category_name));
+
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::db_init::init_sqlite;
+    use crate::helpers::datetime_helpers::date_string_to_timestamp;
+
+    fn day(date: &str) -> i64 {
+        date_string_to_timestamp(date).unwrap()
+    }
+
+    fn budget(month: &str, category_id: &str, amount: f64) -> BudgetItem {
+        BudgetItem {
+            id: format!("{}-{}", month, category_id),
+            month: month.into(),
+            category_id: category_id.into(),
+            amount,
+            notes: None,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    // Vivienda con la subcategoría Arriendo y Comida, todas de gastos
+    fn budgets_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO categories (id, name, type, icon, color, parent_id) VALUES
+                ('home', 'Vivienda', 'expense', '', '#06b6d4', NULL),
+                ('rent', 'Arriendo', 'expense', '', '', 'home'),
+                ('food', 'Comida', 'expense', '', '', NULL),
+                ('salary', 'Salario', 'income', '', '', NULL);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn insert_expense(conn: &Connection, id: &str, category_id: &str, amount: f64, timestamp: i64) {
+        conn.execute(
+            "INSERT INTO records (id, type, timestamp, amount, account_id, category_id, file_path)
+             VALUES (?1, 'expense', ?2, ?3, 'bank', ?4, '')",
+            params![id, timestamp, amount, category_id],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn months_must_be_year_and_month() {
+        assert_eq!(parse_month(" 2024-3 ").unwrap(), (2024, 3));
+        assert_eq!(format_month(2024, 3), "2024-03");
+        for invalid in ["2024", "2024-13", "2024-00", "marzo"] {
+            assert!(parse_month(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn only_expense_categories_can_be_budgeted() {
+        let conn = budgets_db();
+
+        assert!(validate_budget(&conn, "food", 100.0).is_ok());
+        assert!(validate_budget(&conn, "food", -1.0).is_err());
+        assert!(validate_budget(&conn, "salary", 100.0).is_err());
+        assert!(matches!(
+            validate_budget(&conn, "missing", 100.0),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn status_adds_subcategory_spending_within_the_month() {
+        let conn = budgets_db();
+        insert_expense(&conn, "r1", "rent", 300.0, day("2024-03-05"));
+        insert_expense(&conn, "r2", "home", 50.0, day("2024-03-31"));
+        insert_expense(&conn, "r3", "rent", 999.0, day("2024-04-01"));
+        insert_expense(&conn, "r4", "food", 30.0, day("2024-03-10"));
+        let budgets = BudgetsConfig {
+            budgets: vec![
+                budget("2024-03", "home", 500.0),
+                budget("2024-03", "food", 0.0),
+                budget("2024-04", "food", 10.0),
+            ],
+            envelopes: None,
+        };
+
+        let statuses = get_budget_status(&conn, &budgets, "2024-3").unwrap();
+
+        assert_eq!(statuses.len(), 2);
+        let (food, home) = (&statuses[0], &statuses[1]);
+        assert_eq!(home.category_name, "Vivienda");
+        assert!(home.is_group);
+        assert!((home.spent - 350.0).abs() < 1e-9);
+        assert!((home.remaining - 150.0).abs() < 1e-9);
+        assert!((home.percent_used - 70.0).abs() < 1e-9);
+        assert!(!food.is_group);
+        assert!((food.percent_used - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn copying_keeps_existing_targets_unless_overwriting() {
+        let mut budgets = BudgetsConfig {
+            budgets: vec![
+                budget("2024-03", "home", 500.0),
+                budget("2024-03", "food", 200.0),
+                budget("2024-04", "food", 150.0),
+            ],
+            envelopes: None,
+        };
+
+        assert_eq!(
+            copy_month_budgets(&mut budgets, "2024-03", "2024-04", false),
+            1
+        );
+        let april = |budgets: &BudgetsConfig, category_id: &str| {
+            budgets
+                .budgets
+                .iter()
+                .filter(|b| b.month == "2024-04" && b.category_id == category_id)
+                .map(|b| b.amount)
+                .collect::<Vec<f64>>()
+        };
+        assert_eq!(april(&budgets, "home"), vec![500.0]);
+        assert_eq!(april(&budgets, "food"), vec![150.0]);
+
+        assert_eq!(
+            copy_month_budgets(&mut budgets, "2024-03", "2024-04", true),
+            2
+        );
+        assert_eq!(april(&budgets, "home"), vec![500.0]);
+        assert_eq!(april(&budgets, "food"), vec![200.0]);
+        assert_eq!(budgets.budgets.len(), 4);
+    }
+}
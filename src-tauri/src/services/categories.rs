@@ -1,16 +1,25 @@
+use crate::domain::config_models::{BudgetsConfig, CategoriesConfig, CategoryItem, RulesConfig};
 use crate::domain::error::AppError;
-use crate::dto::category_dto::CategoryDto;
-use rusqlite::Connection;
+use crate::dto::category_dto::{CategoryDto, CategoryTreeDto, SaveCategoryDto};
+use crate::services::envelopes::count_envelope_references;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
 
-pub fn get_categories(conn: &mut Connection) -> Result<Vec<CategoryDto>, AppError> {
+pub fn get_categories(
+    conn: &mut Connection,
+    include_inactive: bool,
+) -> Result<Vec<CategoryDto>, AppError> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, name, type, icon, color, is_active FROM categories WHERE is_active = 1",
+            "SELECT id, name, type, icon, color, is_active, created_by_user, parent_id
+             FROM categories
+             WHERE ?1 = 1 OR is_active = 1
+             ORDER BY name",
         )
         .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
     let rows = stmt
-        .query_map([], |row| {
+        .query_map([include_inactive as i32], |row| {
             Ok(CategoryDto {
                 id: row.get(0)?,
                 name: row.get(1)?,
@@ -18,6 +27,8 @@ pub fn get_categories(conn: &mut Connection) -> Result<Vec<CategoryDto>, AppErro
                 icon: row.get(3)?,
                 color: row.get(4)?,
                 is_active: row.get::<_, i32>(5)? == 1,
+                created_by_user: row.get::<_, i32>(6)? == 1,
+                parent_id: row.get(7)?,
             })
         })
         .map_err(|e| AppError::DatabaseError(e.to_string()))?;
@@ -29,3 +40,441 @@ pub fn get_categories(conn: &mut Connection) -> Result<Vec<CategoryDto>, AppErro
 
     Ok(categories)
 }
+
+/// Arma el árbol de categorías. Las subcategorías cuyo padre no está en la
+/// lista (por ejemplo, un padre deshabilitado) quedan en el primer nivel.
+pub fn build_category_tree(categories: Vec<CategoryDto>) -> Vec<CategoryTreeDto> {
+    let ids: Vec<String> = categories.iter().map(|c| c.id.clone()).collect();
+    let mut children_of: HashMap<Option<String>, Vec<CategoryDto>> = HashMap::new();
+
+    for category in categories {
+        let parent = category.parent_id.clone().filter(|p| ids.contains(p));
+        children_of.entry(parent).or_default().push(category);
+    }
+
+    fn attach(
+        parent: Option<String>,
+        children_of: &mut HashMap<Option<String>, Vec<CategoryDto>>,
+    ) -> Vec<CategoryTreeDto> {
+        children_of
+            .remove(&parent)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|category| {
+                let children = attach(Some(category.id.clone()), children_of);
+                CategoryTreeDto { category, children }
+            })
+            .collect()
+    }
+
+    attach(None, &mut children_of)
+}
+
+/// Valida nombre, tipo y padre; el nombre no puede repetirse entre hermanas.
+/// Un árbol no mezcla ingresos y gastos ni puede tener ciclos.
+pub fn validate_category(
+    categories: &CategoriesConfig,
+    category_id: Option<&str>,
+    category: &SaveCategoryDto,
+) -> Result<(), AppError> {
+    let name = category.name.trim();
+    if name.is_empty() {
+        return Err(AppError::ConfigError(
+            "El nombre de la categoría no puede estar vacío".into(),
+        ));
+    }
+
+    if category.r#type != "expense" && category.r#type != "income" {
+        return Err(AppError::ConfigError(format!(
+            "Tipo de categoría inválido: {} (use 'expense' o 'income')",
+            category.r#type
+        )));
+    }
+
+    let duplicated = categories.categories.iter().any(|c| {
+        Some(c.id.as_str()) != category_id
+            && c.r#type == category.r#type
+            && c.parent_id == category.parent_id
+            && c.name.trim().to_lowercase() == name.to_lowercase()
+    });
+    if duplicated {
+        return Err(AppError::ConfigError(format!(
+            "Ya existe una categoría llamada '{}'",
+            name
+        )));
+    }
+
+    // Las subcategorías existentes deben seguir siendo del mismo tipo
+    if let Some(id) = category_id {
+        let mixed_children = categories
+            .categories
+            .iter()
+            .any(|c| c.parent_id.as_deref() == Some(id) && c.r#type != category.r#type);
+        if mixed_children {
+            return Err(AppError::ConfigError(
+                "No se puede cambiar el tipo de una categoría que tiene subcategorías".into(),
+            ));
+        }
+    }
+
+    let Some(parent_id) = category.parent_id.as_deref() else {
+        return Ok(());
+    };
+
+    let find = |id: &str| categories.categories.iter().find(|c| c.id == id);
+    let parent = find(parent_id).ok_or_else(|| {
+        AppError::NotFound(format!("Categoría padre {} no encontrada", parent_id))
+    })?;
+
+    if parent.r#type != category.r#type {
+        return Err(AppError::ConfigError(format!(
+            "La categoría padre '{}' es de otro tipo; ingresos y gastos no se mezclan",
+            parent.name
+        )));
+    }
+
+    // Subimos por los ancestros del padre: si aparece la categoría hay un ciclo
+    let mut current = Some(parent);
+    let mut depth = 0;
+    while let Some(ancestor) = current {
+        if Some(ancestor.id.as_str()) == category_id || depth > categories.categories.len() {
+            return Err(AppError::ConfigError(
+                "Una categoría no puede quedar dentro de sí misma o de sus subcategorías".into(),
+            ));
+        }
+        current = ancestor.parent_id.as_deref().and_then(find);
+        depth += 1;
+    }
+
+    Ok(())
+}
+
+/// Subcategorías directas de la categoría
+pub fn count_subcategories(categories: &CategoriesConfig, category_id: &str) -> usize {
+    categories
+        .categories
+        .iter()
+        .filter(|c| c.parent_id.as_deref() == Some(category_id))
+        .count()
+}
+
+/// Registros y préstamos que apuntan a la categoría
+pub fn count_category_usage(conn: &Connection, category_id: &str) -> Result<i64, AppError> {
+    conn.query_row(
+        "SELECT (SELECT COUNT(*) FROM records WHERE category_id = ?1)
+              + (SELECT COUNT(*) FROM loans WHERE interest_category_id = ?1)",
+        [category_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| AppError::DatabaseError(e.to_string()))
+}
+
+/// Reglas (activas o no) que asignan la categoría
+pub fn count_category_rules(rules: &RulesConfig, category_id: &str) -> usize {
+    rules
+        .rules
+        .iter()
+        .filter(|r| r.actions.category_id.as_deref() == Some(category_id))
+        .count()
+}
+
+/// Presupuestos (de cualquier mes) y movimientos de sobres de la categoría
+pub fn count_category_budgets(budgets: &BudgetsConfig, category_id: &str) -> usize {
+    let envelope_references = budgets
+        .envelopes
+        .as_ref()
+        .map(|e| count_envelope_references(e, category_id))
+        .unwrap_or(0);
+
+    budgets
+        .budgets
+        .iter()
+        .filter(|b| b.category_id == category_id)
+        .count()
+        + envelope_references
+}
+
+pub fn upsert_category_in_database(
+    conn: &Connection,
+    category: &CategoryItem,
+) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO categories (id, name, type, icon, color, is_active, created_by_user, parent_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            category.id,
+            category.name,
+            category.r#type,
+            category.icon,
+            category.color,
+            category.is_active,
+            category.created_by_user,
+            category.parent_id,
+        ],
+    )
+    .map_err(|e| AppError::DatabaseError(format!("Error guardando categoría: {e}")))?;
+
+    Ok(())
+}
+
+pub fn delete_category_in_database(conn: &Connection, category_id: &str) -> Result<(), AppError> {
+    conn.execute("DELETE FROM categories WHERE id = ?1", [category_id])
+        .map_err(|e| AppError::DatabaseError(format!("Error eliminando categoría: {e}")))?;
+
+    Ok(())
+}
+
+// Pasa todo lo que apunta a la categoría fusionada a la que sobrevive y luego
+// elimina (remove = true) o deshabilita la fusionada
+pub fn merge_categories_in_database(
+    conn: &mut Connection,
+    source_id: &str,
+    target_id: &str,
+    target_parent_id: Option<&str>,
+    remove: bool,
+) -> Result<(), AppError> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    for sql in [
+        "UPDATE records SET category_id = ?2 WHERE category_id = ?1",
+        "UPDATE loans SET interest_category_id = ?2 WHERE interest_category_id = ?1",
+        "UPDATE categories SET parent_id = ?2 WHERE parent_id = ?1 AND id != ?2",
+    ] {
+        tx.execute(sql, (source_id, target_id))
+            .map_err(|e| AppError::DatabaseError(format!("Error fusionando categorías: {e}")))?;
+    }
+
+    tx.execute(
+        "UPDATE categories SET parent_id = ?1 WHERE id = ?2",
+        (target_parent_id, target_id),
+    )
+    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let sql = if remove {
+        "DELETE FROM categories WHERE id = ?1"
+    } else {
+        "UPDATE categories SET is_active = 0 WHERE id = ?1"
+    };
+    tx.execute(sql, [source_id])
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    tx.commit()
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::db_init::init_sqlite;
+
+    fn item(id: &str, kind: &str, parent_id: Option<&str>) -> CategoryItem {
+        CategoryItem {
+            id: id.into(),
+            name: id.into(),
+            r#type: kind.into(),
+            icon: String::new(),
+            color: String::new(),
+            created_by_user: true,
+            is_active: true,
+            created_at: 0,
+            parent_id: parent_id.map(String::from),
+        }
+    }
+
+    fn save(name: &str, kind: &str, parent_id: Option<&str>) -> SaveCategoryDto {
+        SaveCategoryDto {
+            name: name.into(),
+            r#type: kind.into(),
+            icon: String::new(),
+            color: String::new(),
+            parent_id: parent_id.map(String::from),
+        }
+    }
+
+    fn config(categories: Vec<CategoryItem>) -> CategoriesConfig {
+        CategoriesConfig { categories }
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    fn is_config_error(result: Result<(), AppError>) -> bool {
+        matches!(result, Err(AppError::ConfigError(_)))
+    }
+
+    #[test]
+    fn name_and_type_are_required() {
+        let categories = config(vec![]);
+
+        assert!(is_config_error(validate_category(
+            &categories,
+            None,
+            &save("  ", "expense", None)
+        )));
+        assert!(is_config_error(validate_category(
+            &categories,
+            None,
+            &save("Viajes", "transfer", None)
+        )));
+        assert!(validate_category(&categories, None, &save("Viajes", "expense", None)).is_ok());
+    }
+
+    #[test]
+    fn names_are_unique_per_type_ignoring_case() {
+        let categories = config(vec![item("food", "expense", None)]);
+
+        assert!(is_config_error(validate_category(
+            &categories,
+            None,
+            &save(" FOOD ", "expense", None)
+        )));
+        assert!(validate_category(&categories, None, &save("food", "income", None)).is_ok());
+        // Al editarse a sí misma no choca con su propio nombre
+        assert!(
+            validate_category(&categories, Some("food"), &save("Food", "expense", None)).is_ok()
+        );
+    }
+
+    #[test]
+    fn a_category_cannot_move_under_its_own_subcategories() {
+        let categories = config(vec![
+            item("home", "expense", None),
+            item("rent", "expense", Some("home")),
+            item("deposit", "expense", Some("rent")),
+        ]);
+
+        assert!(is_config_error(validate_category(
+            &categories,
+            Some("home"),
+            &save("home", "expense", Some("deposit")),
+        )));
+        assert!(is_config_error(validate_category(
+            &categories,
+            Some("home"),
+            &save("home", "expense", Some("home")),
+        )));
+        assert!(validate_category(
+            &categories,
+            Some("deposit"),
+            &save("deposit", "expense", Some("home")),
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn trees_do_not_mix_income_and_expenses() {
+        let categories = config(vec![
+            item("salary", "income", None),
+            item("home", "expense", None),
+            item("rent", "expense", Some("home")),
+        ]);
+
+        assert!(is_config_error(validate_category(
+            &categories,
+            None,
+            &save("Bono", "expense", Some("salary")),
+        )));
+        assert!(is_config_error(validate_category(
+            &categories,
+            Some("home"),
+            &save("home", "income", None),
+        )));
+        assert!(matches!(
+            validate_category(&categories, None, &save("Bono", "income", Some("missing"))),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn tree_nests_children_and_lifts_orphans() {
+        let dto = |id: &str, parent_id: Option<&str>| CategoryDto {
+            id: id.into(),
+            name: id.into(),
+            r#type: "expense".into(),
+            icon: String::new(),
+            color: String::new(),
+            is_active: true,
+            created_by_user: true,
+            parent_id: parent_id.map(String::from),
+        };
+
+        let tree = build_category_tree(vec![
+            dto("home", None),
+            dto("rent", Some("home")),
+            dto("deposit", Some("rent")),
+            dto("gym", Some("disabled")),
+        ]);
+
+        let roots: Vec<&str> = tree.iter().map(|t| t.category.id.as_str()).collect();
+        assert_eq!(roots, vec!["home", "gym"]);
+        assert_eq!(tree[0].children[0].category.id, "rent");
+        assert_eq!(tree[0].children[0].children[0].category.id, "deposit");
+        assert!(tree[1].children.is_empty());
+    }
+
+    #[test]
+    fn merging_moves_records_loans_and_subcategories_to_the_target() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        for category in [
+            item("food", "expense", None),
+            item("meals", "expense", Some("home")),
+            item("snacks", "expense", Some("meals")),
+            item("home", "expense", None),
+        ] {
+            upsert_category_in_database(&conn, &category).unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO records (id, type, timestamp, amount, account_id, category_id, file_path)
+             VALUES ('r1', 'expense', 0, 10, 'bank', 'meals', '');
+             INSERT INTO loans (account_id, principal, annual_interest_rate, term_months, start_date, payment_day, interest_category_id)
+             VALUES ('loan', 1000, 10, 12, 0, 1, 'meals');",
+        )
+        .unwrap();
+
+        merge_categories_in_database(&mut conn, "meals", "food", Some("home"), false).unwrap();
+
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM records WHERE category_id = 'food'"
+            ),
+            1
+        );
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM loans WHERE interest_category_id = 'food'"
+            ),
+            1
+        );
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM categories WHERE id = 'snacks' AND parent_id = 'food'"
+            ),
+            1
+        );
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM categories WHERE id = 'food' AND parent_id = 'home'"
+            ),
+            1
+        );
+        assert_eq!(
+            count(&conn, "SELECT is_active FROM categories WHERE id = 'meals'"),
+            0
+        );
+
+        merge_categories_in_database(&mut conn, "meals", "food", Some("home"), true).unwrap();
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM categories WHERE id = 'meals'"),
+            0
+        );
+    }
+}
@@ -0,0 +1,399 @@
+// Tarjetas de crédito: ciclos de facturación, pagos y cupo disponible
+
+use crate::domain::config_models::CreditCardSettings;
+use crate::domain::error::AppError;
+use crate::dto::credit_card_dto::{CreditCardStatementDto, CreditCardSummaryDto};
+use crate::helpers::datetime_helpers::{
+    clamped_date, end_of_day_timestamp, shift_month, start_of_day_timestamp,
+    timestamp_to_local_date,
+};
+use crate::services::accounts::{
+    get_account_balance_at, resolve_account_class, AFTER_OPENING_SQL, INSTALLMENT_INTEREST_SQL,
+};
+use chrono::{Datelike, NaiveDate};
+use rusqlite::{params, Connection};
+
+// Porcentaje de pago mínimo cuando la tarjeta no define uno
+const DEFAULT_MINIMUM_PAYMENT_PERCENT: f64 = 5.0;
+
+struct CreditCardAccount {
+    credit_limit: f64,
+    settings: CreditCardSettings,
+}
+
+fn load_credit_card(conn: &Connection, account_id: &str) -> Result<CreditCardAccount, AppError> {
+    let (account_type, credit_limit, closing_day, due_day, minimum_percent): (
+        String,
+        Option<f64>,
+        Option<u32>,
+        Option<u32>,
+        Option<f64>,
+    ) = conn
+        .query_row(
+            "SELECT type, credit_limit, statement_closing_day, payment_due_day, minimum_payment_percent
+             FROM accounts WHERE id = ?1",
+            [account_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .map_err(|_| AppError::NotFound(format!("Cuenta {} no encontrada", account_id)))?;
+
+    if account_type != "credit" {
+        return Err(AppError::ConfigError(format!(
+            "La cuenta {} no es una tarjeta de crédito",
+            account_id
+        )));
+    }
+
+    match (closing_day, due_day) {
+        (Some(statement_closing_day), Some(payment_due_day)) => Ok(CreditCardAccount {
+            credit_limit: credit_limit.unwrap_or(0.0),
+            settings: CreditCardSettings {
+                statement_closing_day,
+                payment_due_day,
+                minimum_payment_percent: minimum_percent,
+            },
+        }),
+        _ => Err(AppError::ConfigError(
+            "La tarjeta no tiene configurado el día de corte y de pago".into(),
+        )),
+    }
+}
+
+// Fecha de corte del mes indicado
+fn closing_date(settings: &CreditCardSettings, year: i32, month: u32) -> NaiveDate {
+    clamped_date(year, month, settings.statement_closing_day)
+}
+
+// Último corte en o antes de la fecha dada
+fn last_closing_on_or_before(settings: &CreditCardSettings, date: NaiveDate) -> NaiveDate {
+    let this_month = closing_date(settings, date.year(), date.month());
+    if this_month <= date {
+        this_month
+    } else {
+        let (y, m) = shift_month(date.year(), date.month(), -1);
+        closing_date(settings, y, m)
+    }
+}
+
+fn next_closing(settings: &CreditCardSettings, closing: NaiveDate) -> NaiveDate {
+    let (y, m) = shift_month(closing.year(), closing.month(), 1);
+    closing_date(settings, y, m)
+}
+
+fn previous_closing(settings: &CreditCardSettings, closing: NaiveDate) -> NaiveDate {
+    let (y, m) = shift_month(closing.year(), closing.month(), -1);
+    closing_date(settings, y, m)
+}
+
+// Primera fecha de pago posterior al corte
+fn due_date_after(settings: &CreditCardSettings, closing: NaiveDate) -> NaiveDate {
+    let same_month = clamped_date(closing.year(), closing.month(), settings.payment_due_day);
+    if same_month > closing {
+        same_month
+    } else {
+        let (y, m) = shift_month(closing.year(), closing.month(), 1);
+        clamped_date(y, m, settings.payment_due_day)
+    }
+}
+
+// Compras (gastos e intereses de cuotas) y abonos (ingresos y transferencias
+// entrantes) en (from, to]
+fn cycle_movements(
+    conn: &Connection,
+    account_id: &str,
+    from: i64,
+    to: i64,
+) -> Result<(f64, f64), AppError> {
+    let sql = format!(
+        "SELECT
+            COALESCE(SUM(CASE WHEN account_id = ?1 AND type = 'expense' THEN amount ELSE 0 END), 0)
+              + COALESCE((SELECT SUM(ii.interest) FROM ({interest}) ii
+                          WHERE ii.due_timestamp > ?2 AND ii.due_timestamp <= ?3), 0),
+            COALESCE(SUM(CASE
+                WHEN account_id = ?1 AND type = 'income' THEN amount
+                WHEN to_account_id = ?1 AND type = 'transfer' THEN amount
+                ELSE 0 END), 0)
+         FROM records r
+         WHERE (account_id = ?1 OR to_account_id = ?1) AND timestamp > ?2 AND timestamp <= ?3
+           AND {after_opening}",
+        interest = INSTALLMENT_INTEREST_SQL,
+        after_opening = AFTER_OPENING_SQL
+    );
+
+    conn.query_row(&sql, params![account_id, from, to], |row| {
+        Ok((row.get(0)?, row.get(1)?))
+    })
+    .map_err(|e| AppError::DatabaseError(e.to_string()))
+}
+
+fn build_statement(
+    conn: &Connection,
+    account_id: &str,
+    card: &CreditCardAccount,
+    closing: NaiveDate,
+) -> Result<CreditCardStatementDto, AppError> {
+    let settings = &card.settings;
+    let previous = previous_closing(settings, closing);
+    let due = due_date_after(settings, closing);
+
+    let (purchases, payments) = cycle_movements(
+        conn,
+        account_id,
+        end_of_day_timestamp(previous),
+        end_of_day_timestamp(closing),
+    )?;
+
+    // Deuda al corte: el saldo de una tarjeta es negativo cuando se debe
+    let closing_balance =
+        (-get_account_balance_at(conn, account_id, Some(end_of_day_timestamp(closing)))?).max(0.0);
+    let percent = settings
+        .minimum_payment_percent
+        .unwrap_or(DEFAULT_MINIMUM_PAYMENT_PERCENT);
+    let minimum_payment = closing_balance * percent / 100.0;
+
+    // Pagos hechos entre el corte y la fecha límite
+    let (_, paid_towards_statement) = cycle_movements(
+        conn,
+        account_id,
+        end_of_day_timestamp(closing),
+        end_of_day_timestamp(due),
+    )?;
+
+    Ok(CreditCardStatementDto {
+        period_start: start_of_day_timestamp(previous.succ_opt().unwrap_or(previous)),
+        period_end: end_of_day_timestamp(closing),
+        due_date: end_of_day_timestamp(due),
+        purchases,
+        payments,
+        closing_balance,
+        minimum_payment,
+        paid_towards_statement,
+        minimum_payment_met: paid_towards_statement + 0.005 >= minimum_payment,
+    })
+}
+
+/// Extractos de los últimos `cycles` cortes (el más reciente primero)
+pub fn get_credit_card_statements(
+    conn: &Connection,
+    account_id: &str,
+    cycles: u32,
+    now: i64,
+) -> Result<Vec<CreditCardStatementDto>, AppError> {
+    let card = load_credit_card(conn, account_id)?;
+    let mut closing = last_closing_on_or_before(&card.settings, timestamp_to_local_date(now));
+    let mut statements = Vec::new();
+
+    for _ in 0..cycles {
+        statements.push(build_statement(conn, account_id, &card, closing)?);
+        closing = previous_closing(&card.settings, closing);
+    }
+
+    Ok(statements)
+}
+
+/// Resumen de la tarjeta: deuda actual, cupo disponible, ciclo en curso y último extracto
+pub fn get_credit_card_summary(
+    conn: &Connection,
+    account_id: &str,
+    now: i64,
+) -> Result<CreditCardSummaryDto, AppError> {
+    let card = load_credit_card(conn, account_id)?;
+    let last_closing = last_closing_on_or_before(&card.settings, timestamp_to_local_date(now));
+
+    let owed_balance = (-get_account_balance_at(conn, account_id, None)?).max(0.0);
+
+    Ok(CreditCardSummaryDto {
+        account_id: account_id.to_string(),
+        credit_limit: card.credit_limit,
+        owed_balance,
+        available_credit: card.credit_limit - owed_balance,
+        current_cycle: build_statement(
+            conn,
+            account_id,
+            &card,
+            next_closing(&card.settings, last_closing),
+        )?,
+        last_statement: build_statement(conn, account_id, &card, last_closing)?,
+    })
+}
+
+/// La cuenta que paga la tarjeta debe existir, estar activa, ser un activo
+/// distinto de la tarjeta y tener su misma moneda
+pub fn validate_card_payment_source(
+    conn: &Connection,
+    from_account_id: &str,
+    card_account_id: &str,
+) -> Result<(), AppError> {
+    if from_account_id == card_account_id {
+        return Err(AppError::ConfigError(
+            "La tarjeta no puede pagarse desde sí misma".into(),
+        ));
+    }
+
+    let (account_type, currency, is_active, class_override): (
+        String,
+        String,
+        bool,
+        Option<String>,
+    ) = conn
+        .query_row(
+            "SELECT type, currency, is_active, account_class FROM accounts WHERE id = ?1",
+            [from_account_id],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get::<_, i32>(2)? == 1,
+                    row.get(3)?,
+                ))
+            },
+        )
+        .map_err(|_| AppError::NotFound(format!("Cuenta {} no encontrada", from_account_id)))?;
+
+    if !is_active {
+        return Err(AppError::ConfigError(
+            "La cuenta de origen del pago está archivada".into(),
+        ));
+    }
+    if resolve_account_class(&account_type, class_override.as_deref()) != "asset" {
+        return Err(AppError::ConfigError(
+            "La tarjeta solo puede pagarse desde una cuenta de activo".into(),
+        ));
+    }
+
+    let card_currency: String = conn
+        .query_row(
+            "SELECT currency FROM accounts WHERE id = ?1",
+            [card_account_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| AppError::NotFound(format!("Cuenta {} no encontrada", card_account_id)))?;
+    if !currency.eq_ignore_ascii_case(&card_currency) {
+        return Err(AppError::ConfigError(format!(
+            "La cuenta de origen está en {} y la tarjeta en {}",
+            currency, card_currency
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::db_init::init_sqlite;
+    use crate::helpers::datetime_helpers::date_string_to_timestamp;
+
+    fn day(date: &str) -> i64 {
+        date_string_to_timestamp(date).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    // Tarjeta con cupo de 1000, corte el 25, pago el 10 y mínimo del 10%
+    fn card_db(closing_day: u32) -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn.execute(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, credit_limit, is_active, created_at,
+                                   statement_closing_day, payment_due_day, minimum_payment_percent)
+             VALUES ('card', 'Tarjeta', 'credit', 'USD', 0, 1000, 1, 0, ?1, 10, 10)",
+            [closing_day],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn insert_account(
+        conn: &Connection,
+        id: &str,
+        account_type: &str,
+        currency: &str,
+        is_active: bool,
+    ) {
+        conn.execute(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, is_active, created_at)
+             VALUES (?1, ?1, ?2, ?3, 0, ?4, 0)",
+            params![id, account_type, currency, is_active as i32],
+        )
+        .unwrap();
+    }
+
+    fn insert_record(conn: &Connection, id: &str, kind: &str, amount: f64, timestamp: i64) {
+        conn.execute(
+            "INSERT INTO records (id, type, timestamp, amount, account_id, file_path)
+             VALUES (?1, ?2, ?3, ?4, 'card', '')",
+            params![id, kind, timestamp, amount],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn closing_day_is_clamped_to_short_months() {
+        let conn = card_db(31);
+
+        let statements = get_credit_card_statements(&conn, "card", 2, day("2024-03-15")).unwrap();
+
+        assert_eq!(
+            statements[0].period_end,
+            end_of_day_timestamp(date("2024-02-29"))
+        );
+        assert_eq!(
+            statements[0].period_start,
+            start_of_day_timestamp(date("2024-02-01"))
+        );
+        assert_eq!(
+            statements[0].due_date,
+            end_of_day_timestamp(date("2024-03-10"))
+        );
+        assert_eq!(
+            statements[1].period_end,
+            end_of_day_timestamp(date("2024-01-31"))
+        );
+    }
+
+    #[test]
+    fn statement_totals_the_cycle_and_the_payments_before_the_due_date() {
+        let conn = card_db(25);
+        insert_record(&conn, "before", "expense", 40.0, day("2024-02-20"));
+        insert_record(&conn, "r1", "expense", 200.0, day("2024-03-05"));
+        insert_record(&conn, "r2", "expense", 100.0, day("2024-03-20"));
+        insert_record(&conn, "refund", "income", 50.0, day("2024-03-22"));
+        insert_record(&conn, "payment", "income", 30.0, day("2024-04-05"));
+
+        let summary = get_credit_card_summary(&conn, "card", day("2024-04-01")).unwrap();
+        let statement = &summary.last_statement;
+
+        assert!((statement.purchases - 300.0).abs() < 1e-9);
+        assert!((statement.payments - 50.0).abs() < 1e-9);
+        assert!((statement.closing_balance - 290.0).abs() < 1e-9);
+        assert!((statement.minimum_payment - 29.0).abs() < 1e-9);
+        assert!((statement.paid_towards_statement - 30.0).abs() < 1e-9);
+        assert!(statement.minimum_payment_met);
+        assert!((summary.owed_balance - 260.0).abs() < 1e-9);
+        assert!((summary.available_credit - 740.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn payment_source_must_be_an_active_asset_in_the_card_currency() {
+        let conn = card_db(25);
+        insert_account(&conn, "bank", "bank", "usd", true);
+        insert_account(&conn, "euros", "bank", "EUR", true);
+        insert_account(&conn, "archived", "bank", "USD", false);
+        insert_account(&conn, "loan", "loan", "USD", true);
+
+        assert!(validate_card_payment_source(&conn, "bank", "card").is_ok());
+        for source in ["card", "euros", "archived", "loan"] {
+            assert!(matches!(
+                validate_card_payment_source(&conn, source, "card"),
+                Err(AppError::ConfigError(_))
+            ));
+        }
+        assert!(matches!(
+            validate_card_payment_source(&conn, "missing", "card"),
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
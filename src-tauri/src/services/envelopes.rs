@@ -0,0 +1,432 @@
+// Presupuesto por sobres (base cero): cada peso de ingreso se asigna a un sobre
+// y el saldo de cada sobre pasa al mes siguiente
+
+use crate::domain::config_models::{BudgetsConfig, EnvelopeBudgetConfig};
+use crate::domain::error::AppError;
+use crate::dto::envelope_dto::{EnvelopeBalanceDto, EnvelopeMonthDto};
+use crate::helpers::datetime_helpers::shift_month;
+use crate::services::budgets::{format_month, month_bounds, parse_month};
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+
+/// Configuración de sobres activa; error si el modo no está habilitado
+pub fn active_envelopes(budgets: &BudgetsConfig) -> Result<&EnvelopeBudgetConfig, AppError> {
+    budgets
+        .envelopes
+        .as_ref()
+        .filter(|e| e.enabled)
+        .ok_or_else(|| AppError::ConfigError("El presupuesto por sobres no está habilitado".into()))
+}
+
+pub fn active_envelopes_mut(
+    budgets: &mut BudgetsConfig,
+) -> Result<&mut EnvelopeBudgetConfig, AppError> {
+    budgets
+        .envelopes
+        .as_mut()
+        .filter(|e| e.enabled)
+        .ok_or_else(|| AppError::ConfigError("El presupuesto por sobres no está habilitado".into()))
+}
+
+/// Normaliza el mes y verifica que no sea anterior al inicio de los sobres
+pub fn validate_envelope_month(
+    envelopes: &EnvelopeBudgetConfig,
+    month: &str,
+) -> Result<String, AppError> {
+    let (year, month_number) = parse_month(month)?;
+    let month = format_month(year, month_number);
+
+    // 'YYYY-MM' se ordena igual como texto que como fecha
+    if month < envelopes.start_month {
+        return Err(AppError::ConfigError(format!(
+            "El mes {} es anterior al inicio del presupuesto por sobres ({})",
+            month, envelopes.start_month
+        )));
+    }
+
+    Ok(month)
+}
+
+fn month_income(conn: &Connection, from: i64, to: i64) -> Result<f64, AppError> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(amount), 0) FROM records
+         WHERE type = 'income' AND timestamp >= ?1 AND timestamp <= ?2",
+        params![from, to],
+        |row| row.get(0),
+    )
+    .map_err(|e| AppError::DatabaseError(e.to_string()))
+}
+
+// Gasto del mes por categoría (las cuotas cuentan en el mes en que vencen)
+fn month_spending(
+    conn: &Connection,
+    from: i64,
+    to: i64,
+) -> Result<HashMap<Option<String>, f64>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT category_id, SUM(amount) FROM expense_entries
+             WHERE timestamp >= ?1 AND timestamp <= ?2
+             GROUP BY category_id",
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map(params![from, to], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut spending = HashMap::new();
+    for row in rows {
+        let (category_id, amount) = row.map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        spending.insert(category_id, amount);
+    }
+
+    Ok(spending)
+}
+
+// Sobres posibles: todas las categorías de gastos (id, nombre, color, activa)
+fn expense_categories(conn: &Connection) -> Result<Vec<(String, String, String, bool)>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, color, is_active FROM categories
+             WHERE type = 'expense' ORDER BY name",
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get::<_, i32>(3)? == 1,
+            ))
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut categories = Vec::new();
+    for row in rows {
+        categories.push(row.map_err(|e| AppError::DatabaseError(e.to_string()))?);
+    }
+
+    Ok(categories)
+}
+
+/// Saldos de los sobres en el mes, recorriendo mes a mes desde el inicio
+pub fn get_envelope_month(
+    conn: &Connection,
+    envelopes: &EnvelopeBudgetConfig,
+    month: &str,
+) -> Result<EnvelopeMonthDto, AppError> {
+    let month = validate_envelope_month(envelopes, month)?;
+    let (mut year, mut month_number) = parse_month(&envelopes.start_month)?;
+    let categories = expense_categories(conn)?;
+
+    let mut balances: HashMap<String, f64> = HashMap::new();
+    let mut to_be_assigned = 0.0;
+
+    loop {
+        let current = format_month(year, month_number);
+        let (from, to) = month_bounds(year, month_number);
+        let income = month_income(conn, from, to)?;
+        let spending = month_spending(conn, from, to)?;
+
+        // 1. Movimiento de cada sobre en el mes
+        let mut rows = Vec::with_capacity(categories.len());
+        let mut total_assigned = 0.0;
+        for (category_id, name, color, is_active) in &categories {
+            let assigned: f64 = envelopes
+                .assignments
+                .iter()
+                .filter(|a| a.month == current && &a.category_id == category_id)
+                .map(|a| a.amount)
+                .sum();
+            let moved: f64 = envelopes
+                .moves
+                .iter()
+                .filter(|m| m.month == current)
+                .map(|m| {
+                    if &m.to_category_id == category_id {
+                        m.amount
+                    } else if &m.from_category_id == category_id {
+                        -m.amount
+                    } else {
+                        0.0
+                    }
+                })
+                .sum();
+            let spent = spending
+                .get(&Some(category_id.clone()))
+                .copied()
+                .unwrap_or(0.0);
+            let carried_over = balances.get(category_id).copied().unwrap_or(0.0);
+            let available = carried_over + assigned + moved - spent;
+
+            total_assigned += assigned;
+            balances.insert(category_id.clone(), available);
+
+            // Las categorías deshabilitadas solo aparecen si tienen saldo o movimiento
+            let has_activity = [carried_over, assigned, moved, spent]
+                .iter()
+                .any(|v| v.abs() > f64::EPSILON);
+            if *is_active || has_activity {
+                rows.push(EnvelopeBalanceDto {
+                    category_id: category_id.clone(),
+                    category_name: name.clone(),
+                    color: color.clone(),
+                    carried_over,
+                    assigned,
+                    moved,
+                    spent,
+                    available,
+                });
+            }
+        }
+
+        // 2. Lo gastado fuera de los sobres sale de lo disponible para asignar
+        let unassigned_spent: f64 = spending
+            .iter()
+            .filter(|(category_id, _)| {
+                category_id
+                    .as_ref()
+                    .is_none_or(|id| !categories.iter().any(|c| &c.0 == id))
+            })
+            .map(|(_, amount)| amount)
+            .sum();
+        to_be_assigned += income - total_assigned - unassigned_spent;
+
+        if current == month {
+            return Ok(EnvelopeMonthDto {
+                month,
+                income,
+                assigned: total_assigned,
+                unassigned_spent,
+                to_be_assigned,
+                envelopes: rows,
+            });
+        }
+
+        (year, month_number) = shift_month(year, month_number, 1);
+    }
+}
+
+/// Asignaciones y traslados que usan la categoría
+pub fn count_envelope_references(envelopes: &EnvelopeBudgetConfig, category_id: &str) -> usize {
+    envelopes
+        .assignments
+        .iter()
+        .filter(|a| a.category_id == category_id)
+        .count()
+        + envelopes
+            .moves
+            .iter()
+            .filter(|m| m.from_category_id == category_id || m.to_category_id == category_id)
+            .count()
+}
+
+/// Pasa asignaciones y traslados de una categoría a otra. Las asignaciones del
+/// mismo mes se suman y los traslados que quedarían entre el mismo sobre se eliminan.
+pub fn reassign_envelope_category(
+    envelopes: &mut EnvelopeBudgetConfig,
+    source_id: &str,
+    target_id: &str,
+    updated_at: i64,
+) -> usize {
+    let mut updated = 0;
+
+    let source_assignments: Vec<_> = envelopes
+        .assignments
+        .iter()
+        .filter(|a| a.category_id == source_id)
+        .cloned()
+        .collect();
+    for source in source_assignments {
+        match envelopes
+            .assignments
+            .iter_mut()
+            .find(|a| a.category_id == target_id && a.month == source.month)
+        {
+            Some(target) => {
+                target.amount += source.amount;
+                target.updated_at = updated_at;
+                envelopes.assignments.retain(|a| a.id != source.id);
+            }
+            None => {
+                if let Some(assignment) =
+                    envelopes.assignments.iter_mut().find(|a| a.id == source.id)
+                {
+                    assignment.category_id = target_id.to_string();
+                    assignment.updated_at = updated_at;
+                }
+            }
+        }
+        updated += 1;
+    }
+
+    for envelope_move in envelopes.moves.iter_mut() {
+        if envelope_move.from_category_id == source_id {
+            envelope_move.from_category_id = target_id.to_string();
+            updated += 1;
+        }
+        if envelope_move.to_category_id == source_id {
+            envelope_move.to_category_id = target_id.to_string();
+            updated += 1;
+        }
+    }
+    envelopes
+        .moves
+        .retain(|m| m.from_category_id != m.to_category_id);
+
+    updated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::config_models::{EnvelopeAssignmentItem, EnvelopeMoveItem};
+    use crate::fs::db_init::init_sqlite;
+    use crate::helpers::datetime_helpers::date_string_to_timestamp;
+
+    fn day(date: &str) -> i64 {
+        date_string_to_timestamp(date).unwrap()
+    }
+
+    fn assignment(month: &str, category_id: &str, amount: f64) -> EnvelopeAssignmentItem {
+        EnvelopeAssignmentItem {
+            id: format!("{}-{}", month, category_id),
+            month: month.into(),
+            category_id: category_id.into(),
+            amount,
+            updated_at: 0,
+        }
+    }
+
+    fn envelope_move(month: &str, from: &str, to: &str, amount: f64) -> EnvelopeMoveItem {
+        EnvelopeMoveItem {
+            id: format!("{}-{}-{}", month, from, to),
+            month: month.into(),
+            from_category_id: from.into(),
+            to_category_id: to.into(),
+            amount,
+            notes: None,
+            created_at: 0,
+        }
+    }
+
+    // Enero: ingreso de 1000, 800 asignados y Comida sobregirada en 50.
+    // Febrero: se asignan 100 a Comida y se le trasladan 50 desde Vivienda.
+    fn envelopes() -> EnvelopeBudgetConfig {
+        EnvelopeBudgetConfig {
+            enabled: true,
+            start_month: "2024-01".into(),
+            assignments: vec![
+                assignment("2024-01", "food", 300.0),
+                assignment("2024-01", "home", 500.0),
+                assignment("2024-02", "food", 100.0),
+            ],
+            moves: vec![envelope_move("2024-02", "home", "food", 50.0)],
+        }
+    }
+
+    fn envelopes_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO categories (id, name, type, icon, color, is_active) VALUES
+                ('food', 'Comida', 'expense', '', '', 1),
+                ('home', 'Vivienda', 'expense', '', '', 1),
+                ('old', 'Antigua', 'expense', '', '', 0);",
+        )
+        .unwrap();
+        for (id, kind, amount, category_id, date) in [
+            ("salary", "income", 1000.0, None, "2024-01-01"),
+            ("market", "expense", 350.0, Some("food"), "2024-01-10"),
+            ("misc", "expense", 20.0, None, "2024-01-15"),
+            ("repair", "expense", 100.0, Some("home"), "2024-02-05"),
+        ] {
+            conn.execute(
+                "INSERT INTO records (id, type, timestamp, amount, account_id, category_id, file_path)
+                 VALUES (?1, ?2, ?3, ?4, 'bank', ?5, '')",
+                params![id, kind, day(date), amount, category_id],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn available(month: &EnvelopeMonthDto, category_id: &str) -> f64 {
+        month
+            .envelopes
+            .iter()
+            .find(|e| e.category_id == category_id)
+            .map(|e| e.available)
+            .unwrap()
+    }
+
+    #[test]
+    fn balances_and_overspending_roll_over_to_the_next_month() {
+        let conn = envelopes_db();
+
+        let january = get_envelope_month(&conn, &envelopes(), "2024-01").unwrap();
+        assert!((available(&january, "food") + 50.0).abs() < 1e-9);
+        assert!((january.unassigned_spent - 20.0).abs() < 1e-9);
+        assert!((january.to_be_assigned - 180.0).abs() < 1e-9);
+
+        let february = get_envelope_month(&conn, &envelopes(), "2024-02").unwrap();
+        let food = february
+            .envelopes
+            .iter()
+            .find(|e| e.category_id == "food")
+            .unwrap();
+        assert!((food.carried_over + 50.0).abs() < 1e-9);
+        assert!((food.moved - 50.0).abs() < 1e-9);
+        assert!((food.available - 100.0).abs() < 1e-9);
+        assert!((available(&february, "home") - 350.0).abs() < 1e-9);
+        assert!((february.to_be_assigned - 80.0).abs() < 1e-9);
+        // Las categorías deshabilitadas sin movimientos no se listan
+        assert_eq!(february.envelopes.len(), 2);
+    }
+
+    #[test]
+    fn months_before_the_start_are_rejected() {
+        assert!(validate_envelope_month(&envelopes(), "2023-12").is_err());
+        assert_eq!(
+            validate_envelope_month(&envelopes(), "2024-2").unwrap(),
+            "2024-02"
+        );
+    }
+
+    #[test]
+    fn disabled_envelopes_are_not_active() {
+        let mut budgets = BudgetsConfig {
+            budgets: vec![],
+            envelopes: Some(EnvelopeBudgetConfig {
+                enabled: false,
+                ..envelopes()
+            }),
+        };
+
+        assert!(active_envelopes(&budgets).is_err());
+        assert!(active_envelopes_mut(&mut budgets).is_err());
+        budgets.envelopes.as_mut().unwrap().enabled = true;
+        assert!(active_envelopes_mut(&mut budgets).is_ok());
+    }
+
+    #[test]
+    fn reassigning_merges_assignments_and_drops_moves_within_one_envelope() {
+        let mut config = envelopes();
+
+        let updated = reassign_envelope_category(&mut config, "home", "food", 1);
+
+        assert_eq!(updated, 2);
+        assert_eq!(count_envelope_references(&config, "home"), 0);
+        assert!(config.moves.is_empty());
+        let january: Vec<f64> = config
+            .assignments
+            .iter()
+            .filter(|a| a.month == "2024-01")
+            .map(|a| a.amount)
+            .collect();
+        assert_eq!(january, vec![800.0]);
+    }
+}
@@ -0,0 +1,344 @@
+// Metas de ahorro: aportes, progreso y aporte mensual necesario
+
+use crate::domain::config_models::{GoalItem, GoalsConfig};
+use crate::domain::error::AppError;
+use crate::dto::goal_dto::GoalProgressDto;
+use crate::helpers::datetime_helpers::{timestamp_now, timestamp_to_local_date};
+use chrono::Datelike;
+use rusqlite::{params, Connection};
+
+/// Reemplaza el índice de metas con el contenido de goals.json
+pub fn index_goals(conn: &Connection, goals: &GoalsConfig) -> Result<(), AppError> {
+    conn.execute("DELETE FROM goals", [])
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    for goal in &goals.goals {
+        conn.execute(
+            "INSERT INTO goals (id, name, target_amount, target_date, start_date, account_id, tag, color, is_active) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                goal.id,
+                goal.name,
+                goal.target_amount,
+                goal.target_date,
+                goal.start_date,
+                goal.account_id,
+                goal.tag,
+                goal.color,
+                goal.is_active as i32,
+            ],
+        )
+        .map_err(|e| AppError::DatabaseError(format!("Error indexando metas: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// Nombre, monto y fechas válidos; la meta se sigue por una cuenta existente o una etiqueta
+pub fn validate_goal(conn: &Connection, goal: &GoalItem) -> Result<(), AppError> {
+    if goal.name.is_empty() {
+        return Err(AppError::ConfigError("La meta necesita un nombre".into()));
+    }
+    if !goal.target_amount.is_finite() || goal.target_amount <= 0.0 {
+        return Err(AppError::ConfigError(
+            "El monto objetivo debe ser mayor que cero".into(),
+        ));
+    }
+    if goal.target_date <= goal.start_date {
+        return Err(AppError::ConfigError(
+            "La fecha objetivo debe ser posterior a la fecha de inicio".into(),
+        ));
+    }
+    if goal.account_id.is_none() && goal.tag.is_none() {
+        return Err(AppError::ConfigError(
+            "La meta necesita una cuenta o una etiqueta para seguir los aportes".into(),
+        ));
+    }
+
+    if let Some(account_id) = &goal.account_id {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM accounts WHERE id = ?1)",
+                [account_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !exists {
+            return Err(AppError::NotFound(format!(
+                "Cuenta {} no encontrada",
+                account_id
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// Aportes netos desde start_date:
+// - con cuenta: transferencias hacia la cuenta menos las que salen de ella
+//   (si además hay etiqueta, solo las transferencias etiquetadas)
+// - solo con etiqueta: ingresos y transferencias etiquetados menos gastos etiquetados
+fn goal_contributions(
+    conn: &Connection,
+    account_id: Option<&str>,
+    tag: Option<&str>,
+    start_date: i64,
+) -> Result<f64, AppError> {
+    let query = match account_id {
+        Some(_) => {
+            "SELECT COALESCE(SUM(CASE
+                WHEN r.to_account_id = ?1 THEN r.amount
+                WHEN r.account_id = ?1 THEN -r.amount
+                ELSE 0 END), 0)
+             FROM records r
+             WHERE r.type = 'transfer'
+               AND (r.to_account_id = ?1 OR r.account_id = ?1)
+               AND r.timestamp >= ?3
+               AND (?2 IS NULL OR EXISTS (
+                    SELECT 1 FROM record_tags t WHERE t.record_id = r.id AND t.tag_id = ?2))"
+        }
+        None => {
+            "SELECT COALESCE(SUM(CASE
+                WHEN r.type = 'expense' THEN -r.amount
+                ELSE r.amount END), 0)
+             FROM records r
+             JOIN record_tags t ON t.record_id = r.id AND t.tag_id = ?2
+             WHERE r.timestamp >= ?3"
+        }
+    };
+
+    conn.query_row(query, params![account_id, tag, start_date], |row| {
+        row.get(0)
+    })
+    .map_err(|e| AppError::DatabaseError(e.to_string()))
+}
+
+// Meses calendario desde hoy hasta la fecha objetivo, contando el mes actual
+fn months_until(target_date: i64) -> u32 {
+    let today = timestamp_to_local_date(timestamp_now());
+    let target = timestamp_to_local_date(target_date);
+
+    if target < today {
+        return 0;
+    }
+
+    let months =
+        (target.year() - today.year()) * 12 + target.month() as i32 - today.month() as i32 + 1;
+    months.max(1) as u32
+}
+
+/// Progreso de las metas indexadas, ordenadas por fecha objetivo
+pub fn get_goals_progress(
+    conn: &Connection,
+    include_inactive: bool,
+) -> Result<Vec<GoalProgressDto>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, target_amount, target_date, start_date, account_id, tag, color, is_active
+             FROM goals
+             WHERE (?1 = 1 OR is_active = 1)
+             ORDER BY target_date, name",
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map([include_inactive as i32], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, f64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, Option<String>>(7)?,
+                row.get::<_, i32>(8)? == 1,
+            ))
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut goals = Vec::new();
+    for row in rows {
+        let (id, name, target_amount, target_date, start_date, account_id, tag, color, is_active) =
+            row.map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let contributed =
+            goal_contributions(conn, account_id.as_deref(), tag.as_deref(), start_date)?;
+        let remaining = (target_amount - contributed).max(0.0);
+        let progress_percent = if target_amount > 0.0 {
+            (contributed * 100.0 / target_amount).clamp(0.0, 100.0)
+        } else {
+            0.0
+        };
+        let months_left = months_until(target_date);
+        // Con la fecha vencida, lo que falta se necesita de una vez
+        let required_monthly = remaining / months_left.max(1) as f64;
+
+        goals.push(GoalProgressDto {
+            id,
+            name,
+            target_amount,
+            target_date,
+            start_date,
+            account_id,
+            tag,
+            color,
+            is_active,
+            contributed,
+            remaining,
+            progress_percent,
+            months_left,
+            required_monthly,
+            is_completed: remaining <= 0.0,
+        });
+    }
+
+    Ok(goals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::db_init::init_sqlite;
+    use crate::helpers::datetime_helpers::{
+        clamped_date, date_string_to_timestamp, end_of_day_timestamp, shift_month,
+    };
+
+    fn day(date: &str) -> i64 {
+        date_string_to_timestamp(date).unwrap()
+    }
+
+    fn goal(account_id: Option<&str>, tag: Option<&str>) -> GoalItem {
+        GoalItem {
+            id: "g1".into(),
+            name: "Viaje".into(),
+            target_amount: 300.0,
+            target_date: day("2099-12-31"),
+            start_date: day("2024-01-01"),
+            account_id: account_id.map(String::from),
+            tag: tag.map(String::from),
+            color: None,
+            notes: None,
+            is_active: true,
+            created_at: 0,
+        }
+    }
+
+    // Transferencias hacia y desde 'savings' y movimientos con la etiqueta 'trip'
+    fn goals_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn.execute(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, is_active, created_at)
+             VALUES ('savings', 'Ahorros', 'bank', 'USD', 0, 1, 0)",
+            [],
+        )
+        .unwrap();
+        for (id, kind, amount, account_id, to_account_id, date, tagged) in [
+            (
+                "before",
+                "transfer",
+                999.0,
+                "bank",
+                Some("savings"),
+                "2023-12-01",
+                false,
+            ),
+            (
+                "in",
+                "transfer",
+                200.0,
+                "bank",
+                Some("savings"),
+                "2024-01-10",
+                true,
+            ),
+            (
+                "out",
+                "transfer",
+                50.0,
+                "savings",
+                Some("bank"),
+                "2024-01-20",
+                false,
+            ),
+            ("gift", "income", 100.0, "bank", None, "2024-02-01", true),
+            ("ticket", "expense", 30.0, "bank", None, "2024-02-05", true),
+        ] {
+            conn.execute(
+                "INSERT INTO records (id, type, timestamp, amount, account_id, to_account_id, file_path)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, '')",
+                params![id, kind, day(date), amount, account_id, to_account_id],
+            )
+            .unwrap();
+            if tagged {
+                conn.execute(
+                    "INSERT INTO record_tags (record_id, tag_id) VALUES (?1, 'trip')",
+                    [id],
+                )
+                .unwrap();
+            }
+        }
+        conn
+    }
+
+    fn contributions(conn: &Connection, account_id: Option<&str>, tag: Option<&str>) -> f64 {
+        goal_contributions(conn, account_id, tag, day("2024-01-01")).unwrap()
+    }
+
+    #[test]
+    fn contributions_follow_the_account_the_tag_or_both() {
+        let conn = goals_db();
+
+        assert!((contributions(&conn, Some("savings"), None) - 150.0).abs() < 1e-9);
+        assert!((contributions(&conn, Some("savings"), Some("trip")) - 200.0).abs() < 1e-9);
+        assert!((contributions(&conn, None, Some("trip")) - 270.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn months_until_counts_the_current_month() {
+        let today = timestamp_to_local_date(timestamp_now());
+        let (year, month) = shift_month(today.year(), today.month(), 3);
+        let in_three_months = end_of_day_timestamp(clamped_date(year, month, today.day()));
+
+        assert_eq!(months_until(end_of_day_timestamp(today)), 1);
+        assert_eq!(months_until(in_three_months), 4);
+        assert_eq!(months_until(timestamp_now() - 40 * 86_400), 0);
+    }
+
+    #[test]
+    fn progress_reports_what_is_left_and_the_monthly_amount() {
+        let conn = goals_db();
+        index_goals(
+            &conn,
+            &GoalsConfig {
+                goals: vec![goal(Some("savings"), None)],
+            },
+        )
+        .unwrap();
+
+        let progress = get_goals_progress(&conn, false).unwrap();
+
+        assert!((progress[0].contributed - 150.0).abs() < 1e-9);
+        assert!((progress[0].remaining - 150.0).abs() < 1e-9);
+        assert!((progress[0].progress_percent - 50.0).abs() < 1e-9);
+        let expected_monthly = 150.0 / progress[0].months_left as f64;
+        assert!((progress[0].required_monthly - expected_monthly).abs() < 1e-9);
+        assert!(!progress[0].is_completed);
+    }
+
+    #[test]
+    fn goals_need_an_existing_account_or_a_tag() {
+        let conn = goals_db();
+
+        assert!(validate_goal(&conn, &goal(Some("savings"), None)).is_ok());
+        assert!(validate_goal(&conn, &goal(None, Some("trip"))).is_ok());
+        assert!(matches!(
+            validate_goal(&conn, &goal(None, None)),
+            Err(AppError::ConfigError(_))
+        ));
+        assert!(matches!(
+            validate_goal(&conn, &goal(Some("missing"), None)),
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
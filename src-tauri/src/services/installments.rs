@@ -0,0 +1,310 @@
+// Compras a cuotas: plan de pagos y cuotas pendientes por tarjeta
+
+use crate::domain::config_models::{InstallmentPlan, RecordItem};
+use crate::domain::error::AppError;
+use crate::dto::installment_dto::{InstallmentDto, InstallmentPurchaseDto};
+use crate::helpers::datetime_helpers::{
+    clamped_date, shift_month, start_of_day_timestamp, timestamp_to_local_date,
+};
+use chrono::Datelike;
+use rusqlite::{params, Connection};
+
+/// Al menos una cuota y una tasa mensual finita no negativa
+pub fn validate_installment_plan(count: u32, monthly_interest_rate: f64) -> Result<(), AppError> {
+    if count < 1 {
+        return Err(AppError::ConfigError(
+            "El número de cuotas debe ser al menos 1".into(),
+        ));
+    }
+    if !monthly_interest_rate.is_finite() || monthly_interest_rate < 0.0 {
+        return Err(AppError::ConfigError(
+            "La tasa de interés mensual no puede ser negativa".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Plan de cuotas con cuota fija (sistema francés). La primera cuota cae en el
+/// mes de la compra y las siguientes el mismo día de los meses posteriores.
+pub fn build_schedule(
+    amount: f64,
+    plan: &InstallmentPlan,
+    purchase_timestamp: i64,
+) -> Vec<InstallmentDto> {
+    let count = plan.count.max(1);
+    let rate = plan.monthly_interest_rate / 100.0;
+    let payment = if rate > 0.0 {
+        amount * rate / (1.0 - (1.0 + rate).powi(-(count as i32)))
+    } else {
+        amount / count as f64
+    };
+
+    let purchase_date = timestamp_to_local_date(purchase_timestamp);
+    let mut remaining = amount;
+    let mut schedule = Vec::new();
+
+    for number in 1..=count {
+        let interest = remaining * rate;
+        // La última cuota absorbe el redondeo
+        let principal = if number == count {
+            remaining
+        } else {
+            payment - interest
+        };
+        remaining -= principal;
+
+        let (y, m) = shift_month(
+            purchase_date.year(),
+            purchase_date.month(),
+            number as i32 - 1,
+        );
+        schedule.push(InstallmentDto {
+            number,
+            due_timestamp: start_of_day_timestamp(clamped_date(y, m, purchase_date.day())),
+            principal,
+            interest,
+            amount: principal + interest,
+            remaining_principal: remaining.max(0.0),
+        });
+    }
+
+    schedule
+}
+
+// Reemplaza las cuotas indexadas del registro según su plan
+pub fn index_installments(conn: &Connection, record: &RecordItem) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM record_installments WHERE record_id = ?1",
+        [&record.id],
+    )
+    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let Some(plan) = &record.installments else {
+        return Ok(());
+    };
+
+    for installment in build_schedule(record.amount, plan, record.timestamp) {
+        conn.execute(
+            "INSERT INTO record_installments (record_id, number, due_timestamp, principal, interest, amount) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                record.id,
+                installment.number,
+                installment.due_timestamp,
+                installment.principal,
+                installment.interest,
+                installment.amount,
+            ],
+        )
+        .map_err(|e| AppError::DatabaseError(format!("Error guardando cuotas: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// Compras a cuotas de una tarjeta con cuotas aún por facturar
+pub fn get_outstanding_installments(
+    conn: &Connection,
+    account_id: &str,
+    now: i64,
+) -> Result<Vec<InstallmentPurchaseDto>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.id, r.description, r.timestamp, r.amount, COUNT(i.number)
+             FROM records r
+             JOIN record_installments i ON i.record_id = r.id
+             WHERE r.account_id = ?1
+             GROUP BY r.id
+             HAVING MAX(i.due_timestamp) > ?2
+             ORDER BY r.timestamp DESC",
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let purchases: Vec<(String, Option<String>, i64, f64, u32)> = stmt
+        .query_map(params![account_id, now], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?
+        .collect::<Result<_, _>>()
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut installment_stmt = conn
+        .prepare(
+            "SELECT number, due_timestamp, principal, interest, amount
+             FROM record_installments WHERE record_id = ?1 ORDER BY number",
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut result = Vec::new();
+    for (record_id, description, purchase_timestamp, total_amount, installments_total) in purchases
+    {
+        let rows: Vec<(u32, i64, f64, f64, f64)> = installment_stmt
+            .query_map([&record_id], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            })
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .collect::<Result<_, _>>()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let mut remaining = total_amount;
+        let mut pending = Vec::new();
+        let mut billed = 0;
+        for (number, due_timestamp, principal, interest, amount) in rows {
+            remaining -= principal;
+            if due_timestamp <= now {
+                billed += 1;
+                continue;
+            }
+            pending.push(InstallmentDto {
+                number,
+                due_timestamp,
+                principal,
+                interest,
+                amount,
+                remaining_principal: remaining.max(0.0),
+            });
+        }
+
+        result.push(InstallmentPurchaseDto {
+            record_id,
+            description,
+            purchase_timestamp,
+            total_amount,
+            installments_total,
+            installments_billed: billed,
+            outstanding_principal: pending.iter().map(|i| i.principal).sum(),
+            pending_installments: pending,
+        });
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::db_init::init_sqlite;
+    use crate::helpers::datetime_helpers::date_string_to_timestamp;
+    use crate::services::accounts::get_account_balance_at;
+    use chrono::NaiveDate;
+
+    fn plan(count: u32, monthly_interest_rate: f64) -> InstallmentPlan {
+        InstallmentPlan {
+            count,
+            monthly_interest_rate,
+        }
+    }
+
+    #[test]
+    fn schedule_without_interest_splits_the_amount_evenly() {
+        let purchase = date_string_to_timestamp("2024-03-10").unwrap();
+        let schedule = build_schedule(900.0, &plan(3, 0.0), purchase);
+
+        assert_eq!(schedule.len(), 3);
+        for installment in &schedule {
+            assert!((installment.principal - 300.0).abs() < 1e-9);
+            assert_eq!(installment.interest, 0.0);
+        }
+        assert!(schedule[2].remaining_principal.abs() < 1e-9);
+    }
+
+    #[test]
+    fn schedule_with_interest_pays_off_the_principal() {
+        let purchase = date_string_to_timestamp("2024-03-10").unwrap();
+        let schedule = build_schedule(1000.0, &plan(12, 2.0), purchase);
+
+        let principal: f64 = schedule.iter().map(|i| i.principal).sum();
+        assert!((principal - 1000.0).abs() < 1e-6);
+        // La primera cuota paga el interés sobre todo el capital
+        assert!((schedule[0].interest - 20.0).abs() < 1e-9);
+        // Cuota fija salvo el redondeo de la última
+        assert!((schedule[0].amount - schedule[5].amount).abs() < 1e-6);
+        assert!(schedule.last().unwrap().remaining_principal.abs() < 1e-9);
+    }
+
+    #[test]
+    fn due_dates_are_clamped_to_the_end_of_short_months() {
+        let purchase = date_string_to_timestamp("2024-01-31").unwrap();
+        let schedule = build_schedule(300.0, &plan(3, 0.0), purchase);
+
+        let dates: Vec<NaiveDate> = schedule
+            .iter()
+            .map(|i| timestamp_to_local_date(i.due_timestamp))
+            .collect();
+        assert_eq!(
+            dates,
+            vec![
+                NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_validation_rejects_zero_installments_and_bad_rates() {
+        assert!(validate_installment_plan(0, 1.0).is_err());
+        assert!(validate_installment_plan(3, -1.0).is_err());
+        assert!(validate_installment_plan(3, f64::NAN).is_err());
+        assert!(validate_installment_plan(3, 0.0).is_ok());
+    }
+
+    #[test]
+    fn installment_interest_is_charged_to_the_card_on_each_due_date() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn.execute(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, is_active, created_at)
+             VALUES ('card', 'Tarjeta', 'credit', 'USD', 0, 1, 0)",
+            [],
+        )
+        .unwrap();
+
+        let purchase = RecordItem {
+            id: "purchase".into(),
+            r#type: "expense".into(),
+            amount: 1000.0,
+            timestamp: date_string_to_timestamp("2024-03-10").unwrap(),
+            account_id: "card".into(),
+            installments: Some(plan(3, 2.0)),
+            ..Default::default()
+        };
+        conn.execute(
+            "INSERT INTO records (id, type, timestamp, amount, account_id, file_path)
+             VALUES (?1, 'expense', ?2, ?3, 'card', '')",
+            params![purchase.id, purchase.timestamp, purchase.amount],
+        )
+        .unwrap();
+        index_installments(&conn, &purchase).unwrap();
+
+        let schedule = build_schedule(purchase.amount, &plan(3, 2.0), purchase.timestamp);
+        let second_due = schedule[1].due_timestamp;
+
+        // Capital completo más los intereses de las dos primeras cuotas
+        let balance = get_account_balance_at(&conn, "card", Some(second_due)).unwrap();
+        let expected = -(1000.0 + schedule[0].interest + schedule[1].interest);
+        assert!((balance - expected).abs() < 1e-6);
+
+        // Los reportes cuentan la cuota completa en su mes
+        let reported: f64 = conn
+            .query_row(
+                "SELECT SUM(amount) FROM expense_entries WHERE timestamp <= ?1",
+                [second_due],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!((reported - (schedule[0].amount + schedule[1].amount)).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,666 @@
+// Inversiones: posiciones por título, lotes, precios y ganancias
+
+use crate::domain::config_models::{
+    InvestmentTransactionItem, InvestmentsConfig, SecurityPriceItem,
+};
+use crate::domain::error::AppError;
+use crate::dto::import_report_dto::SkippedEntryDto;
+use crate::dto::investment_dto::{
+    HoldingDto, InvestmentCurrencyWarningDto, InvestmentGainsReportDto, RealizedGainDto,
+};
+use crate::helpers::datetime_helpers::date_string_to_timestamp;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CostBasisMethod {
+    Fifo,
+    Average,
+}
+
+impl CostBasisMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CostBasisMethod::Fifo => "fifo",
+            CostBasisMethod::Average => "average",
+        }
+    }
+}
+
+// Efecto de la operación sobre el efectivo de la cuenta. Dividendos e
+// intereses entran como registros de ingreso, así que aquí no suman.
+pub fn cash_amount(transaction: &InvestmentTransactionItem) -> f64 {
+    match transaction.kind.as_str() {
+        "buy" => -(transaction.quantity * transaction.price + transaction.fees),
+        "sell" => transaction.quantity * transaction.price - transaction.fees,
+        _ => 0.0,
+    }
+}
+
+/// Reemplaza el índice de inversiones con el contenido de investments.json
+pub fn index_investments(
+    conn: &Connection,
+    investments: &InvestmentsConfig,
+) -> Result<(), AppError> {
+    for table in ["securities", "investment_transactions", "security_prices"] {
+        conn.execute(&format!("DELETE FROM {}", table), [])
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    }
+
+    for security in &investments.securities {
+        conn.execute(
+            "INSERT INTO securities (id, symbol, name, kind, currency) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                security.id,
+                security.symbol,
+                security.name,
+                security.kind,
+                security.currency
+            ],
+        )
+        .map_err(|e| AppError::DatabaseError(format!("Error indexando títulos: {}", e)))?;
+    }
+
+    for transaction in &investments.transactions {
+        conn.execute(
+            "INSERT INTO investment_transactions (id, account_id, security_id, kind, quantity, price, fees, amount, cash_amount, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                transaction.id,
+                transaction.account_id,
+                transaction.security_id,
+                transaction.kind,
+                transaction.quantity,
+                transaction.price,
+                transaction.fees,
+                transaction.amount,
+                cash_amount(transaction),
+                transaction.timestamp,
+            ],
+        )
+        .map_err(|e| AppError::DatabaseError(format!("Error indexando operaciones: {}", e)))?;
+    }
+
+    for price in &investments.prices {
+        conn.execute(
+            "INSERT OR REPLACE INTO security_prices (security_id, date, price) VALUES (?1, ?2, ?3)",
+            params![price.security_id, price.date, price.price],
+        )
+        .map_err(|e| AppError::DatabaseError(format!("Error indexando precios: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+// Crea el precio o reemplaza el del mismo título y fecha
+pub fn upsert_price(investments: &mut InvestmentsConfig, price: SecurityPriceItem) {
+    match investments
+        .prices
+        .iter_mut()
+        .find(|p| p.security_id == price.security_id && p.date == price.date)
+    {
+        Some(existing) => *existing = price,
+        None => investments.prices.push(price),
+    }
+}
+
+/// Menor posición que alcanza el título en la cuenta a lo largo de su historia.
+/// Negativa si alguna venta (en su fecha o por operaciones posteriores) no
+/// tiene unidades que la cubran. Las compras del mismo instante van primero.
+pub fn lowest_quantity_held(
+    investments: &InvestmentsConfig,
+    account_id: &str,
+    security_id: &str,
+) -> f64 {
+    let mut movements: Vec<(i64, f64)> = investments
+        .transactions
+        .iter()
+        .filter(|t| t.account_id == account_id && t.security_id == security_id)
+        .filter_map(|t| match t.kind.as_str() {
+            "buy" => Some((t.timestamp, t.quantity)),
+            "sell" => Some((t.timestamp, -t.quantity)),
+            _ => None,
+        })
+        .collect();
+    movements.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.total_cmp(&a.1)));
+
+    let mut held = 0.0;
+    let mut lowest = 0.0_f64;
+    for (_, quantity) in movements {
+        held += quantity;
+        lowest = lowest.min(held);
+    }
+    lowest
+}
+
+/// Lee un CSV de precios `symbol,date,price` (fecha YYYY-MM-DD; acepta `,` o `;`
+/// y una fila de encabezado). Devuelve (símbolo, fecha, precio) y las líneas omitidas.
+pub fn parse_price_csv(content: &str) -> (Vec<(String, i64, f64)>, Vec<SkippedEntryDto>) {
+    let mut prices = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let text = line.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let delimiter = if text.contains(';') { ';' } else { ',' };
+        let fields: Vec<&str> = text
+            .split(delimiter)
+            .map(|f| f.trim().trim_matches('"'))
+            .collect();
+
+        if index == 0
+            && fields
+                .first()
+                .is_some_and(|f| f.eq_ignore_ascii_case("symbol"))
+        {
+            continue;
+        }
+
+        let parsed = match fields.as_slice() {
+            [symbol, date, price, ..] => date_string_to_timestamp(date)
+                .zip(price.replace(' ', "").parse::<f64>().ok())
+                .map(|(date, price)| (symbol.to_string(), date, price)),
+            _ => None,
+        };
+
+        match parsed {
+            Some(entry) if entry.2 > 0.0 => prices.push(entry),
+            _ => skipped.push(SkippedEntryDto {
+                line: index + 1,
+                reason: "Se esperaba symbol,date(YYYY-MM-DD),price".into(),
+                text: text.to_string(),
+            }),
+        }
+    }
+
+    (prices, skipped)
+}
+
+struct TransactionRow {
+    id: String,
+    account_id: String,
+    security_id: String,
+    symbol: String,
+    name: String,
+    kind: String,
+    quantity: f64,
+    price: f64,
+    fees: f64,
+    amount: f64,
+    timestamp: i64,
+}
+
+// Operaciones (t) que suman al saldo de su cuenta (a): desde la apertura y con
+// el título (s) en la moneda de la cuenta
+const COUNTS_IN_BALANCE_SQL: &str = "(a.opening_date IS NULL OR t.timestamp >= a.opening_date)
+    AND (s.currency IS NULL OR UPPER(s.currency) = UPPER(a.currency))";
+
+// `balance_only` deja solo las operaciones que cuentan en el saldo de la cuenta
+fn load_transactions(
+    conn: &Connection,
+    account_id: Option<&str>,
+    until: i64,
+    balance_only: bool,
+) -> Result<Vec<TransactionRow>, AppError> {
+    let sql = format!(
+        "SELECT t.id, t.account_id, t.security_id, COALESCE(s.symbol, t.security_id), COALESCE(s.name, ''),
+                t.kind, t.quantity, t.price, t.fees, COALESCE(t.amount, 0), t.timestamp
+         FROM investment_transactions t
+         LEFT JOIN securities s ON s.id = t.security_id
+         LEFT JOIN accounts a ON a.id = t.account_id
+         WHERE (?1 IS NULL OR t.account_id = ?1) AND t.timestamp <= ?2
+           AND (?3 = 0 OR ({}))
+         ORDER BY t.timestamp ASC, t.id ASC",
+        COUNTS_IN_BALANCE_SQL
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map(params![account_id, until, balance_only], |row| {
+            Ok(TransactionRow {
+                id: row.get(0)?,
+                account_id: row.get(1)?,
+                security_id: row.get(2)?,
+                symbol: row.get(3)?,
+                name: row.get(4)?,
+                kind: row.get(5)?,
+                quantity: row.get(6)?,
+                price: row.get(7)?,
+                fees: row.get(8)?,
+                amount: row.get(9)?,
+                timestamp: row.get(10)?,
+            })
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut transactions = Vec::new();
+    for row in rows {
+        transactions.push(row.map_err(|e| AppError::DatabaseError(e.to_string()))?);
+    }
+
+    Ok(transactions)
+}
+
+// Último precio registrado del título hasta `until`
+fn latest_price(
+    conn: &Connection,
+    security_id: &str,
+    until: i64,
+) -> Result<Option<(f64, i64)>, AppError> {
+    conn.query_row(
+        "SELECT price, date FROM security_prices
+         WHERE security_id = ?1 AND date <= ?2
+         ORDER BY date DESC LIMIT 1",
+        params![security_id, until],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+    .map_err(|e| AppError::DatabaseError(e.to_string()))
+}
+
+// Lotes abiertos de un título en una cuenta: (cantidad, costo unitario)
+struct Position {
+    symbol: String,
+    name: String,
+    lots: VecDeque<(f64, f64)>,
+    last_trade_price: f64,
+}
+
+// Posiciones por (cuenta, título)
+type Positions = Vec<((String, String), Position)>;
+
+/// Recorre las operaciones en orden y calcula posiciones abiertas y ganancias realizadas
+fn run_lots(
+    transactions: &[TransactionRow],
+    method: CostBasisMethod,
+) -> (Positions, Vec<RealizedGainDto>, f64, f64) {
+    let mut positions: Positions = Vec::new();
+    let mut realized = Vec::new();
+    let mut dividends = 0.0;
+    let mut interest = 0.0;
+
+    for t in transactions {
+        let key = (t.account_id.clone(), t.security_id.clone());
+        let index = match positions.iter().position(|(k, _)| *k == key) {
+            Some(index) => index,
+            None => {
+                positions.push((
+                    key,
+                    Position {
+                        symbol: t.symbol.clone(),
+                        name: t.name.clone(),
+                        lots: VecDeque::new(),
+                        last_trade_price: 0.0,
+                    },
+                ));
+                positions.len() - 1
+            }
+        };
+        let position = &mut positions[index].1;
+
+        match t.kind.as_str() {
+            "buy" if t.quantity > 0.0 => {
+                let unit_cost = (t.quantity * t.price + t.fees) / t.quantity;
+                position.last_trade_price = t.price;
+                match method {
+                    CostBasisMethod::Fifo => position.lots.push_back((t.quantity, unit_cost)),
+                    // Costo promedio: un único lote que acumula cantidad y costo
+                    CostBasisMethod::Average => {
+                        let (quantity, cost) = position
+                            .lots
+                            .pop_front()
+                            .map(|(q, c)| (q, q * c))
+                            .unwrap_or((0.0, 0.0));
+                        let total_quantity = quantity + t.quantity;
+                        let total_cost = cost + t.quantity * unit_cost;
+                        position
+                            .lots
+                            .push_back((total_quantity, total_cost / total_quantity));
+                    }
+                }
+            }
+            "sell" if t.quantity > 0.0 => {
+                position.last_trade_price = t.price;
+                let mut remaining = t.quantity;
+                let mut cost_basis = 0.0;
+
+                while remaining > 1e-9 {
+                    let Some(lot) = position.lots.front_mut() else {
+                        break;
+                    };
+                    let used = remaining.min(lot.0);
+                    cost_basis += used * lot.1;
+                    lot.0 -= used;
+                    remaining -= used;
+                    if lot.0 <= 1e-9 {
+                        position.lots.pop_front();
+                    }
+                }
+
+                let proceeds = t.quantity * t.price - t.fees;
+                realized.push(RealizedGainDto {
+                    transaction_id: t.id.clone(),
+                    account_id: t.account_id.clone(),
+                    security_id: t.security_id.clone(),
+                    symbol: t.symbol.clone(),
+                    timestamp: t.timestamp,
+                    quantity: t.quantity,
+                    proceeds,
+                    cost_basis,
+                    gain: proceeds - cost_basis,
+                });
+            }
+            "dividend" => dividends += t.amount,
+            "interest" => interest += t.amount,
+            _ => {}
+        }
+    }
+
+    (positions, realized, dividends, interest)
+}
+
+/// Posiciones abiertas valoradas al último precio conocido hasta `as_of`
+pub fn get_holdings(
+    conn: &Connection,
+    account_id: Option<&str>,
+    method: CostBasisMethod,
+    as_of: i64,
+) -> Result<Vec<HoldingDto>, AppError> {
+    let transactions = load_transactions(conn, account_id, as_of, false)?;
+    let (positions, _, _, _) = run_lots(&transactions, method);
+    build_holdings(conn, positions, as_of)
+}
+
+fn build_holdings(
+    conn: &Connection,
+    positions: Positions,
+    as_of: i64,
+) -> Result<Vec<HoldingDto>, AppError> {
+    let mut holdings = Vec::new();
+
+    for ((account_id, security_id), position) in positions {
+        let quantity: f64 = position.lots.iter().map(|(q, _)| q).sum();
+        if quantity <= 1e-9 {
+            continue;
+        }
+        let cost_basis: f64 = position.lots.iter().map(|(q, c)| q * c).sum();
+
+        // Sin precios registrados se usa el de la última operación
+        let (price, price_date) = match latest_price(conn, &security_id, as_of)? {
+            Some((price, date)) => (price, Some(date)),
+            None => (position.last_trade_price, None),
+        };
+        let market_value = quantity * price;
+
+        holdings.push(HoldingDto {
+            account_id,
+            security_id,
+            symbol: position.symbol,
+            name: position.name,
+            quantity,
+            cost_basis,
+            average_cost: cost_basis / quantity,
+            price,
+            price_date,
+            market_value,
+            unrealized_gain: market_value - cost_basis,
+        });
+    }
+
+    Ok(holdings)
+}
+
+/// Ganancias realizadas en [from, to], dividendos e intereses del periodo y no
+/// realizadas al final del periodo
+pub fn get_investment_gains(
+    conn: &Connection,
+    account_id: Option<&str>,
+    from: i64,
+    to: i64,
+    method: CostBasisMethod,
+) -> Result<InvestmentGainsReportDto, AppError> {
+    // Los lotes dependen de toda la historia, no solo del periodo
+    let transactions = load_transactions(conn, account_id, to, false)?;
+    let (positions, realized, _, _) = run_lots(&transactions, method);
+
+    let in_period: Vec<TransactionRow> = transactions
+        .into_iter()
+        .filter(|t| t.timestamp >= from)
+        .collect();
+    let (_, _, dividends, interest) = run_lots(&in_period, method);
+
+    let realized: Vec<RealizedGainDto> = realized
+        .into_iter()
+        .filter(|r| r.timestamp >= from)
+        .collect();
+    let holdings = build_holdings(conn, positions, to)?;
+
+    Ok(InvestmentGainsReportDto {
+        method: method.as_str().to_string(),
+        total_realized: realized.iter().map(|r| r.gain).sum(),
+        total_unrealized: holdings.iter().map(|h| h.unrealized_gain).sum(),
+        dividends,
+        interest,
+        realized,
+        holdings,
+    })
+}
+
+/// Efectivo movido por compras y ventas y valor de mercado por cuenta. Las
+/// operaciones anteriores a la apertura o de títulos en otra moneda no suman
+/// (ver `get_investment_currency_warnings`).
+pub fn get_investment_totals(
+    conn: &Connection,
+    as_of: i64,
+) -> Result<HashMap<String, (f64, f64)>, AppError> {
+    let mut totals: HashMap<String, (f64, f64)> = HashMap::new();
+
+    let sql = format!(
+        "SELECT t.account_id, SUM(t.cash_amount)
+         FROM investment_transactions t
+         JOIN accounts a ON a.id = t.account_id
+         LEFT JOIN securities s ON s.id = t.security_id
+         WHERE t.timestamp <= ?1 AND {}
+         GROUP BY t.account_id",
+        COUNTS_IN_BALANCE_SQL
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    let rows = stmt
+        .query_map([as_of], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    for row in rows {
+        let (account_id, cash) = row.map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        totals.entry(account_id).or_default().0 += cash;
+    }
+
+    // El valor de mercado sale de las mismas operaciones que el efectivo
+    let transactions = load_transactions(conn, None, as_of, true)?;
+    let (positions, _, _, _) = run_lots(&transactions, CostBasisMethod::Fifo);
+    for holding in build_holdings(conn, positions, as_of)? {
+        totals.entry(holding.account_id).or_default().1 += holding.market_value;
+    }
+
+    Ok(totals)
+}
+
+/// Títulos con operaciones en una cuenta de otra moneda: quedan fuera del saldo
+/// de la cuenta hasta que se corrijan
+pub fn get_investment_currency_warnings(
+    conn: &Connection,
+) -> Result<Vec<InvestmentCurrencyWarningDto>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.id, a.name, a.currency, s.id, s.symbol, s.currency, COUNT(t.id)
+             FROM investment_transactions t
+             JOIN securities s ON s.id = t.security_id
+             JOIN accounts a ON a.id = t.account_id
+             WHERE UPPER(s.currency) <> UPPER(a.currency)
+             GROUP BY a.id, s.id
+             ORDER BY a.name, s.symbol",
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(InvestmentCurrencyWarningDto {
+                account_id: row.get(0)?,
+                account_name: row.get(1)?,
+                account_currency: row.get(2)?,
+                security_id: row.get(3)?,
+                symbol: row.get(4)?,
+                security_currency: row.get(5)?,
+                transaction_count: row.get(6)?,
+            })
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut warnings = Vec::new();
+    for row in rows {
+        warnings.push(row.map_err(|e| AppError::DatabaseError(e.to_string()))?);
+    }
+
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::config_models::SecurityItem;
+
+    fn row(id: &str, kind: &str, quantity: f64, price: f64, timestamp: i64) -> TransactionRow {
+        TransactionRow {
+            id: id.into(),
+            account_id: "broker".into(),
+            security_id: "acme".into(),
+            symbol: "ACME".into(),
+            name: "Acme".into(),
+            kind: kind.into(),
+            quantity,
+            price,
+            fees: 0.0,
+            amount: 0.0,
+            timestamp,
+        }
+    }
+
+    fn item(kind: &str, quantity: f64, timestamp: i64) -> InvestmentTransactionItem {
+        InvestmentTransactionItem {
+            id: format!("{}-{}", kind, timestamp),
+            account_id: "broker".into(),
+            security_id: "acme".into(),
+            kind: kind.into(),
+            quantity,
+            price: 1.0,
+            fees: 0.0,
+            amount: None,
+            timestamp,
+            record_id: None,
+            created_at: 0,
+        }
+    }
+
+    fn lots() -> Vec<TransactionRow> {
+        vec![
+            row("b1", "buy", 10.0, 10.0, 1),
+            row("b2", "buy", 10.0, 20.0, 2),
+            row("s1", "sell", 15.0, 30.0, 3),
+        ]
+    }
+
+    #[test]
+    fn fifo_sells_the_oldest_lots_first() {
+        let (positions, realized, _, _) = run_lots(&lots(), CostBasisMethod::Fifo);
+
+        // 10 a 10 + 5 a 20
+        assert!((realized[0].cost_basis - 200.0).abs() < 1e-9);
+        assert!((realized[0].gain - 250.0).abs() < 1e-9);
+        let remaining: Vec<(f64, f64)> = positions[0].1.lots.iter().copied().collect();
+        assert_eq!(remaining, vec![(5.0, 20.0)]);
+    }
+
+    #[test]
+    fn average_cost_uses_a_single_lot() {
+        let (positions, realized, _, _) = run_lots(&lots(), CostBasisMethod::Average);
+
+        // Costo promedio 15 por unidad
+        assert!((realized[0].cost_basis - 225.0).abs() < 1e-9);
+        let (quantity, unit_cost) = positions[0].1.lots[0];
+        assert!((quantity - 5.0).abs() < 1e-9);
+        assert!((unit_cost - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lowest_quantity_catches_sells_left_uncovered_later() {
+        let mut investments = InvestmentsConfig {
+            transactions: vec![item("buy", 10.0, 1), item("sell", 8.0, 5)],
+            ..Default::default()
+        };
+        assert_eq!(lowest_quantity_held(&investments, "broker", "acme"), 0.0);
+
+        // Una venta anterior que cabe en su fecha deja sin unidades a la posterior
+        investments.transactions.push(item("sell", 5.0, 3));
+        assert!((lowest_quantity_held(&investments, "broker", "acme") + 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn buys_at_the_same_instant_cover_the_sell() {
+        let investments = InvestmentsConfig {
+            transactions: vec![item("sell", 4.0, 7), item("buy", 4.0, 7)],
+            ..Default::default()
+        };
+        assert_eq!(lowest_quantity_held(&investments, "broker", "acme"), 0.0);
+    }
+
+    fn security(id: &str, currency: &str) -> SecurityItem {
+        SecurityItem {
+            id: id.into(),
+            symbol: id.to_uppercase(),
+            name: id.into(),
+            kind: "stock".into(),
+            currency: currency.into(),
+        }
+    }
+
+    #[test]
+    fn totals_skip_foreign_currency_and_pre_opening_holdings() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::fs::db_init::init_sqlite(&mut conn).unwrap();
+        conn.execute(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, is_active, created_at, opening_date)
+             VALUES ('broker', 'Broker', 'investment', 'USD', 0, 1, 0, 10)",
+            [],
+        )
+        .unwrap();
+
+        let mut euro = item("buy", 3.0, 20);
+        euro.id = "euro-buy".into();
+        euro.security_id = "euro".into();
+        let investments = InvestmentsConfig {
+            securities: vec![security("acme", "USD"), security("euro", "EUR")],
+            // La primera compra es anterior a la apertura: ya está en el saldo inicial
+            transactions: vec![item("buy", 5.0, 1), item("buy", 2.0, 20), euro],
+            prices: Vec::new(),
+        };
+        index_investments(&conn, &investments).unwrap();
+
+        // Un título en otra moneda no bloquea el saldo: se omite y se avisa
+        let totals = get_investment_totals(&conn, 100).unwrap();
+        let (cash, market_value) = totals["broker"];
+        assert!((cash + 2.0).abs() < 1e-9);
+        assert!((market_value - 2.0).abs() < 1e-9);
+
+        let warnings = get_investment_currency_warnings(&conn).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].security_id, "euro");
+    }
+}
@@ -0,0 +1,462 @@
+// Préstamos e hipotecas: tabla de amortización y reparto de pagos
+
+use crate::domain::config_models::{LoanPaymentSplit, LoanSettings, RecordItem};
+use crate::domain::error::AppError;
+use crate::dto::loan_dto::{ExtraPaymentDto, LoanScheduleRowDto, LoanSummaryDto};
+use crate::helpers::datetime_helpers::{
+    clamped_date, end_of_day_timestamp, shift_month, timestamp_to_local_date,
+};
+use crate::services::accounts::get_account_balance_at;
+use chrono::Datelike;
+use rusqlite::{params, Connection, OptionalExtension};
+
+pub fn validate_loan_settings(settings: &LoanSettings) -> Result<(), AppError> {
+    if settings.principal <= 0.0 || settings.term_months == 0 {
+        return Err(AppError::ConfigError(
+            "El préstamo necesita un capital y un plazo mayores que cero".into(),
+        ));
+    }
+    if settings.annual_interest_rate < 0.0 {
+        return Err(AppError::ConfigError(
+            "La tasa de interés no puede ser negativa".into(),
+        ));
+    }
+    if !(1..=31).contains(&settings.payment_day) {
+        return Err(AppError::ConfigError(
+            "El día de pago debe estar entre 1 y 31".into(),
+        ));
+    }
+    match settings.amortization.as_deref() {
+        None | Some("french") | Some("german") => Ok(()),
+        Some(other) => Err(AppError::ConfigError(format!(
+            "Sistema de amortización desconocido: {}",
+            other
+        ))),
+    }
+}
+
+pub fn save_loan_settings(
+    conn: &Connection,
+    account_id: &str,
+    settings: &LoanSettings,
+) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO loans (account_id, principal, annual_interest_rate, term_months, start_date, payment_day, amortization, interest_category_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            account_id,
+            settings.principal,
+            settings.annual_interest_rate,
+            settings.term_months,
+            settings.start_date,
+            settings.payment_day,
+            settings.amortization,
+            settings.interest_category_id,
+        ],
+    )
+    .map_err(|e| AppError::DatabaseError(format!("Error guardando préstamo: {}", e)))?;
+
+    Ok(())
+}
+
+pub fn load_loan_settings(
+    conn: &Connection,
+    account_id: &str,
+) -> Result<Option<LoanSettings>, AppError> {
+    conn.query_row(
+        "SELECT principal, annual_interest_rate, term_months, start_date, payment_day, amortization, interest_category_id
+         FROM loans WHERE account_id = ?1",
+        [account_id],
+        |row| {
+            Ok(LoanSettings {
+                principal: row.get(0)?,
+                annual_interest_rate: row.get(1)?,
+                term_months: row.get(2)?,
+                start_date: row.get(3)?,
+                payment_day: row.get(4)?,
+                amortization: row.get(5)?,
+                interest_category_id: row.get(6)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| AppError::DatabaseError(e.to_string()))
+}
+
+fn monthly_rate(settings: &LoanSettings) -> f64 {
+    settings.annual_interest_rate / 12.0 / 100.0
+}
+
+fn is_german(settings: &LoanSettings) -> bool {
+    settings.amortization.as_deref() == Some("german")
+}
+
+fn round_money(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Cuota programada: fija en el sistema francés, la primera en el alemán
+pub fn scheduled_payment(settings: &LoanSettings) -> f64 {
+    let rate = monthly_rate(settings);
+    let term = settings.term_months.max(1);
+
+    if is_german(settings) {
+        settings.principal / term as f64 + settings.principal * rate
+    } else if rate > 0.0 {
+        settings.principal * rate / (1.0 - (1.0 + rate).powi(-(term as i32)))
+    } else {
+        settings.principal / term as f64
+    }
+}
+
+// Fecha de la cuota `number` (la primera cae el mes siguiente al desembolso)
+fn due_date(settings: &LoanSettings, number: u32) -> i64 {
+    let start = timestamp_to_local_date(settings.start_date);
+    let (y, m) = shift_month(start.year(), start.month(), number as i32);
+    end_of_day_timestamp(clamped_date(y, m, settings.payment_day))
+}
+
+/// Tabla de amortización. Los abonos extraordinarios se aplican al capital en
+/// la cuota en cuyo periodo caen; en el sistema francés se mantiene la cuota y
+/// se acorta el plazo.
+pub fn build_loan_schedule(
+    settings: &LoanSettings,
+    extra_payments: &[ExtraPaymentDto],
+) -> Vec<LoanScheduleRowDto> {
+    let rate = monthly_rate(settings);
+    let term = settings.term_months.max(1);
+    let fixed_payment = scheduled_payment(settings);
+    let fixed_principal = settings.principal / term as f64;
+
+    let mut balance = settings.principal;
+    let mut previous_due = settings.start_date;
+    let mut schedule = Vec::new();
+
+    for number in 1..=term {
+        if balance <= 0.005 {
+            break;
+        }
+
+        let due = due_date(settings, number);
+        let interest = balance * rate;
+        let mut principal = if is_german(settings) {
+            fixed_principal
+        } else {
+            fixed_payment - interest
+        };
+        // La última cuota absorbe el redondeo
+        if number == term || principal > balance {
+            principal = balance;
+        }
+        balance -= principal;
+
+        let extra_principal: f64 = extra_payments
+            .iter()
+            .filter(|e| e.timestamp > previous_due && e.timestamp <= due)
+            .map(|e| e.amount)
+            .sum::<f64>()
+            .min(balance);
+        balance -= extra_principal;
+
+        schedule.push(LoanScheduleRowDto {
+            number,
+            due_date: due,
+            payment: principal + interest,
+            interest,
+            principal,
+            extra_principal,
+            remaining_balance: balance.max(0.0),
+        });
+        previous_due = due;
+    }
+
+    schedule
+}
+
+// Vencimientos de cuota en (desde, hasta]: los periodos cuyo interés ya corrió
+fn due_dates_between(settings: &LoanSettings, from: i64, to: i64) -> u32 {
+    let mut count = 0;
+    let mut number = 1;
+    loop {
+        let due = due_date(settings, number);
+        if due > to {
+            break;
+        }
+        if due > from {
+            count += 1;
+        }
+        number += 1;
+    }
+    count
+}
+
+/// Reparte un pago hecho en `timestamp` entre intereses y capital. Solo se
+/// cobran los intereses de los vencimientos transcurridos desde el pago
+/// anterior (o desde el desembolso); un segundo pago en el mismo periodo va
+/// entero a capital.
+pub fn split_loan_payment(
+    conn: &Connection,
+    loan_account_id: &str,
+    settings: &LoanSettings,
+    amount: f64,
+    timestamp: i64,
+) -> Result<LoanPaymentSplit, AppError> {
+    // Saldo adeudado antes del pago (la cuenta del préstamo es negativa)
+    let outstanding = (-get_account_balance_at(conn, loan_account_id, Some(timestamp))?).max(0.0);
+
+    let last_payment: Option<i64> = conn
+        .query_row(
+            "SELECT MAX(timestamp) FROM loan_payments WHERE loan_account_id = ?1 AND timestamp < ?2",
+            params![loan_account_id, timestamp],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    let periods = due_dates_between(
+        settings,
+        last_payment.unwrap_or(settings.start_date),
+        timestamp,
+    );
+
+    let interest = round_money(outstanding * monthly_rate(settings) * periods as f64);
+
+    if interest > 0.0 && amount <= interest {
+        return Err(AppError::ConfigError(format!(
+            "El pago no cubre los intereses del periodo ({:.2})",
+            interest
+        )));
+    }
+
+    let principal = amount - interest;
+    if principal > outstanding + 0.005 {
+        return Err(AppError::ConfigError(format!(
+            "El pago supera el saldo del préstamo ({:.2})",
+            outstanding + interest
+        )));
+    }
+
+    // Capital que correspondía a las cuotas vencidas; lo demás es abono extraordinario
+    let expected_principal = if is_german(settings) {
+        settings.principal / settings.term_months.max(1) as f64 * periods as f64
+    } else {
+        (scheduled_payment(settings) * periods as f64 - interest).max(0.0)
+    };
+    let extra_principal = round_money((principal - expected_principal).max(0.0));
+
+    Ok(LoanPaymentSplit {
+        interest,
+        principal,
+        extra_principal,
+        interest_record_id: None,
+    })
+}
+
+// Reemplaza el pago indexado del registro según su reparto
+pub fn index_loan_payment(conn: &Connection, record: &RecordItem) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM loan_payments WHERE record_id = ?1",
+        [&record.id],
+    )
+    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let (Some(split), Some(loan_account_id)) = (&record.loan_payment, &record.to_account_id) else {
+        return Ok(());
+    };
+
+    conn.execute(
+        "INSERT INTO loan_payments (record_id, loan_account_id, timestamp, interest, principal, extra_principal) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            record.id,
+            loan_account_id,
+            record.timestamp,
+            split.interest,
+            split.principal,
+            split.extra_principal,
+        ],
+    )
+    .map_err(|e| AppError::DatabaseError(format!("Error guardando pago de préstamo: {}", e)))?;
+
+    Ok(())
+}
+
+pub fn get_recorded_extra_payments(
+    conn: &Connection,
+    loan_account_id: &str,
+) -> Result<Vec<ExtraPaymentDto>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT timestamp, extra_principal FROM loan_payments
+             WHERE loan_account_id = ?1 AND extra_principal > 0
+             ORDER BY timestamp",
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map([loan_account_id], |row| {
+            Ok(ExtraPaymentDto {
+                timestamp: row.get(0)?,
+                amount: row.get(1)?,
+            })
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut payments = Vec::new();
+    for row in rows {
+        payments.push(row.map_err(|e| AppError::DatabaseError(e.to_string()))?);
+    }
+
+    Ok(payments)
+}
+
+pub fn load_loan(conn: &Connection, account_id: &str) -> Result<LoanSettings, AppError> {
+    load_loan_settings(conn, account_id)?.ok_or_else(|| {
+        AppError::ConfigError(format!(
+            "La cuenta {} no tiene condiciones de préstamo",
+            account_id
+        ))
+    })
+}
+
+/// Estado del préstamo: saldo, lo pagado y la tabla proyectada con los abonos hechos
+pub fn get_loan_summary(
+    conn: &Connection,
+    account_id: &str,
+    now: i64,
+) -> Result<LoanSummaryDto, AppError> {
+    let settings = load_loan(conn, account_id)?;
+    let outstanding_balance = (-get_account_balance_at(conn, account_id, None)?).max(0.0);
+
+    let (interest_paid, principal_paid, extra_principal_paid, payments_made): (f64, f64, f64, u32) =
+        conn.query_row(
+            "SELECT COALESCE(SUM(interest), 0), COALESCE(SUM(principal), 0),
+                    COALESCE(SUM(extra_principal), 0), COUNT(*)
+             FROM loan_payments WHERE loan_account_id = ?1",
+            [account_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let extra_payments = get_recorded_extra_payments(conn, account_id)?;
+    let schedule = build_loan_schedule(&settings, &extra_payments);
+
+    let next_payment = schedule.iter().find(|row| row.due_date > now).cloned();
+
+    Ok(LoanSummaryDto {
+        account_id: account_id.to_string(),
+        scheduled_payment: scheduled_payment(&settings),
+        payoff_date: schedule.last().map(|row| row.due_date),
+        settings,
+        outstanding_balance,
+        interest_paid,
+        principal_paid,
+        extra_principal_paid,
+        payments_made,
+        next_payment,
+        schedule,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::db_init::init_sqlite;
+    use crate::helpers::datetime_helpers::date_string_to_timestamp;
+
+    fn day(date: &str) -> i64 {
+        date_string_to_timestamp(date).unwrap()
+    }
+
+    fn settings() -> LoanSettings {
+        LoanSettings {
+            principal: 12000.0,
+            annual_interest_rate: 12.0,
+            term_months: 12,
+            start_date: day("2024-01-01"),
+            payment_day: 15,
+            amortization: None,
+            interest_category_id: None,
+        }
+    }
+
+    // Préstamo recién desembolsado: la cuenta debe todo el capital
+    fn loan_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn.execute(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, is_active, created_at)
+             VALUES ('loan', 'Préstamo', 'loan', 'USD', -12000, 1, 0)",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn record_payment(conn: &Connection, timestamp: i64) {
+        conn.execute(
+            "INSERT INTO loan_payments (record_id, loan_account_id, timestamp, interest, principal, extra_principal)
+             VALUES ('previous', 'loan', ?1, 0, 0, 0)",
+            [timestamp],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn scheduled_payment_charges_one_period_of_interest() {
+        let conn = loan_db();
+        let payment = scheduled_payment(&settings());
+
+        let split =
+            split_loan_payment(&conn, "loan", &settings(), payment, day("2024-02-20")).unwrap();
+
+        assert!((split.interest - 120.0).abs() < 1e-9);
+        assert!((split.principal - (payment - 120.0)).abs() < 1e-9);
+        assert!(split.extra_principal.abs() < 1e-9);
+    }
+
+    #[test]
+    fn second_payment_in_the_same_period_goes_to_principal() {
+        let conn = loan_db();
+        record_payment(&conn, day("2024-02-20"));
+
+        let split =
+            split_loan_payment(&conn, "loan", &settings(), 500.0, day("2024-02-25")).unwrap();
+
+        assert_eq!(split.interest, 0.0);
+        assert!((split.principal - 500.0).abs() < 1e-9);
+        assert!((split.extra_principal - 500.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn missed_periods_accrue_interest_for_each_due_date() {
+        let conn = loan_db();
+        record_payment(&conn, day("2024-02-20"));
+
+        let split =
+            split_loan_payment(&conn, "loan", &settings(), 2000.0, day("2024-04-20")).unwrap();
+
+        assert!((split.interest - 240.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn payment_below_interest_or_above_balance_is_rejected() {
+        let conn = loan_db();
+
+        assert!(split_loan_payment(&conn, "loan", &settings(), 50.0, day("2024-02-20")).is_err());
+        assert!(
+            split_loan_payment(&conn, "loan", &settings(), 20000.0, day("2024-02-20")).is_err()
+        );
+    }
+
+    #[test]
+    fn german_schedule_amortizes_a_fixed_principal() {
+        let mut settings = settings();
+        settings.amortization = Some("german".into());
+
+        let schedule = build_loan_schedule(&settings, &[]);
+
+        assert_eq!(schedule.len(), 12);
+        assert!(schedule
+            .iter()
+            .all(|row| (row.principal - 1000.0).abs() < 1e-9));
+        assert!((schedule[0].interest - 120.0).abs() < 1e-9);
+        assert!(schedule.last().unwrap().remaining_balance.abs() < 1e-9);
+    }
+}
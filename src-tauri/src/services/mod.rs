@@ -1,4 +1,18 @@
+pub mod account_history;
 pub mod accounts;
+pub mod beancount_import;
+pub mod budget_alerts;
+pub mod budgets;
 pub mod categories;
+pub mod credit_cards;
+pub mod envelopes;
+pub mod goals;
+pub mod installments;
+pub mod investments;
+pub mod loans;
+pub mod net_worth;
+pub mod plain_text_export;
+pub mod reconciliation;
 pub mod records;
+pub mod rules;
 pub mod stats;
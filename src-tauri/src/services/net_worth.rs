@@ -0,0 +1,145 @@
+// Patrimonio neto: activos menos pasivos en la moneda del workspace
+
+use crate::domain::error::AppError;
+use crate::dto::net_worth_dto::{NetWorthByTypeDto, NetWorthDto};
+use crate::services::accounts::{get_accounts_with_balance, resolve_account_class};
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+
+// Tasa para pasar de `currency` a la moneda base (None si no hay tasa)
+pub fn conversion_rate(
+    currency: &str,
+    base_currency: &str,
+    exchange_rates: &BTreeMap<String, f64>,
+) -> Option<f64> {
+    if currency.eq_ignore_ascii_case(base_currency) {
+        return Some(1.0);
+    }
+    exchange_rates
+        .get(&currency.to_ascii_uppercase())
+        .or_else(|| exchange_rates.get(currency))
+        .copied()
+}
+
+pub fn get_net_worth(
+    conn: &mut Connection,
+    base_currency: &str,
+    exchange_rates: &BTreeMap<String, f64>,
+) -> Result<NetWorthDto, AppError> {
+    // Las cuentas archivadas también cuentan mientras tengan saldo
+    let accounts = get_accounts_with_balance(conn, None)?;
+
+    let mut total_assets = 0.0;
+    let mut total_liabilities = 0.0;
+    let mut by_type: Vec<NetWorthByTypeDto> = Vec::new();
+    let mut missing_rates: Vec<String> = Vec::new();
+
+    for account in accounts {
+        let account_type = account.account_type.clone().unwrap_or_default();
+        let currency = account
+            .currency
+            .clone()
+            .unwrap_or_else(|| base_currency.to_string());
+
+        let Some(rate) = conversion_rate(&currency, base_currency, exchange_rates) else {
+            if !missing_rates.contains(&currency) {
+                missing_rates.push(currency);
+            }
+            continue;
+        };
+
+        let class = account
+            .account_class
+            .clone()
+            .unwrap_or_else(|| resolve_account_class(&account_type, None));
+
+        // Un pasivo tiene saldo negativo cuando se debe
+        let amount = if class == "liability" {
+            -account.balance * rate
+        } else {
+            account.balance * rate
+        };
+
+        if class == "liability" {
+            total_liabilities += amount;
+        } else {
+            total_assets += amount;
+        }
+
+        match by_type
+            .iter_mut()
+            .find(|t| t.account_type == account_type && t.account_class == class)
+        {
+            Some(entry) => {
+                entry.total += amount;
+                entry.account_count += 1;
+            }
+            None => by_type.push(NetWorthByTypeDto {
+                account_type,
+                account_class: class,
+                total: amount,
+                account_count: 1,
+            }),
+        }
+    }
+
+    by_type.sort_by(|a, b| {
+        a.account_class
+            .cmp(&b.account_class)
+            .then(a.account_type.cmp(&b.account_type))
+    });
+
+    Ok(NetWorthDto {
+        currency: base_currency.to_string(),
+        total_assets,
+        total_liabilities,
+        net_worth: total_assets - total_liabilities,
+        by_type,
+        missing_rates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::db_init::init_sqlite;
+
+    fn rates() -> BTreeMap<String, f64> {
+        BTreeMap::from([("EUR".to_string(), 1.1)])
+    }
+
+    #[test]
+    fn conversion_rate_ignores_case() {
+        assert_eq!(conversion_rate("usd", "USD", &rates()), Some(1.0));
+        assert_eq!(conversion_rate("eur", "USD", &rates()), Some(1.1));
+        assert_eq!(conversion_rate("GBP", "USD", &rates()), None);
+    }
+
+    #[test]
+    fn accounts_without_a_rate_are_left_out_and_reported() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, is_active, created_at, account_class) VALUES
+                ('bank', 'Banco', 'bank', 'USD', 1000, 1, 0, NULL),
+                ('euros', 'Euros', 'cash', 'EUR', 100, 0, 0, NULL),
+                ('pounds', 'Libras', 'cash', 'GBP', 500, 1, 0, NULL),
+                ('mortgage', 'Hipoteca', 'bank', 'USD', -400, 1, 0, 'liability');",
+        )
+        .unwrap();
+
+        let net_worth = get_net_worth(&mut conn, "USD", &rates()).unwrap();
+
+        assert_eq!(net_worth.missing_rates, vec!["GBP".to_string()]);
+        assert!((net_worth.total_assets - 1110.0).abs() < 1e-9);
+        assert!((net_worth.total_liabilities - 400.0).abs() < 1e-9);
+        assert!((net_worth.net_worth - 710.0).abs() < 1e-9);
+        let liabilities: Vec<&str> = net_worth
+            .by_type
+            .iter()
+            .filter(|t| t.account_class == "liability")
+            .map(|t| t.account_type.as_str())
+            .collect();
+        assert_eq!(liabilities, vec!["bank"]);
+    }
+}
@@ -0,0 +1,626 @@
+// Exportación a contabilidad en texto plano (beancount, ledger/hledger)
+
+use crate::domain::config_models::{AccountItem, AppConfig, CategoryItem, RecordItem};
+use crate::dto::export_report_dto::SkippedRecordDto;
+use crate::helpers::datetime_helpers::timestamp_to_date_string;
+use crate::services::accounts::resolve_account_class;
+use crate::services::net_worth::conversion_rate;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+const OPENING_BALANCES_ACCOUNT: &str = "Equity:Opening-Balances";
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlainTextFormat {
+    Beancount,
+    #[serde(alias = "hledger")]
+    Ledger,
+}
+
+impl PlainTextFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PlainTextFormat::Beancount => "beancount",
+            PlainTextFormat::Ledger => "journal",
+        }
+    }
+}
+
+struct Posting {
+    account: String,
+    // (monto, moneda); None deja que el formato complete el balance
+    amount: Option<(f64, String)>,
+    // Precio total (@@) cuando la contrapartida está en otra moneda
+    price: Option<(f64, String)>,
+}
+
+struct Transaction {
+    date: String,
+    description: String,
+    postings: Vec<Posting>,
+}
+
+// Monto en otra moneda usando las tasas del workspace (hacia su moneda base)
+fn convert_amount(amount: f64, from: &str, to: &str, app_config: &AppConfig) -> Option<f64> {
+    if from.eq_ignore_ascii_case(to) {
+        return Some(amount);
+    }
+    let from_rate = conversion_rate(from, &app_config.currency, &app_config.exchange_rates)?;
+    let to_rate = conversion_rate(to, &app_config.currency, &app_config.exchange_rates)?;
+    Some(amount * from_rate / to_rate)
+}
+
+/// Construye el journal completo del workspace en el formato indicado.
+/// Devuelve también los registros que no se pudieron exportar balanceados.
+pub fn build_journal(
+    app_config: &AppConfig,
+    accounts: &[AccountItem],
+    categories: &[CategoryItem],
+    records: &[RecordItem],
+    format: PlainTextFormat,
+) -> (String, Vec<SkippedRecordDto>) {
+    // 1. Resolver nombres de cuentas contables (únicos)
+    let mut used_names: HashSet<String> = HashSet::new();
+    let mut account_names: HashMap<&str, String> = HashMap::new();
+    let mut account_currency: HashMap<&str, String> = HashMap::new();
+
+    for acc in accounts {
+        let class = resolve_account_class(&acc.r#type, acc.account_class.as_deref());
+        let root = if class == "liability" {
+            "Liabilities"
+        } else {
+            "Assets"
+        };
+        let name = unique_name(
+            format!("{}:{}:{}", root, type_segment(&acc.r#type), sanitize_segment(&acc.name)),
+            &acc.id,
+            &mut used_names,
+        );
+        account_names.insert(acc.id.as_str(), name);
+        account_currency.insert(
+            acc.id.as_str(),
+            normalize_currency(&acc.currency, &app_config.currency),
+        );
+    }
+
+    let mut category_names: HashMap<&str, String> = HashMap::new();
+    for cat in categories {
+        let root = if cat.r#type == "income" {
+            "Income"
+        } else {
+            "Expenses"
+        };
+        let name = unique_name(
+            format!("{}:{}", root, category_path(cat, categories)),
+            &cat.id,
+            &mut used_names,
+        );
+        category_names.insert(cat.id.as_str(), name);
+    }
+
+    // 2. Construir transacciones
+    let mut transactions: Vec<Transaction> = Vec::new();
+    let mut extra_accounts: HashSet<String> = HashSet::new();
+
+    for acc in accounts {
+        if acc.initial_balance == 0.0 {
+            continue;
+        }
+        transactions.push(Transaction {
+            date: timestamp_to_date_string(acc.opening_date.unwrap_or(acc.created_at)),
+            description: format!("Saldo inicial {}", acc.name),
+            postings: vec![
+                Posting {
+                    account: account_names[acc.id.as_str()].clone(),
+                    amount: Some((
+                        acc.initial_balance,
+                        account_currency[acc.id.as_str()].clone(),
+                    )),
+                    price: None,
+                },
+                Posting {
+                    account: OPENING_BALANCES_ACCOUNT.to_string(),
+                    amount: None,
+                    price: None,
+                },
+            ],
+        });
+        extra_accounts.insert(OPENING_BALANCES_ACCOUNT.to_string());
+    }
+
+    // Los registros anteriores a la apertura ya están en el saldo inicial
+    let opening_dates: HashMap<&str, i64> = accounts
+        .iter()
+        .filter_map(|acc| acc.opening_date.map(|d| (acc.id.as_str(), d)))
+        .collect();
+    let before_opening = |account_id: &str, timestamp: i64| {
+        opening_dates
+            .get(account_id)
+            .is_some_and(|opening| timestamp < *opening)
+    };
+
+    let mut skipped: Vec<SkippedRecordDto> = Vec::new();
+
+    for record in records {
+        let Some(source) = account_names.get(record.account_id.as_str()) else {
+            continue;
+        };
+        let source_currency = account_currency[record.account_id.as_str()].clone();
+        let currency = record
+            .currency
+            .as_deref()
+            .map(|c| normalize_currency(c, &source_currency))
+            .unwrap_or_else(|| source_currency.clone());
+        let source_closed = before_opening(&record.account_id, record.timestamp);
+
+        // Cada lado: (cuenta contable, moneda en que se registra)
+        let (source_leg, counterpart) = match record.r#type.as_str() {
+            "transfer" => {
+                let Some(to_account_id) = record.to_account_id.as_deref() else {
+                    continue;
+                };
+                let Some(target) = account_names.get(to_account_id) else {
+                    continue;
+                };
+                let target_closed = before_opening(to_account_id, record.timestamp);
+
+                // Un lado aún no abierto ya está en su saldo inicial: se registra
+                // contra el saldo de apertura para no perder el movimiento del otro
+                if source_closed && target_closed {
+                    continue;
+                }
+                let source_leg = if source_closed {
+                    (OPENING_BALANCES_ACCOUNT.to_string(), currency.clone())
+                } else {
+                    (source.clone(), source_currency.clone())
+                };
+                let target_leg = if target_closed {
+                    (OPENING_BALANCES_ACCOUNT.to_string(), currency.clone())
+                } else {
+                    (target.clone(), account_currency[to_account_id].clone())
+                };
+                if source_closed || target_closed {
+                    extra_accounts.insert(OPENING_BALANCES_ACCOUNT.to_string());
+                }
+                (source_leg, target_leg)
+            }
+            other => {
+                // Ya están en el saldo inicial de la cuenta
+                if source_closed {
+                    continue;
+                }
+                let fallback = if other == "income" {
+                    "Income:Uncategorized"
+                } else {
+                    "Expenses:Uncategorized"
+                };
+                let category = match record
+                    .category_id
+                    .as_deref()
+                    .and_then(|id| category_names.get(id))
+                {
+                    Some(name) => name.clone(),
+                    None => {
+                        extra_accounts.insert(fallback.to_string());
+                        fallback.to_string()
+                    }
+                };
+                (
+                    (source.clone(), source_currency.clone()),
+                    (category, currency.clone()),
+                )
+            }
+        };
+
+        // El dinero entra a la cuenta origen solo en ingresos
+        let (debit, credit) = if record.r#type == "income" {
+            (source_leg, counterpart)
+        } else {
+            (counterpart, source_leg)
+        };
+
+        // Montos en la moneda de cada lado; si difieren se agrega el precio total
+        let (Some(debit_amount), Some(credit_amount)) = (
+            convert_amount(record.amount, &currency, &debit.1, app_config),
+            convert_amount(record.amount, &currency, &credit.1, app_config),
+        ) else {
+            skipped.push(SkippedRecordDto {
+                record_id: record.id.clone(),
+                reason: format!(
+                    "No hay tasa de cambio para convertir entre {} y {}",
+                    debit.1, credit.1
+                ),
+            });
+            continue;
+        };
+
+        let postings = if debit.1 == credit.1 {
+            vec![
+                Posting {
+                    account: debit.0,
+                    amount: Some((debit_amount, debit.1)),
+                    price: None,
+                },
+                Posting {
+                    account: credit.0,
+                    amount: None,
+                    price: None,
+                },
+            ]
+        } else {
+            vec![
+                Posting {
+                    account: debit.0,
+                    amount: Some((debit_amount, debit.1)),
+                    price: Some((credit_amount, credit.1.clone())),
+                },
+                Posting {
+                    account: credit.0,
+                    amount: Some((-credit_amount, credit.1)),
+                    price: None,
+                },
+            ]
+        };
+
+        transactions.push(Transaction {
+            date: timestamp_to_date_string(record.timestamp),
+            description: record
+                .description
+                .clone()
+                .unwrap_or_else(|| record.r#type.clone()),
+            postings,
+        });
+    }
+
+    transactions.sort_by(|a, b| a.date.cmp(&b.date));
+
+    // 3. Fecha de apertura: la más antigua del journal para que bean-check no falle
+    let open_date = accounts
+        .iter()
+        .map(|a| timestamp_to_date_string(a.created_at))
+        .chain(transactions.iter().map(|t| t.date.clone()))
+        .min()
+        .unwrap_or_else(|| timestamp_to_date_string(app_config.created_at));
+
+    // 4. Serializar
+    let mut out = String::new();
+    let operating_currency = normalize_currency(&app_config.currency, "COP");
+
+    match format {
+        PlainTextFormat::Beancount => {
+            let _ = writeln!(out, "option \"operating_currency\" \"{}\"", operating_currency);
+            out.push('\n');
+            for acc in accounts {
+                let _ = writeln!(
+                    out,
+                    "{} open {} {}",
+                    open_date,
+                    account_names[acc.id.as_str()],
+                    account_currency[acc.id.as_str()]
+                );
+            }
+            for cat in categories {
+                let _ = writeln!(out, "{} open {}", open_date, category_names[cat.id.as_str()]);
+            }
+            let mut extra: Vec<&String> = extra_accounts.iter().collect();
+            extra.sort();
+            for name in extra {
+                let _ = writeln!(out, "{} open {}", open_date, name);
+            }
+        }
+        PlainTextFormat::Ledger => {
+            let _ = writeln!(out, "commodity {}", operating_currency);
+            out.push('\n');
+            let mut names: Vec<&String> = account_names
+                .values()
+                .chain(category_names.values())
+                .chain(extra_accounts.iter())
+                .collect();
+            names.sort();
+            for name in names {
+                let _ = writeln!(out, "account {}", name);
+            }
+        }
+    }
+
+    for tx in &transactions {
+        out.push('\n');
+        match format {
+            PlainTextFormat::Beancount => {
+                let _ = writeln!(out, "{} * \"{}\"", tx.date, escape_beancount(&tx.description));
+            }
+            PlainTextFormat::Ledger => {
+                let _ = writeln!(out, "{} * {}", tx.date, single_line(&tx.description));
+            }
+        }
+        for posting in &tx.postings {
+            match (&posting.amount, &posting.price) {
+                (Some((amount, currency)), Some((price, price_currency))) => {
+                    let _ = writeln!(
+                        out,
+                        "  {}  {:.2} {} @@ {:.2} {}",
+                        posting.account, amount, currency, price, price_currency
+                    );
+                }
+                (Some((amount, currency)), None) => {
+                    let _ = writeln!(out, "  {}  {:.2} {}", posting.account, amount, currency);
+                }
+                (None, _) => {
+                    let _ = writeln!(out, "  {}", posting.account);
+                }
+            }
+        }
+    }
+
+    (out, skipped)
+}
+
+fn type_segment(account_type: &str) -> &'static str {
+    match account_type {
+        "cash" => "Cash",
+        "debit" => "Bank",
+        "credit" => "CreditCard",
+        "loan" => "Loan",
+        "investment" => "Investment",
+        _ => "Other",
+    }
+}
+
+// Los segmentos de cuenta en beancount deben empezar en mayúscula y
+// contener solo letras, números y guiones
+fn sanitize_segment(name: &str) -> String {
+    let ascii: String = name
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'ä' | 'â' => 'a',
+            'Á' | 'À' | 'Ä' | 'Â' => 'A',
+            'é' | 'è' | 'ë' | 'ê' => 'e',
+            'É' | 'È' | 'Ë' | 'Ê' => 'E',
+            'í' | 'ì' | 'ï' | 'î' => 'i',
+            'Í' | 'Ì' | 'Ï' | 'Î' => 'I',
+            'ó' | 'ò' | 'ö' | 'ô' => 'o',
+            'Ó' | 'Ò' | 'Ö' | 'Ô' => 'O',
+            'ú' | 'ù' | 'ü' | 'û' => 'u',
+            'Ú' | 'Ù' | 'Ü' | 'Û' => 'U',
+            'ñ' => 'n',
+            'Ñ' => 'N',
+            other => other,
+        })
+        .collect();
+
+    let segment: String = ascii
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    match segment.chars().next() {
+        Some(c) if c.is_ascii_uppercase() => segment,
+        Some(_) => format!("X{}", segment),
+        None => "Unnamed".to_string(),
+    }
+}
+
+// Segmentos de la categoría desde su raíz: "Vivienda:Servicios:Energia"
+fn category_path(category: &CategoryItem, categories: &[CategoryItem]) -> String {
+    let mut segments = vec![sanitize_segment(&category.name)];
+    let mut parent_id = category.parent_id.as_deref();
+
+    // El límite evita un ciclo infinito si el archivo fue editado a mano
+    while let Some(parent) = parent_id
+        .and_then(|id| categories.iter().find(|c| c.id == id))
+        .filter(|_| segments.len() <= categories.len())
+    {
+        segments.push(sanitize_segment(&parent.name));
+        parent_id = parent.parent_id.as_deref();
+    }
+
+    segments.reverse();
+    segments.join(":")
+}
+
+fn unique_name(candidate: String, id: &str, used: &mut HashSet<String>) -> String {
+    let name = if used.contains(&candidate) {
+        let suffix: String = id
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .take(8)
+            .collect();
+        format!("{}-{}", candidate, suffix)
+    } else {
+        candidate
+    };
+    used.insert(name.clone());
+    name
+}
+
+fn normalize_currency(currency: &str, fallback: &str) -> String {
+    let upper = currency.trim().to_ascii_uppercase();
+    if upper.len() >= 2 && upper.chars().all(|c| c.is_ascii_uppercase()) {
+        upper
+    } else {
+        fallback.to_string()
+    }
+}
+
+fn single_line(text: &str) -> String {
+    text.replace(['\n', '\r'], " ")
+}
+
+fn escape_beancount(text: &str) -> String {
+    single_line(text).replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::datetime_helpers::date_string_to_timestamp;
+
+    fn day(date: &str) -> i64 {
+        date_string_to_timestamp(date).unwrap()
+    }
+
+    fn app_config() -> AppConfig {
+        let mut config = AppConfig::for_workspace("es", "COP");
+        config.exchange_rates.insert("USD".into(), 4000.0);
+        config
+    }
+
+    fn account(id: &str, name: &str, currency: &str, initial_balance: f64) -> AccountItem {
+        AccountItem {
+            id: id.into(),
+            name: name.into(),
+            r#type: "debit".into(),
+            currency: currency.into(),
+            initial_balance,
+            credit_limit: None,
+            is_active: true,
+            created_at: day("2024-01-01"),
+            credit_card: None,
+            account_class: None,
+            loan: None,
+            opening_date: None,
+        }
+    }
+
+    fn category(id: &str, name: &str, parent_id: Option<&str>) -> CategoryItem {
+        CategoryItem {
+            id: id.into(),
+            name: name.into(),
+            r#type: "expense".into(),
+            icon: String::new(),
+            color: String::new(),
+            created_by_user: true,
+            is_active: true,
+            created_at: 0,
+            parent_id: parent_id.map(String::from),
+        }
+    }
+
+    fn record(id: &str, kind: &str, amount: f64, account_id: &str, date: &str) -> RecordItem {
+        RecordItem {
+            id: id.into(),
+            r#type: kind.into(),
+            amount,
+            timestamp: day(date),
+            account_id: account_id.into(),
+            description: Some(format!("Registro \"{}\"", id)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn beancount_journal_opens_accounts_and_nests_subcategories() {
+        let mut coffee = record("r1", "expense", 20.0, "bank", "2024-02-01");
+        coffee.category_id = Some("coffee".into());
+
+        let (journal, skipped) = build_journal(
+            &app_config(),
+            &[account("bank", "Cuenta Ahorros", "COP", 100.0)],
+            &[
+                category("food", "Comida", None),
+                category("coffee", "Café", Some("food")),
+            ],
+            &[coffee],
+            PlainTextFormat::Beancount,
+        );
+
+        assert!(skipped.is_empty());
+        assert!(journal.starts_with("option \"operating_currency\" \"COP\""));
+        assert!(journal.contains("2024-01-01 open Assets:Bank:CuentaAhorros COP"));
+        assert!(journal.contains("2024-01-01 open Expenses:Comida:Cafe"));
+        assert!(journal
+            .contains("  Assets:Bank:CuentaAhorros  100.00 COP\n  Equity:Opening-Balances\n"));
+        assert!(journal.contains("2024-02-01 * \"Registro \\\"r1\\\"\""));
+        assert!(
+            journal.contains("  Expenses:Comida:Cafe  20.00 COP\n  Assets:Bank:CuentaAhorros\n")
+        );
+    }
+
+    #[test]
+    fn transfers_between_currencies_carry_a_total_price() {
+        let mut transfer = record("t1", "transfer", 10.0, "dollars", "2024-02-01");
+        transfer.to_account_id = Some("bank".into());
+
+        let (journal, skipped) = build_journal(
+            &app_config(),
+            &[
+                account("bank", "Banco", "COP", 0.0),
+                account("dollars", "Dolares", "USD", 0.0),
+            ],
+            &[],
+            &[transfer],
+            PlainTextFormat::Ledger,
+        );
+
+        assert!(skipped.is_empty());
+        assert!(journal.starts_with("commodity COP"));
+        assert!(journal.contains("account Assets:Bank:Dolares\n"));
+        assert!(journal.contains("  Assets:Bank:Banco  40000.00 COP @@ 10.00 USD\n"));
+        assert!(journal.contains("  Assets:Bank:Dolares  -10.00 USD\n"));
+    }
+
+    #[test]
+    fn records_without_a_rate_are_skipped() {
+        let mut euros = record("r1", "expense", 5.0, "bank", "2024-02-01");
+        euros.currency = Some("EUR".into());
+
+        let (journal, skipped) = build_journal(
+            &app_config(),
+            &[account("bank", "Banco", "COP", 0.0)],
+            &[],
+            &[euros],
+            PlainTextFormat::Beancount,
+        );
+
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].record_id, "r1");
+        assert!(!journal.contains("2024-02-01 *"));
+    }
+
+    #[test]
+    fn records_before_the_opening_date_are_left_to_the_initial_balance() {
+        let mut bank = account("bank", "Banco", "COP", 500.0);
+        bank.opening_date = Some(day("2024-02-01"));
+
+        let (journal, _) = build_journal(
+            &app_config(),
+            &[bank],
+            &[],
+            &[
+                record("old", "expense", 20.0, "bank", "2024-01-15"),
+                record("new", "expense", 30.0, "bank", "2024-02-10"),
+            ],
+            PlainTextFormat::Beancount,
+        );
+
+        assert!(!journal.contains("Registro \\\"old\\\""));
+        assert!(journal.contains("2024-02-01 * \"Saldo inicial Banco\""));
+        assert!(journal.contains("  Expenses:Uncategorized  30.00 COP\n"));
+    }
+
+    #[test]
+    fn duplicated_names_get_a_suffix_from_the_id() {
+        let (journal, _) = build_journal(
+            &app_config(),
+            &[
+                account("a1", "Banco", "COP", 0.0),
+                account("a2", "Banco!", "COP", 0.0),
+            ],
+            &[],
+            &[],
+            PlainTextFormat::Ledger,
+        );
+
+        assert!(journal.contains("account Assets:Bank:Banco\n"));
+        assert!(journal.contains("account Assets:Bank:Banco-a2\n"));
+    }
+}
@@ -0,0 +1,273 @@
+// Conciliación de cuentas contra extractos bancarios
+
+use crate::domain::config_models::ReconciliationItem;
+use crate::domain::error::AppError;
+use crate::dto::reconciliation_dto::{ReconcileRecordDto, ReconciliationStatusDto};
+use crate::services::accounts::{AFTER_OPENING_SQL, INSTALLMENT_INTEREST_SQL, SIGNED_AMOUNT_SQL};
+use rusqlite::{params, Connection};
+
+/// Registros sin conciliar de la cuenta hasta la fecha del extracto
+pub fn get_uncleared_records(
+    conn: &Connection,
+    account_id: &str,
+    until: i64,
+) -> Result<Vec<ReconcileRecordDto>, AppError> {
+    let sql = format!(
+        "SELECT r.id, r.type, r.amount, {}, r.timestamp, r.description
+         FROM records r
+         WHERE (r.account_id = ?1 OR r.to_account_id = ?1)
+           AND r.cleared_at IS NULL
+           AND r.timestamp <= ?2
+           AND {}
+         ORDER BY r.timestamp ASC",
+        SIGNED_AMOUNT_SQL, AFTER_OPENING_SQL
+    );
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map(params![account_id, until], |row| {
+            Ok(ReconcileRecordDto {
+                id: row.get(0)?,
+                r#type: row.get(1)?,
+                amount: row.get(2)?,
+                signed_amount: row.get(3)?,
+                timestamp: row.get(4)?,
+                description: row.get(5)?,
+                selected: false,
+            })
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut records = Vec::new();
+    for row in rows {
+        records.push(row.map_err(|e| AppError::DatabaseError(e.to_string()))?);
+    }
+
+    Ok(records)
+}
+
+/// Saldo inicial más los registros ya conciliados
+pub fn get_cleared_balance(conn: &Connection, account_id: &str) -> Result<f64, AppError> {
+    let sql = format!(
+        "SELECT a.initial_balance + COALESCE((
+            SELECT SUM({}) FROM records r
+            WHERE (r.account_id = ?1 OR r.to_account_id = ?1) AND r.cleared_at IS NOT NULL
+              AND {}
+         ), 0)
+         FROM accounts a WHERE a.id = ?1",
+        SIGNED_AMOUNT_SQL, AFTER_OPENING_SQL
+    );
+
+    conn.query_row(&sql, [account_id], |row| row.get(0))
+        .map_err(|_| AppError::NotFound(format!("Cuenta {} no encontrada", account_id)))
+}
+
+// Los intereses de cuotas no son registros seleccionables: los vencidos hasta
+// la fecha del extracto ya figuran en él
+fn installment_interest_until(
+    conn: &Connection,
+    account_id: &str,
+    until: i64,
+) -> Result<f64, AppError> {
+    let sql = format!(
+        "SELECT COALESCE(SUM(ii.interest), 0) FROM ({}) ii WHERE ii.due_timestamp <= ?2",
+        INSTALLMENT_INTEREST_SQL
+    );
+
+    conn.query_row(&sql, params![account_id, until], |row| row.get(0))
+        .map_err(|e| AppError::DatabaseError(e.to_string()))
+}
+
+/// Estado de la sesión: registros pendientes, saldo conciliado y diferencia
+pub fn build_status(
+    conn: &Connection,
+    session: ReconciliationItem,
+) -> Result<ReconciliationStatusDto, AppError> {
+    let mut records = get_uncleared_records(conn, &session.account_id, session.statement_date)?;
+    let mut cleared_balance = get_cleared_balance(conn, &session.account_id)?
+        - installment_interest_until(conn, &session.account_id, session.statement_date)?;
+
+    for record in records.iter_mut() {
+        if session.selected_record_ids.contains(&record.id) {
+            record.selected = true;
+            cleared_balance += record.signed_amount;
+        }
+    }
+
+    Ok(ReconciliationStatusDto {
+        difference: session.statement_balance - cleared_balance,
+        cleared_balance,
+        session,
+        records,
+    })
+}
+
+// Ruta del archivo de cada registro
+pub fn record_file_paths(
+    conn: &Connection,
+    record_ids: &[String],
+) -> Result<Vec<String>, AppError> {
+    let mut paths = Vec::new();
+
+    for record_id in record_ids {
+        let file_path: String = conn
+            .query_row(
+                "SELECT file_path FROM records WHERE id = ?1",
+                [record_id],
+                |row| row.get(0),
+            )
+            .map_err(|_| AppError::NotFound(format!("Registro {} no encontrado", record_id)))?;
+        paths.push(file_path);
+    }
+
+    Ok(paths)
+}
+
+// Marca los registros como conciliados en el índice
+pub fn mark_records_cleared(
+    conn: &Connection,
+    record_ids: &[String],
+    cleared_at: i64,
+) -> Result<(), AppError> {
+    for record_id in record_ids {
+        conn.execute(
+            "UPDATE records SET cleared_at = ?1 WHERE id = ?2",
+            params![cleared_at, record_id],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::db_init::init_sqlite;
+    use crate::helpers::datetime_helpers::date_string_to_timestamp;
+
+    fn day(date: &str) -> i64 {
+        date_string_to_timestamp(date).unwrap()
+    }
+
+    // Cuenta con saldo inicial 100 y un ingreso de 50 ya conciliado
+    fn bank_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, is_active, created_at)
+             VALUES ('bank', 'Banco', 'bank', 'USD', 100, 1, 0),
+                    ('cash', 'Efectivo', 'cash', 'USD', 0, 1, 0);",
+        )
+        .unwrap();
+        insert_record(
+            &conn,
+            "salary",
+            "income",
+            50.0,
+            "bank",
+            None,
+            day("2024-03-01"),
+        );
+        conn.execute("UPDATE records SET cleared_at = 1 WHERE id = 'salary'", [])
+            .unwrap();
+        conn
+    }
+
+    fn insert_record(
+        conn: &Connection,
+        id: &str,
+        kind: &str,
+        amount: f64,
+        account_id: &str,
+        to_account_id: Option<&str>,
+        timestamp: i64,
+    ) {
+        conn.execute(
+            "INSERT INTO records (id, type, timestamp, amount, account_id, to_account_id, file_path)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, '')",
+            params![id, kind, timestamp, amount, account_id, to_account_id],
+        )
+        .unwrap();
+    }
+
+    fn session(selected: &[&str]) -> ReconciliationItem {
+        ReconciliationItem {
+            id: "s1".into(),
+            account_id: "bank".into(),
+            statement_date: day("2024-03-15"),
+            statement_balance: 140.0,
+            status: "open".into(),
+            selected_record_ids: selected.iter().map(|id| id.to_string()).collect(),
+            difference: None,
+            adjustment_record_id: None,
+            created_at: 0,
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn difference_counts_only_the_selected_records() {
+        let conn = bank_db();
+        insert_record(
+            &conn,
+            "rent",
+            "expense",
+            30.0,
+            "bank",
+            None,
+            day("2024-03-05"),
+        );
+        insert_record(
+            &conn,
+            "deposit",
+            "transfer",
+            20.0,
+            "cash",
+            Some("bank"),
+            day("2024-03-10"),
+        );
+        insert_record(
+            &conn,
+            "later",
+            "expense",
+            10.0,
+            "bank",
+            None,
+            day("2024-03-20"),
+        );
+
+        let status = build_status(&conn, session(&["rent"])).unwrap();
+        let ids: Vec<&str> = status.records.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["rent", "deposit"]);
+        assert!((status.cleared_balance - 120.0).abs() < 1e-9);
+        assert!((status.difference - 20.0).abs() < 1e-9);
+
+        let status = build_status(&conn, session(&["rent", "deposit"])).unwrap();
+        assert!(status.difference.abs() < 1e-9);
+    }
+
+    #[test]
+    fn cleared_records_leave_the_pending_list() {
+        let conn = bank_db();
+        insert_record(
+            &conn,
+            "rent",
+            "expense",
+            30.0,
+            "bank",
+            None,
+            day("2024-03-05"),
+        );
+
+        mark_records_cleared(&conn, &["rent".to_string()], 2).unwrap();
+
+        assert!((get_cleared_balance(&conn, "bank").unwrap() - 120.0).abs() < 1e-9);
+        assert!(get_uncleared_records(&conn, "bank", day("2024-03-15"))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,8 +1,11 @@
+use crate::domain::config_models::RecordItem;
 use crate::domain::error::AppError;
 use crate::dto::category_dto::CategoryDto;
-use crate::dto::create_record_dto::CreateRecordDto;
 use crate::dto::pagination_dto::Pagination;
 use crate::dto::{account_info_dto::AccountInfoDto, record_dto::RecordDto};
+use crate::services::installments::index_installments;
+use crate::services::loans::index_loan_payment;
+use crate::services::rules::record_tags;
 use rusqlite::{params, Connection};
 
 pub fn get_records(
@@ -21,8 +24,8 @@ pub fn get_records(
             a.id, a.name, a.type, a.currency, a.initial_balance,
             -- Cuenta Destino (Indices 10-14) - Puede ser NULL
             ta.id, ta.name, ta.type, ta.currency, ta.initial_balance,
-            -- Categoría (Indices 15-20) - Puede ser NULL
-            c.id, c.name, c.type, c.icon, c.color, c.is_active
+            -- Categoría (Indices 15-22) - Puede ser NULL
+            c.id, c.name, c.type, c.icon, c.color, c.is_active, c.created_by_user, c.parent_id
         FROM records r
         LEFT JOIN accounts a ON r.account_id = a.id
         LEFT JOIN accounts ta ON r.to_account_id = ta.id
@@ -45,11 +48,12 @@ pub fn get_records(
                 name: row.get(6)?,
                 account_type: row.get(7)?,
                 currency: Some(acc_currency.clone()),
-                // Nota: En un listado transaccional, 'balance' suele ser el saldo al momento
-                // o el actual. Aquí ponemos initial_balance por rendimiento.
+                // Nota: aquí 'balance' es initial_balance por rendimiento; el saldo
+                // después de cada registro está en get_account_ledger.
                 balance: row.get(9)?,
                 initial_balance: row.get(9)?,
                 credit_limit: None, // No está en tu tabla 'accounts' actual
+                ..Default::default()
             };
 
             // --- 2. Construir Cuenta Destino (Option<AccountInfoDto>) ---
@@ -63,6 +67,7 @@ pub fn get_records(
                     balance: row.get(14)?, // Simplificación
                     initial_balance: row.get(14)?,
                     credit_limit: None,
+                    ..Default::default()
                 })
             } else {
                 None
@@ -78,6 +83,8 @@ pub fn get_records(
                     icon: row.get(18)?,
                     color: row.get(19)?,
                     is_active: row.get::<_, i32>(20)? == 1, // Convertir Integer sqlite a Bool
+                    created_by_user: row.get::<_, i32>(21)? == 1,
+                    parent_id: row.get(22)?,
                 })
             } else {
                 None
@@ -125,15 +132,14 @@ pub fn get_records(
 }
 
 pub fn create_record_in_database(
-    conn: &mut Connection,
-    record: &CreateRecordDto,
-    record_id: &str,
+    conn: &Connection,
+    record: &RecordItem,
     file_path: &str,
 ) -> Result<(), AppError> {
     conn.execute(
-        "INSERT INTO records (id, type, timestamp, amount, account_id, to_account_id, category_id, description, file_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        "INSERT INTO records (id, type, timestamp, amount, account_id, to_account_id, category_id, description, file_path, cleared_at, currency) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
         params![
-            record_id,
+            record.id,
             record.r#type,
             record.timestamp,
             record.amount,
@@ -142,9 +148,62 @@ pub fn create_record_in_database(
             record.category_id,
             record.description,
             file_path,
+            record.cleared_at,
+            record.currency,
         ],
     )
     .map_err(|e| AppError::DatabaseError(format!("Error creando registro: {}", e)))?;
 
+    index_installments(conn, record)?;
+    index_loan_payment(conn, record)?;
+    index_record_tags(conn, record)?;
+
+    Ok(())
+}
+
+// Inserta o reemplaza el registro (mismo criterio que el indexador)
+pub fn upsert_record_in_database(
+    conn: &Connection,
+    record: &RecordItem,
+    file_path: &str,
+) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO records (id, type, timestamp, amount, account_id, to_account_id, category_id, description, file_path, cleared_at, currency) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            record.id,
+            record.r#type,
+            record.timestamp,
+            record.amount,
+            record.account_id,
+            record.to_account_id,
+            record.category_id,
+            record.description,
+            file_path,
+            record.cleared_at,
+            record.currency,
+        ],
+    )
+    .map_err(|e| AppError::DatabaseError(format!("Error actualizando registro: {}", e)))?;
+
+    index_installments(conn, record)?;
+    index_loan_payment(conn, record)?;
+    index_record_tags(conn, record)?;
+
+    Ok(())
+}
+
+// Reemplaza las etiquetas indexadas del registro (viven en su metadata)
+pub fn index_record_tags(conn: &Connection, record: &RecordItem) -> Result<(), AppError> {
+    conn.execute("DELETE FROM record_tags WHERE record_id = ?1", [&record.id])
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    for tag in record_tags(record) {
+        conn.execute(
+            "INSERT OR IGNORE INTO record_tags (record_id, tag_id) VALUES (?1, ?2)",
+            params![record.id, tag],
+        )
+        .map_err(|e| AppError::DatabaseError(format!("Error guardando etiquetas: {}", e)))?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,336 @@
+// Motor de reglas de auto-categorización
+
+use crate::domain::config_models::{CategoryItem, RecordItem, RuleItem};
+use crate::domain::error::AppError;
+use regex::{Regex, RegexBuilder};
+use serde_json::{json, Value};
+
+pub struct CompiledRule {
+    pub rule: RuleItem,
+    description_regex: Option<Regex>,
+    // Tipo de la categoría que asigna la regla (None si no existe)
+    category_type: Option<String>,
+}
+
+/// Compila las expresiones regulares de las reglas activas (en orden)
+pub fn compile_rules(
+    rules: Vec<RuleItem>,
+    categories: &[CategoryItem],
+) -> Result<Vec<CompiledRule>, AppError> {
+    let mut compiled = Vec::new();
+
+    for rule in rules.into_iter().filter(|r| r.is_active) {
+        compiled.push(compile_rule(rule, categories)?);
+    }
+
+    Ok(compiled)
+}
+
+pub fn compile_rule(rule: RuleItem, categories: &[CategoryItem]) -> Result<CompiledRule, AppError> {
+    let description_regex = match &rule.conditions.description_regex {
+        Some(pattern) => Some(
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| {
+                    AppError::ConfigError(format!("Regex inválida en la regla {}: {}", rule.name, e))
+                })?,
+        ),
+        None => None,
+    };
+
+    let category_type = rule.actions.category_id.as_ref().and_then(|id| {
+        categories
+            .iter()
+            .find(|c| &c.id == id)
+            .map(|c| c.r#type.clone())
+    });
+
+    Ok(CompiledRule {
+        rule,
+        description_regex,
+        category_type,
+    })
+}
+
+pub fn rule_matches(compiled: &CompiledRule, record: &RecordItem) -> bool {
+    let conditions = &compiled.rule.conditions;
+    let description = record.description.clone().unwrap_or_default();
+
+    if let Some(regex) = &compiled.description_regex {
+        if !regex.is_match(&description) {
+            return false;
+        }
+    }
+
+    if let Some(needle) = &conditions.description_contains {
+        if !description.to_lowercase().contains(&needle.to_lowercase()) {
+            return false;
+        }
+    }
+
+    if let Some(payee) = &conditions.payee {
+        match record_payee(record) {
+            Some(current) if current.eq_ignore_ascii_case(payee) => {}
+            _ => return false,
+        }
+    }
+
+    if conditions.min_amount.is_some_and(|min| record.amount < min)
+        || conditions.max_amount.is_some_and(|max| record.amount > max)
+    {
+        return false;
+    }
+
+    if conditions
+        .account_id
+        .as_ref()
+        .is_some_and(|id| id != &record.account_id)
+    {
+        return false;
+    }
+
+    if conditions
+        .record_type
+        .as_ref()
+        .is_some_and(|t| t != &record.r#type)
+    {
+        return false;
+    }
+
+    true
+}
+
+/// Aplica la primera regla que coincida y tenga alguna acción aplicable al
+/// registro (una regla cuya única acción es una categoría de otro tipo se salta).
+/// Devuelve true si el registro cambió.
+pub fn apply_rules(rules: &[CompiledRule], record: &mut RecordItem) -> bool {
+    match rules
+        .iter()
+        .find(|r| rule_matches(r, record) && has_applicable_action(r, record))
+    {
+        Some(rule) => apply_actions(rule, record),
+        None => false,
+    }
+}
+
+// La regla puede actuar sobre el registro aunque ya tenga sus valores
+fn has_applicable_action(compiled: &CompiledRule, record: &RecordItem) -> bool {
+    let actions = &compiled.rule.actions;
+
+    (actions.category_id.is_some() && compiled.category_type.as_ref() == Some(&record.r#type))
+        || actions.description.is_some()
+        || actions.payee.is_some()
+        || !actions.tags.is_empty()
+}
+
+pub fn apply_actions(compiled: &CompiledRule, record: &mut RecordItem) -> bool {
+    let actions = &compiled.rule.actions;
+    let mut changed = false;
+
+    // Solo se asigna una categoría existente del mismo tipo que el registro
+    // (las transferencias no llevan categoría)
+    if let Some(category_id) = &actions.category_id {
+        if compiled.category_type.as_ref() == Some(&record.r#type)
+            && record.category_id.as_ref() != Some(category_id)
+        {
+            record.category_id = Some(category_id.clone());
+            changed = true;
+        }
+    }
+
+    if let Some(description) = &actions.description {
+        if record.description.as_ref() != Some(description) {
+            record.description = Some(description.clone());
+            changed = true;
+        }
+    }
+
+    // payee y tags viven en la metadata del registro
+    if actions.payee.is_some() || !actions.tags.is_empty() {
+        let mut metadata = match record.metadata.take() {
+            Some(Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+
+        if let Some(payee) = &actions.payee {
+            if metadata.get("payee").and_then(|p| p.as_str()) != Some(payee.as_str()) {
+                metadata.insert("payee".into(), json!(payee));
+                changed = true;
+            }
+        }
+
+        let mut tags: Vec<String> = metadata
+            .get("tags")
+            .and_then(|t| serde_json::from_value(t.clone()).ok())
+            .unwrap_or_default();
+        for tag in &actions.tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+                changed = true;
+            }
+        }
+        if !tags.is_empty() {
+            metadata.insert("tags".into(), json!(tags));
+        }
+
+        record.metadata = Some(Value::Object(metadata));
+    }
+
+    changed
+}
+
+pub fn record_payee(record: &RecordItem) -> Option<String> {
+    record
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("payee"))
+        .and_then(|p| p.as_str())
+        .map(|p| p.to_string())
+}
+
+pub fn record_tags(record: &RecordItem) -> Vec<String> {
+    record
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("tags"))
+        .and_then(|t| serde_json::from_value(t.clone()).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::config_models::{RuleActions, RuleConditions};
+
+    fn category(id: &str, kind: &str) -> CategoryItem {
+        CategoryItem {
+            id: id.into(),
+            name: id.into(),
+            r#type: kind.into(),
+            icon: String::new(),
+            color: String::new(),
+            created_by_user: true,
+            is_active: true,
+            created_at: 0,
+            parent_id: None,
+        }
+    }
+
+    fn rule(id: &str, conditions: RuleConditions, actions: RuleActions) -> RuleItem {
+        RuleItem {
+            id: id.into(),
+            name: id.into(),
+            is_active: true,
+            conditions,
+            actions,
+            created_at: 0,
+        }
+    }
+
+    fn expense(description: &str) -> RecordItem {
+        RecordItem {
+            id: "r1".into(),
+            r#type: "expense".into(),
+            amount: 50.0,
+            account_id: "bank".into(),
+            description: Some(description.into()),
+            ..Default::default()
+        }
+    }
+
+    fn assigns(category_id: &str) -> RuleActions {
+        RuleActions {
+            category_id: Some(category_id.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn a_rule_with_a_category_of_another_type_lets_the_next_one_apply() {
+        let categories = vec![category("salary", "income"), category("food", "expense")];
+        let contains = |text: &str| RuleConditions {
+            description_contains: Some(text.into()),
+            ..Default::default()
+        };
+        let rules = compile_rules(
+            vec![
+                rule("income-rule", contains("market"), assigns("salary")),
+                rule("expense-rule", contains("market"), assigns("food")),
+            ],
+            &categories,
+        )
+        .unwrap();
+
+        let mut record = expense("Super Market");
+        assert!(apply_rules(&rules, &mut record));
+        assert_eq!(record.category_id.as_deref(), Some("food"));
+    }
+
+    #[test]
+    fn the_first_applicable_rule_wins_even_when_already_applied() {
+        let categories = vec![category("food", "expense"), category("other", "expense")];
+        let rules = compile_rules(
+            vec![
+                rule("first", RuleConditions::default(), assigns("food")),
+                rule("second", RuleConditions::default(), assigns("other")),
+            ],
+            &categories,
+        )
+        .unwrap();
+
+        let mut record = expense("Almuerzo");
+        record.category_id = Some("food".into());
+        assert!(!apply_rules(&rules, &mut record));
+        assert_eq!(record.category_id.as_deref(), Some("food"));
+    }
+
+    #[test]
+    fn invalid_regex_is_a_config_error() {
+        let conditions = RuleConditions {
+            description_regex: Some("(sin cerrar".into()),
+            ..Default::default()
+        };
+        let result = compile_rules(vec![rule("bad", conditions, assigns("food"))], &[]);
+
+        assert!(matches!(result, Err(AppError::ConfigError(_))));
+    }
+
+    #[test]
+    fn inactive_rules_are_not_compiled() {
+        let mut inactive = rule("off", RuleConditions::default(), assigns("food"));
+        inactive.is_active = false;
+
+        let rules = compile_rules(vec![inactive], &[category("food", "expense")]).unwrap();
+
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn regex_ignores_case_and_all_conditions_must_hold() {
+        let conditions = RuleConditions {
+            description_regex: Some("^uber".into()),
+            min_amount: Some(10.0),
+            max_amount: Some(100.0),
+            account_id: Some("bank".into()),
+            record_type: Some("expense".into()),
+            ..Default::default()
+        };
+        let compiled = compile_rule(rule("uber", conditions, assigns("food")), &[]).unwrap();
+
+        assert!(rule_matches(&compiled, &expense("UBER trip")));
+        assert!(!rule_matches(&compiled, &expense("Pago Uber")));
+
+        let mut too_big = expense("Uber trip");
+        too_big.amount = 150.0;
+        assert!(!rule_matches(&compiled, &too_big));
+
+        let mut other_account = expense("Uber trip");
+        other_account.account_id = "cash".into();
+        assert!(!rule_matches(&compiled, &other_account));
+
+        let mut income = expense("Uber trip");
+        income.r#type = "income".into();
+        assert!(!rule_matches(&compiled, &income));
+    }
+}
@@ -1,7 +1,16 @@
 /// Statistics calculation service
 use crate::domain::error::AppError;
+use crate::dto::category_breakdown_dto::{CategoryBreakdownDto, CategoryBreakdownItemDto};
+use crate::helpers::datetime_helpers::{
+    end_of_day_timestamp, shift_month, start_of_day_timestamp, timestamp_now,
+    timestamp_to_local_date,
+};
+use crate::services::budgets::month_bounds;
+use crate::services::net_worth::get_net_worth;
+use chrono::{Datelike, Days};
 use rusqlite::{params, Connection, Result};
 use serde::Serialize;
+use std::collections::BTreeMap;
 
 #[derive(Serialize)]
 pub struct DashboardStats {
@@ -12,26 +21,33 @@ pub struct DashboardStats {
 
 #[derive(Serialize)]
 pub struct CategoryPercentage {
+    pub category_id: String,
     pub category_name: String,
     pub color: String,
     pub amount: f64,
     pub percentage: f64,
+    // Tiene subcategorías con gastos en el periodo (se puede profundizar)
+    pub has_children: bool,
 }
 
-pub fn calculate_overall_stats(conn: &mut Connection) -> Result<DashboardStats, AppError> {
-    // 1. Obtener la suma de todos los balances iniciales
-    let initial_balances_sum: f64 = conn
-        .query_row("SELECT SUM(initial_balance) FROM accounts", [], |row| {
-            row.get(0)
-        })
-        .unwrap_or(0.0);
+/// Totales del tablero. El balance total es el patrimonio neto en la moneda
+/// del workspace: los pasivos restan y las demás monedas se convierten.
+pub fn calculate_overall_stats(
+    conn: &mut Connection,
+    base_currency: &str,
+    exchange_rates: &BTreeMap<String, f64>,
+) -> Result<DashboardStats, AppError> {
+    // 1. Balance total desde el patrimonio neto (cuentas sin tasa quedan fuera)
+    let net_worth = get_net_worth(conn, base_currency, exchange_rates)?;
 
     // 2. Obtener la suma de ingresos y gastos
+    // El gasto reportado cuenta las compras a cuotas solo por las cuotas ya causadas.
     let mut stmt = conn
         .prepare(
             "SELECT 
                 SUM(CASE WHEN type = 'income' THEN amount ELSE 0 END) as income,
-                SUM(CASE WHEN type = 'expense' THEN amount ELSE 0 END) as expense
+                (SELECT SUM(amount) FROM expense_entries
+                 WHERE is_installment = 0 OR timestamp <= CAST(strftime('%s', 'now') AS INTEGER)) as reported_expense
              FROM records",
         )
         .map_err(|e| AppError::DatabaseError(e.to_string()))?;
@@ -39,13 +55,12 @@ pub fn calculate_overall_stats(conn: &mut Connection) -> Result<DashboardStats,
     let stats = stmt
         .query_row([], |row| {
             let income: f64 = row.get(0).unwrap_or(0.0);
-            let expense: f64 = row.get(1).unwrap_or(0.0);
+            let reported_expense: f64 = row.get(1).unwrap_or(0.0);
 
-            // 3. El balance real es: Saldo Inicial + Ingresos - Gastos
             Ok(DashboardStats {
                 total_income: income,
-                total_expense: expense,
-                total_balance: initial_balances_sum + income - expense,
+                total_expense: reported_expense,
+                total_balance: net_worth.net_worth,
             })
         })
         .map_err(|e| AppError::DatabaseError(e.to_string()))?;
@@ -53,29 +68,52 @@ pub fn calculate_overall_stats(conn: &mut Connection) -> Result<DashboardStats,
     Ok(stats)
 }
 
+/// Gastos agrupados por las categorías de un nivel, sumando sus subcategorías.
+/// Sin `parent_id` se muestran las de primer nivel; con él, sus hijas directas
+/// más la propia categoría padre para los gastos asignados a ella.
 pub fn get_expenses_by_category(
     conn: &Connection,
     days: i64,
+    parent_id: Option<&str>,
 ) -> Result<Vec<CategoryPercentage>, AppError> {
     let seconds_ago = days * 24 * 60 * 60;
 
+    // bucket_id: categoría del nivel en la que se acumula cada subcategoría
     let mut stmt = conn.prepare(
-        "SELECT 
-            c.name, c.color, SUM(r.amount) as total,
-            (SUM(r.amount) * 100.0 / (SELECT SUM(amount) FROM records WHERE type = 'expense' AND timestamp > strftime('%s', 'now') - ?1)) as percent
-         FROM records r
-         JOIN categories c ON r.category_id = c.id
-         WHERE r.type = 'expense' AND r.timestamp > strftime('%s', 'now') - ?1
-         GROUP BY c.id"
+        "WITH RECURSIVE tree(id, bucket_id) AS (
+            SELECT id, id FROM categories WHERE parent_id IS ?2
+            UNION ALL
+            SELECT id, id FROM categories WHERE id = ?2
+            UNION ALL
+            SELECT c.id, t.bucket_id FROM categories c
+            JOIN tree t ON c.parent_id = t.id
+            WHERE t.id IS NOT ?2
+         ),
+         period AS (
+            SELECT * FROM expense_entries
+            WHERE timestamp > strftime('%s', 'now') - ?1 AND timestamp <= CAST(strftime('%s', 'now') AS INTEGER)
+         )
+         SELECT
+            b.id, b.name, b.color, SUM(r.amount) as total,
+            (SUM(r.amount) * 100.0 / (SELECT SUM(amount) FROM period
+                WHERE ?2 IS NULL OR category_id IN (SELECT id FROM tree))) as percent,
+            MAX(r.category_id != b.id) as has_children
+         FROM period r
+         JOIN tree t ON r.category_id = t.id
+         JOIN categories b ON t.bucket_id = b.id
+         GROUP BY b.id
+         ORDER BY total DESC"
     ).map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
     let rows = stmt
-        .query_map(params![seconds_ago], |row| {
+        .query_map(params![seconds_ago, parent_id], |row| {
             Ok(CategoryPercentage {
-                category_name: row.get(0)?,
-                color: row.get(1)?,
-                amount: row.get(2)?,
-                percentage: row.get(3)?,
+                category_id: row.get(0)?,
+                category_name: row.get(1)?,
+                color: row.get(2)?,
+                amount: row.get(3)?,
+                percentage: row.get(4)?,
+                has_children: row.get::<_, i32>(5)? == 1,
             })
         })
         .map_err(|e| AppError::DatabaseError(e.to_string()))?;
@@ -86,3 +124,313 @@ pub fn get_expenses_by_category(
     }
     Ok(results)
 }
+
+pub const NAMED_PERIODS: [&str; 9] = [
+    "this_week",
+    "this_month",
+    "last_month",
+    "this_year",
+    "last_year",
+    "last_7_days",
+    "last_30_days",
+    "last_90_days",
+    "last_365_days",
+];
+
+/// Inicio y fin (inclusive) de un periodo con nombre, relativo a hoy.
+/// La semana empieza según `week_start` de app.json ("monday" o "sunday").
+pub fn resolve_period(period: &str, week_start: &str) -> Result<(i64, i64), AppError> {
+    let today = timestamp_to_local_date(timestamp_now());
+    let end_of_today = end_of_day_timestamp(today);
+    let days_back = |days: u64| {
+        start_of_day_timestamp(today.checked_sub_days(Days::new(days - 1)).unwrap_or(today))
+    };
+
+    let bounds = match period.trim() {
+        "this_week" => {
+            let offset = match week_start {
+                "sunday" => today.weekday().num_days_from_sunday(),
+                _ => today.weekday().num_days_from_monday(),
+            };
+            (days_back(offset as u64 + 1), end_of_today)
+        }
+        "this_month" => month_bounds(today.year(), today.month()),
+        "last_month" => {
+            let (year, month) = shift_month(today.year(), today.month(), -1);
+            month_bounds(year, month)
+        }
+        "this_year" => (
+            month_bounds(today.year(), 1).0,
+            month_bounds(today.year(), 12).1,
+        ),
+        "last_year" => (
+            month_bounds(today.year() - 1, 1).0,
+            month_bounds(today.year() - 1, 12).1,
+        ),
+        "last_7_days" => (days_back(7), end_of_today),
+        "last_30_days" => (days_back(30), end_of_today),
+        "last_90_days" => (days_back(90), end_of_today),
+        "last_365_days" => (days_back(365), end_of_today),
+        other => {
+            return Err(AppError::ConfigError(format!(
+                "Periodo desconocido: '{}' (disponibles: {})",
+                other,
+                NAMED_PERIODS.join(", ")
+            )))
+        }
+    };
+
+    Ok(bounds)
+}
+
+/// Montos y cantidad de registros por categoría en [start, end], sumando
+/// subcategorías como `get_expenses_by_category`. Los gastos a cuotas cuentan
+/// por cada cuota que vence en el periodo. Incluye categorías deshabilitadas y,
+/// en el primer nivel, un grupo para los registros sin categoría.
+pub fn get_category_breakdown(
+    conn: &Connection,
+    record_type: &str,
+    start: i64,
+    end: i64,
+    account_ids: Option<&[String]>,
+    currency: Option<&str>,
+    parent_id: Option<&str>,
+) -> Result<CategoryBreakdownDto, AppError> {
+    let source = match record_type {
+        "expense" => "expense_entries",
+        "income" => {
+            "(SELECT id AS record_id, timestamp, amount, category_id, account_id, currency
+              FROM records WHERE type = 'income')"
+        }
+        other => {
+            return Err(AppError::ConfigError(format!(
+                "Tipo de registro no soportado en el desglose: '{}' (use expense o income)",
+                other
+            )))
+        }
+    };
+    if start > end {
+        return Err(AppError::ConfigError(
+            "La fecha de inicio debe ser anterior a la fecha final".into(),
+        ));
+    }
+
+    // Sin cuentas en el filtro se incluyen todas
+    let account_ids = account_ids
+        .filter(|ids| !ids.is_empty())
+        .map(|ids| serde_json::to_string(ids).unwrap_or_default());
+
+    // Cada registro cuenta en su moneda (o la de su cuenta). Los montos de
+    // monedas distintas no se suman: si hay varias hay que elegir una.
+    let currencies: Vec<String> = {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT DISTINCT UPPER(COALESCE(e.currency, a.currency)) FROM {} e
+                 LEFT JOIN accounts a ON a.id = e.account_id
+                 WHERE e.timestamp >= ?1 AND e.timestamp <= ?2
+                   AND (?3 IS NULL OR e.account_id IN (SELECT value FROM json_each(?3)))
+                   AND COALESCE(e.currency, a.currency) IS NOT NULL
+                 ORDER BY 1",
+                source
+            ))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![start, end, account_ids], |row| row.get(0))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+    };
+    let currency = match currency {
+        Some(currency) => Some(currency.to_string()),
+        None if currencies.len() > 1 => {
+            return Err(AppError::ConfigError(format!(
+                "Los registros del periodo están en varias monedas ({}); indique una",
+                currencies.join(", ")
+            )))
+        }
+        None => currencies.into_iter().next(),
+    };
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "WITH RECURSIVE tree(id, bucket_id) AS (
+                SELECT id, id FROM categories WHERE parent_id IS ?1
+                UNION ALL
+                SELECT id, id FROM categories WHERE id = ?1
+                UNION ALL
+                SELECT c.id, t.bucket_id FROM categories c
+                JOIN tree t ON c.parent_id = t.id
+                WHERE t.id IS NOT ?1
+             ),
+             entries AS (
+                SELECT e.record_id, e.amount, e.category_id FROM {} e
+                LEFT JOIN accounts a ON a.id = e.account_id
+                WHERE e.timestamp >= ?2 AND e.timestamp <= ?3
+                  AND (?4 IS NULL OR e.account_id IN (SELECT value FROM json_each(?4)))
+                  AND (?5 IS NULL OR UPPER(COALESCE(e.currency, a.currency)) = ?5)
+             )
+             SELECT
+                t.bucket_id, b.name, b.color, COALESCE(b.is_active, 1),
+                SUM(e.amount) AS total, COUNT(DISTINCT e.record_id),
+                MAX(t.id IS NOT t.bucket_id)
+             FROM entries e
+             LEFT JOIN tree t ON t.id = e.category_id
+             LEFT JOIN categories b ON b.id = t.bucket_id
+             WHERE ?1 IS NULL OR t.id IS NOT NULL
+             GROUP BY t.bucket_id
+             ORDER BY total DESC",
+            source
+        ))
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let rows = stmt
+        .query_map(
+            params![parent_id, start, end, account_ids, currency],
+            |row| {
+                let category_id: Option<String> = row.get(0)?;
+                Ok(CategoryBreakdownItemDto {
+                    category_name: row
+                        .get::<_, Option<String>>(1)?
+                        .unwrap_or_else(|| "Sin categoría".into()),
+                    color: row
+                        .get::<_, Option<String>>(2)?
+                        .unwrap_or_else(|| "#94a3b8".into()), // Slate 400
+                    is_active: row.get::<_, i32>(3)? == 1,
+                    amount: row.get(4)?,
+                    count: row.get(5)?,
+                    percentage: 0.0,
+                    has_children: row.get::<_, i32>(6)? == 1,
+                    category_id,
+                })
+            },
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let mut categories = Vec::new();
+    for row in rows {
+        categories.push(row.map_err(|e| AppError::DatabaseError(e.to_string()))?);
+    }
+
+    // Los porcentajes son sobre el total del nivel mostrado
+    let total: f64 = categories.iter().map(|c| c.amount).sum();
+    let count = categories.iter().map(|c| c.count).sum();
+    if total > 0.0 {
+        for category in categories.iter_mut() {
+            category.percentage = category.amount * 100.0 / total;
+        }
+    }
+
+    Ok(CategoryBreakdownDto {
+        r#type: record_type.to_string(),
+        start,
+        end,
+        currency,
+        total,
+        count,
+        categories,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::db_init::init_sqlite;
+
+    #[test]
+    fn dashboard_balance_matches_net_worth() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, is_active, created_at) VALUES
+                ('bank', 'Banco', 'bank', 'USD', 1000, 1, 0),
+                ('card', 'Tarjeta', 'credit', 'USD', -300, 1, 0),
+                ('euros', 'Euros', 'cash', 'EUR', 100, 1, 0);",
+        )
+        .unwrap();
+        let rates = BTreeMap::from([("EUR".to_string(), 1.1)]);
+
+        let stats = calculate_overall_stats(&mut conn, "USD", &rates).unwrap();
+        let net_worth = get_net_worth(&mut conn, "USD", &rates).unwrap();
+
+        // La deuda de la tarjeta resta y los euros se convierten
+        assert!((stats.total_balance - 810.0).abs() < 1e-9);
+        assert!((stats.total_balance - net_worth.net_worth).abs() < 1e-9);
+    }
+
+    #[test]
+    fn every_named_period_resolves_to_a_valid_range() {
+        let now = timestamp_now();
+        for period in NAMED_PERIODS {
+            let (start, end) = resolve_period(period, "monday").unwrap();
+            assert!(start < end, "{}", period);
+            if !matches!(period, "last_month" | "last_year") {
+                assert!(start <= now && now <= end, "{}", period);
+            }
+        }
+    }
+
+    #[test]
+    fn week_starts_on_the_configured_day() {
+        let (monday_start, _) = resolve_period("this_week", "monday").unwrap();
+        let (sunday_start, _) = resolve_period("this_week", "sunday").unwrap();
+
+        assert_eq!(
+            timestamp_to_local_date(monday_start).weekday(),
+            chrono::Weekday::Mon
+        );
+        assert_eq!(
+            timestamp_to_local_date(sunday_start).weekday(),
+            chrono::Weekday::Sun
+        );
+    }
+
+    #[test]
+    fn rolling_periods_include_today() {
+        let (start, end) = resolve_period("last_7_days", "monday").unwrap();
+        let today = timestamp_to_local_date(timestamp_now());
+
+        assert_eq!(timestamp_to_local_date(end), today);
+        assert_eq!(
+            timestamp_to_local_date(start),
+            today.checked_sub_days(Days::new(6)).unwrap()
+        );
+    }
+
+    #[test]
+    fn last_month_ends_before_this_month_starts() {
+        let (_, last_end) = resolve_period("last_month", "monday").unwrap();
+        let (this_start, _) = resolve_period("this_month", "monday").unwrap();
+
+        assert_eq!(last_end + 1, this_start);
+    }
+
+    #[test]
+    fn unknown_period_is_rejected() {
+        assert!(resolve_period("next_month", "monday").is_err());
+    }
+
+    #[test]
+    fn breakdown_requires_a_currency_when_records_mix_them() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_sqlite(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO accounts (id, name, type, currency, initial_balance, is_active, created_at)
+                VALUES ('cop', 'Pesos', 'cash', 'COP', 0, 1, 0);
+             INSERT INTO records (id, type, timestamp, amount, account_id, file_path, currency)
+                VALUES ('a', 'expense', 10, 5000, 'cop', '', NULL),
+                       ('b', 'expense', 20, 30, 'cop', '', 'usd');",
+        )
+        .unwrap();
+
+        assert!(get_category_breakdown(&conn, "expense", 0, 100, None, None, None).is_err());
+
+        let usd =
+            get_category_breakdown(&conn, "expense", 0, 100, None, Some("USD"), None).unwrap();
+        assert_eq!(usd.total, 30.0);
+        assert_eq!(usd.count, 1);
+
+        let cop = get_category_breakdown(&conn, "expense", 0, 15, None, None, None).unwrap();
+        assert_eq!(cop.currency.as_deref(), Some("COP"));
+        assert_eq!(cop.total, 5000.0);
+    }
+}
@@ -1,3 +1,19 @@
+export type CreditCardSettings = {
+  statement_closing_day: number;
+  payment_due_day: number;
+  minimum_payment_percent?: number | null;
+};
+
+export type LoanSettings = {
+  principal: number;
+  annual_interest_rate: number;
+  term_months: number;
+  start_date: number;
+  payment_day: number;
+  amortization?: "french" | "german" | null;
+  interest_category_id?: string | null;
+};
+
 export type AccountInfoDto = {
   id: string;
   name: string;
@@ -6,4 +22,11 @@ export type AccountInfoDto = {
   currency: string | null | undefined;
   initial_balance: number | null | undefined;
   credit_limit: number | null | undefined;
+  is_active?: boolean | null;
+  credit_card?: CreditCardSettings | null;
+  account_class?: "asset" | "liability" | null;
+  account_class_override?: "asset" | "liability" | "auto" | null;
+  loan?: LoanSettings | null;
+  market_value?: number | null;
+  opening_date?: number | null;
 };
@@ -0,0 +1,53 @@
+export type BudgetItem = {
+  id: string;
+  month: string;
+  category_id: string;
+  amount: number;
+  notes?: string | null;
+  created_at: number;
+  updated_at: number;
+};
+
+export type BudgetStatusDto = {
+  budget_id: string;
+  month: string;
+  category_id: string;
+  category_name: string;
+  color: string;
+  is_group: boolean;
+  notes?: string | null;
+  budgeted: number;
+  spent: number;
+  remaining: number;
+  percent_used: number;
+};
+
+export type EnvelopeBalanceDto = {
+  category_id: string;
+  category_name: string;
+  color: string;
+  carried_over: number;
+  assigned: number;
+  moved: number;
+  spent: number;
+  available: number;
+};
+
+export type EnvelopeMonthDto = {
+  month: string;
+  income: number;
+  assigned: number;
+  unassigned_spent: number;
+  to_be_assigned: number;
+  envelopes: EnvelopeBalanceDto[];
+};
+
+export type BudgetAlertDto = {
+  month: string;
+  category_id: string;
+  category_name: string;
+  threshold: number;
+  budgeted: number;
+  spent: number;
+  percent_used: number;
+};
@@ -5,4 +5,27 @@ export type CategoryDto = {
   icon: string;
   color: string;
   is_active: boolean;
+  created_by_user?: boolean;
+  parent_id?: string | null;
+};
+
+export type CategoryTreeDto = CategoryDto & {
+  children: CategoryTreeDto[];
+};
+
+export type SaveCategoryDto = {
+  name: string;
+  type: "expense" | "income";
+  icon: string;
+  color: string;
+  parent_id?: string | null;
+};
+
+export type CategoryMergeReportDto = {
+  records_moved: number;
+  rules_updated: number;
+  budgets_updated: number;
+  loans_updated: number;
+  subcategories_moved: number;
+  merged_removed: boolean;
 };
@@ -0,0 +1,41 @@
+export type CategoryBreakdownPeriod =
+  | "this_week"
+  | "this_month"
+  | "last_month"
+  | "this_year"
+  | "last_year"
+  | "last_7_days"
+  | "last_30_days"
+  | "last_90_days"
+  | "last_365_days";
+
+export type CategoryBreakdownQueryDto = {
+  type: "expense" | "income";
+  period?: CategoryBreakdownPeriod | null;
+  start?: number | null;
+  end?: number | null;
+  account_ids?: string[] | null;
+  currency?: string | null;
+  parent_id?: string | null;
+};
+
+export type CategoryBreakdownItemDto = {
+  category_id: string | null;
+  category_name: string;
+  color: string;
+  is_active: boolean;
+  amount: number;
+  count: number;
+  percentage: number;
+  has_children: boolean;
+};
+
+export type CategoryBreakdownDto = {
+  type: "expense" | "income";
+  start: number;
+  end: number;
+  currency: string | null;
+  total: number;
+  count: number;
+  categories: CategoryBreakdownItemDto[];
+};
@@ -6,4 +6,7 @@ export type CreateRecordDto = {
   category_id?: string;
   description?: string;
   timestamp: number;
+  apply_rules?: boolean;
+  installments?: number;
+  monthly_interest_rate?: number;
 };
@@ -0,0 +1,31 @@
+export type GoalItem = {
+  id: string;
+  name: string;
+  target_amount: number;
+  target_date: number;
+  start_date: number;
+  account_id?: string | null;
+  tag?: string | null;
+  color?: string | null;
+  notes?: string | null;
+  is_active: boolean;
+  created_at: number;
+};
+
+export type GoalProgressDto = {
+  id: string;
+  name: string;
+  target_amount: number;
+  target_date: number;
+  start_date: number;
+  account_id?: string | null;
+  tag?: string | null;
+  color?: string | null;
+  is_active: boolean;
+  contributed: number;
+  remaining: number;
+  progress_percent: number;
+  months_left: number;
+  required_monthly: number;
+  is_completed: boolean;
+};